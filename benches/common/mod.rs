@@ -0,0 +1,24 @@
+use std::time::{Duration, Instant};
+
+pub fn bench(name: &str, mut f: impl FnMut()) {
+    // warm up and pick an iteration count that runs for about a second
+    let mut iterations = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            f();
+        }
+        if start.elapsed() > Duration::from_millis(100) {
+            break;
+        }
+        iterations *= 2;
+    }
+    iterations *= 10;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per = start.elapsed() / iterations;
+    println!("{name:<24} {per:>12.2?}/iter ({iterations} iterations)");
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use std::{fmt::Write, hint::black_box};
+
+use common::bench;
+use scavenger::svg::{self, ExtractOptions};
+use scavenger::Float;
+
+// a map-like document: many groups of transformed paths with arcs and curves
+fn document(paths: usize) -> String {
+    let mut out = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg">"#);
+    for i in 0..paths {
+        let (x, y) = ((i % 100) as Float * 12.0, (i / 100) as Float * 12.0);
+        let _ = write!(
+            out,
+            r#"<g transform="translate({x} {y}) rotate({})"><path d="M0 0 C4 -6 8 -6 10 0 S14 8 10 10 A5 5 0 0 1 0 10 Q-4 5 0 0 Z"/></g>"#,
+            i % 360
+        );
+    }
+    out.push_str("</svg>");
+    out
+}
+
+fn main() {
+    let source = document(20_000);
+
+    for threads in [1, 0] {
+        let options = ExtractOptions {
+            threads,
+            ..ExtractOptions::default()
+        };
+        let paths = svg::extract(&source, &options).expect("valid svg").len();
+
+        bench(&format!("threads {threads} ({paths} paths)"), || {
+            black_box(svg::extract(black_box(&source), &options).expect("valid svg"));
+        });
+    }
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use std::hint::black_box;
+
+use common::bench;
+use scavenger::{path::Path, FlattenMethod, Parser};
+
+// a mix of tight and gentle curves, roughly what icon sets look like
+const D: &str = "M10 80 C40 10 65 10 95 80 S150 150 180 80 Q200 40 220 80 T260 80 \
+                 A30 50 0 0 1 320 80 C330 60 340 60 350 80 L350 120 C300 200 100 200 10 120 Z \
+                 M60 90 q10 -20 20 0 t20 0 t20 0 c5 10 15 10 20 0 z";
+
+fn main() {
+    let path = Path::new(Parser::new(D).parse().expect("valid path"));
+
+    for tolerance in [1.0, 0.1, 0.01] {
+        for method in [FlattenMethod::Uniform, FlattenMethod::Adaptive] {
+            let points: usize = path
+                .flatten_with(tolerance, method)
+                .iter()
+                .map(|p| p.points.len())
+                .sum();
+
+            bench(&format!("{method:?} {tolerance} ({points} pts)"), || {
+                black_box(black_box(&path).flatten_with(tolerance, method));
+            });
+        }
+    }
+}
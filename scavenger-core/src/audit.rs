@@ -0,0 +1,60 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::Command;
+
+// a command that came out of an operation with a NaN or infinite number in it, from one that had
+// none. kept for the first one only, see `first`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NonFinite {
+    pub op: &'static str,
+    pub source: Command,
+    pub result: Command,
+}
+
+static FIRST: Mutex<Option<NonFinite>> = Mutex::new(None);
+
+fn finite(cmd: &Command) -> bool {
+    let extra = match *cmd {
+        Command::EllipticalArc {
+            rx,
+            ry,
+            x_axis_rotation,
+            ..
+        } => [rx, ry, x_axis_rotation],
+        _ => [0.0; 3],
+    };
+    cmd.points().all(|(x, y)| x.is_finite() && y.is_finite()) && extra.iter().all(|v| v.is_finite())
+}
+
+// called after the coordinate arithmetic on commands: the first time `op` turns a finite command
+// into a non-finite one it is kept and logged as a warning, later ones only at debug level so a
+// bad batch does not flood the log. commands that were already broken going in are skipped, the
+// operation that broke them has been reported. public so the operations in `scavenger` report
+// here too
+pub fn check(op: &'static str, source: &Command, result: &Command) {
+    if finite(result) || !finite(source) {
+        return;
+    }
+
+    let mut first = FIRST.lock().unwrap_or_else(PoisonError::into_inner);
+    if first.is_none() {
+        *first = Some(NonFinite {
+            op,
+            source: *source,
+            result: *result,
+        });
+        tracing::warn!(op, ?source, ?result, "first non-finite coordinate");
+    } else {
+        tracing::debug!(op, ?source, ?result, "non-finite coordinate");
+    }
+}
+
+// the first command any operation turned non-finite since the start or the last `reset`
+pub fn first() -> Option<NonFinite> {
+    *FIRST.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// forget the recorded command, so the next pipeline run reports its own
+pub fn reset() {
+    *FIRST.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
@@ -0,0 +1,208 @@
+use crate::{Command, Float, ParseOptions, Parser};
+
+// habits of the tools path data comes from that a parser has to get right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quirk {
+    // arc flags run into each other and into the next number, `a5 5 0 0110 10`, as minifiers
+    // write them
+    DecimalPackedFlags,
+    // numbers without separators, the next one starting at its `.` or `-`: `c-.8-.3-1.7-.5`
+    PackedNumbers,
+    // tiny values in exponent notation, `9.53674e-07`. the parser does not read exponents yet, so
+    // these cases fail whatever the options
+    Exponents,
+    // more arguments than the command takes, repeating it. after `M`/`m` they draw lines
+    ImplicitCommands,
+    // relative commands right after `z`, which start from where the subpath began
+    RelativeAfterClose,
+    // newlines, tabs and commas anywhere, or no space at all after command letters
+    Whitespace,
+    // `3,5` for 3.5, from writers formatting numbers in their locale
+    DecimalCommas,
+}
+
+pub struct Case {
+    pub quirk: Quirk,
+    // the tool the sample is written in the style of
+    pub exporter: &'static str,
+    pub d: &'static str,
+    // the same drawing written plainly: absolute, one command letter each, spaces only
+    pub expected: &'static str,
+    // only reads right with `ParseOptions::decimal_comma`
+    pub decimal_comma: bool,
+}
+
+static CASES: &[Case] = &[
+    Case {
+        quirk: Quirk::DecimalPackedFlags,
+        exporter: "svgo",
+        d: "M10 80a25 25 0 0145 0",
+        expected: "M10 80 A25 25 0 0 1 55 80",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::DecimalPackedFlags,
+        exporter: "svgo",
+        d: "M0 0a5 5 0 1010 0A5 5 0 11.5 0",
+        expected: "M0 0 A5 5 0 1 0 10 0 A5 5 0 1 1 0.5 0",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::DecimalPackedFlags,
+        exporter: "W3C",
+        d: "M300,200 h-150 a150,150 0 1,0 150,-150 z",
+        expected: "M300 200 L150 200 A150 150 0 1 0 300 50 Z",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::PackedNumbers,
+        exporter: "Illustrator",
+        d: "M12.5,3.5c-.8-.3-1.7-.5-2.6-.5",
+        expected: "M12.5 3.5 C11.7 3.2 10.8 3 9.9 3",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::PackedNumbers,
+        exporter: "svgo",
+        d: "M.5.5l1.5.5.5-1.5",
+        expected: "M0.5 0.5 L2 1 L2.5 -0.5",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::Exponents,
+        exporter: "Figma",
+        d: "M9.53674e-07 12L24 -1.90735e-06V24H0Z",
+        expected: "M0.000000953674 12 L24 -0.00000190735 L24 24 L0 24 Z",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::Exponents,
+        exporter: "Figma",
+        d: "M1E2 0L1e+2 1e1",
+        expected: "M100 0 L100 10",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::ImplicitCommands,
+        exporter: "Inkscape",
+        d: "m 10,10 20,0 0,20 -20,0 z",
+        expected: "M10 10 L30 10 L30 30 L10 30 Z",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::ImplicitCommands,
+        exporter: "W3C",
+        d: "M100,200 C100,100 250,100 250,200 S400,300 400,200",
+        expected: "M100 200 C100 100 250 100 250 200 S400 300 400 200",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::ImplicitCommands,
+        exporter: "Inkscape",
+        d: "M 0,0 L 10,0 10,10 0,10",
+        expected: "M0 0 L10 0 L10 10 L0 10",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::RelativeAfterClose,
+        exporter: "Inkscape",
+        d: "m 10,10 h 10 v 10 z m 5,5 h 2 v 2 z",
+        expected: "M10 10 L20 10 L20 20 Z M15 15 L17 15 L17 17 Z",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::RelativeAfterClose,
+        exporter: "svgo",
+        d: "M4 4h4v4zl2 2",
+        expected: "M4 4 L8 4 L8 8 Z L6 6",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::Whitespace,
+        exporter: "Illustrator",
+        d: "M10,10\n\tL20,10\r\n\tL20,20\n\tz",
+        expected: "M10 10 L20 10 L20 20 Z",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::Whitespace,
+        exporter: "W3C",
+        d: "  M 1 , 2 L3,4 ,5 6  ",
+        expected: "M1 2 L3 4 L5 6",
+        decimal_comma: false,
+    },
+    Case {
+        quirk: Quirk::DecimalCommas,
+        exporter: "locale formatted writer",
+        d: "M 10,50 20,25 L 30,75 40,05",
+        expected: "M10.5 20.25 L30.75 40.05",
+        decimal_comma: true,
+    },
+];
+
+// every case in the corpus
+pub fn all() -> &'static [Case] {
+    CASES
+}
+
+pub fn cases(quirk: Quirk) -> impl Iterator<Item = &'static Case> {
+    CASES.iter().filter(move |case| case.quirk == quirk)
+}
+
+fn close(a: Float, b: Float) -> bool {
+    (a - b).abs() <= 1e-4 * a.abs().max(b.abs()).max(1.0)
+}
+
+impl Case {
+    // the default options with whatever the case needs turned on
+    pub fn options(&self) -> ParseOptions {
+        ParseOptions {
+            decimal_comma: self.decimal_comma,
+            ..ParseOptions::default()
+        }
+    }
+
+    // parses `d` with `options` and compares it with `expected` read with the same options, so
+    // settings such as `preserve_arcs` apply to both. the error says where they part
+    pub fn check(&self, options: ParseOptions) -> Result<(), String> {
+        let got = Parser::with_options(self.d, options)
+            .parse()
+            .map_err(|e| format!("{:?} ({}): {e}", self.quirk, self.exporter))?;
+        let want: Vec<Command> = Parser::with_options(
+            self.expected,
+            ParseOptions {
+                decimal_comma: false,
+                ..options
+            },
+        )
+        .parse()
+        .map_err(|e| format!("expected of {:?} does not parse: {e}", self.quirk))?;
+
+        if got.len() != want.len() {
+            return Err(format!(
+                "{:?} ({}): {} commands, expected {}",
+                self.quirk,
+                self.exporter,
+                got.len(),
+                want.len()
+            ));
+        }
+
+        // the points within rounding, everything else (which command, arc radii and flags) exactly
+        let bare = |cmd: &Command| cmd.map_points(|_| (0.0, 0.0));
+        for (i, (a, b)) in got.iter().zip(&want).enumerate() {
+            let same = bare(a) == bare(b)
+                && a.points()
+                    .zip(b.points())
+                    .all(|(p, q)| close(p.0, q.0) && close(p.1, q.1));
+            if !same {
+                return Err(format!(
+                    "{:?} ({}): command {i} is {a:?}, expected {b:?}",
+                    self.quirk, self.exporter
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
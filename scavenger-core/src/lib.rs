@@ -0,0 +1,1419 @@
+pub mod arc;
+#[cfg(feature = "audit")]
+pub mod audit;
+#[cfg(feature = "corpus")]
+pub mod corpus;
+mod locale;
+mod trace;
+pub mod transform;
+
+// the number every coordinate is kept in. f32 unless the `f64` feature asks for double precision
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+#[cfg(not(feature = "f64"))]
+pub(crate) use std::f32::consts;
+#[cfg(feature = "f64")]
+pub(crate) use std::f64::consts;
+
+pub use transform::Transform;
+
+use arc::{calculate_ellipse_parameters, push_eliptical_cmds, transform_ellipse};
+use locale::looks_decimal_comma;
+use logos::{Logos, SpannedIter};
+use std::{fmt, iter::Peekable, ops::Range};
+
+pub type Point = (Float, Float);
+
+// an `A` command as written, resolved to absolute points. the parser flattens arcs into
+// quadratics, `Path::arc_spans` maps those back to the arc they came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EllipticalArc {
+    pub from: Point,
+    pub rx: Float,
+    pub ry: Float,
+    // degrees, as in the path data
+    pub rotation: Float,
+    pub large_arc: bool,
+    pub sweep: bool,
+    pub to: Point,
+}
+
+// the commands an arc was flattened into, and the arc
+pub type ArcSpan = (Range<usize>, EllipticalArc);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cmd {
+    M,
+    L,
+    H,
+    V,
+    C,
+    S,
+    Q,
+    T,
+    A,
+    Z,
+}
+
+impl Cmd {
+    #[inline]
+    fn map(c: char) -> Option<(Self, bool)> {
+        return Some(match c {
+            'M' => (Cmd::M, false),
+            'm' => (Cmd::M, true),
+            'L' => (Cmd::L, false),
+            'l' => (Cmd::L, true),
+            'H' => (Cmd::H, false),
+            'h' => (Cmd::H, true),
+            'V' => (Cmd::V, false),
+            'v' => (Cmd::V, true),
+            'C' => (Cmd::C, false),
+            'c' => (Cmd::C, true),
+            'S' => (Cmd::S, false),
+            's' => (Cmd::S, true),
+            'Q' => (Cmd::Q, false),
+            'q' => (Cmd::Q, true),
+            'T' => (Cmd::T, false),
+            't' => (Cmd::T, true),
+            'A' => (Cmd::A, false),
+            'a' => (Cmd::A, true),
+            'Z' => (Cmd::Z, false),
+            'z' => (Cmd::Z, true),
+            _ => return None,
+        });
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip r"[ ,\t\r\n]+")]
+enum Token {
+    // any single character
+    #[regex(r"[a-zA-Z]", |lex| Cmd::map(lex.slice().chars().next().expect("char")))]
+    Command((Cmd, bool)),
+
+    // any floating point number
+    #[regex(r"-?(?:0|[1-9]\d*)?(?:\.\d+)?", |lex| lex.slice().parse::<Float>().unwrap_or(0.0))]
+    Number(Float),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    // M x y
+    MoveTo {
+        x: Float,
+        y: Float,
+    },
+    // L x y
+    LineTo {
+        x: Float,
+        y: Float,
+    },
+
+    // C x1 y1 x2 y2 x y
+    CurveTo {
+        x1: Float,
+        y1: Float,
+        x2: Float,
+        y2: Float,
+        x: Float,
+        y: Float,
+    },
+    // Z
+    ClosePath,
+    // S x2 y2 x y
+    SmoothCurveTo {
+        cx: Float,
+        cy: Float,
+
+        x2: Float,
+        y2: Float,
+        x: Float,
+        y: Float,
+    },
+    // Q x1 y1 x y
+    QuadraticBezierCurveTo {
+        x1: Float,
+        y1: Float,
+        x: Float,
+        y: Float,
+    },
+    // T x y
+    SmoothQuadraticBezierCurveTo {
+        cx: Float,
+        cy: Float,
+
+        x: Float,
+        y: Float,
+    },
+    // A rx ry x-axis-rotation large-arc-flag sweep-flag x y, only when the parser was asked to
+    // keep arcs (`Parser::preserve_arcs`)
+    EllipticalArc {
+        rx: Float,
+        ry: Float,
+        // degrees
+        x_axis_rotation: Float,
+        large_arc: bool,
+        sweep: bool,
+        x: Float,
+        y: Float,
+    },
+}
+
+impl Command {
+    // every coordinate pair the command carries, control points first
+    pub fn points(&self) -> impl Iterator<Item = (Float, Float)> {
+        let o = (0.0, 0.0);
+        let (points, n) = match *self {
+            Command::MoveTo { x, y } | Command::LineTo { x, y } => ([(x, y), o, o], 1),
+            Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => ([(x1, y1), (x2, y2), (x, y)], 3),
+            Command::SmoothCurveTo {
+                cx,
+                cy,
+                x2,
+                y2,
+                x,
+                y,
+            } => ([(cx, cy), (x2, y2), (x, y)], 3),
+            Command::QuadraticBezierCurveTo { x1, y1, x, y } => ([(x1, y1), (x, y), o], 2),
+            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => ([(cx, cy), (x, y), o], 2),
+            // the radii are lengths, not points
+            Command::EllipticalArc { x, y, .. } => ([(x, y), o, o], 1),
+            Command::ClosePath => ([o, o, o], 0),
+        };
+
+        points.into_iter().take(n)
+    }
+
+    pub fn translate(self, tx: Float, ty: Float) -> Command {
+        let result = match self {
+            Command::MoveTo { x, y } => Command::MoveTo {
+                x: x + tx,
+                y: y + ty,
+            },
+            Command::LineTo { x, y } => Command::LineTo {
+                x: x + tx,
+                y: y + ty,
+            },
+            Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => Command::CurveTo {
+                x1: x1 + tx,
+                y1: y1 + ty,
+                x2: x2 + tx,
+                y2: y2 + ty,
+                x: x + tx,
+                y: y + ty,
+            },
+            Command::ClosePath => Command::ClosePath,
+            Command::SmoothCurveTo {
+                cx,
+                cy,
+                x2,
+                y2,
+                x,
+                y,
+            } => Command::SmoothCurveTo {
+                cx: cx + tx,
+                cy: cy + ty,
+                x2: x2 + tx,
+                y2: y2 + ty,
+                x: x + tx,
+                y: y + ty,
+            },
+            Command::QuadraticBezierCurveTo { x1, y1, x, y } => Command::QuadraticBezierCurveTo {
+                x1: x1 + tx,
+                y1: y1 + ty,
+                x: x + tx,
+                y: y + ty,
+            },
+            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
+                Command::SmoothQuadraticBezierCurveTo {
+                    cx: cx + tx,
+                    cy: cy + ty,
+                    x: x + tx,
+                    y: y + ty,
+                }
+            }
+            arc @ Command::EllipticalArc { x, y, .. } => arc.with_end(x + tx, y + ty),
+        };
+        audit!("translate", self, result);
+        result
+    }
+
+    pub fn scale(self, sx: Float, sy: Float) -> Command {
+        let result = match self {
+            Command::MoveTo { x, y } => Command::MoveTo {
+                x: x * sx,
+                y: y * sy,
+            },
+            Command::LineTo { x, y } => Command::LineTo {
+                x: x * sx,
+                y: y * sy,
+            },
+            Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => Command::CurveTo {
+                x1: x1 * sx,
+                y1: y1 * sy,
+                x2: x2 * sx,
+                y2: y2 * sy,
+                x: x * sx,
+                y: y * sy,
+            },
+            Command::ClosePath => Command::ClosePath,
+            Command::SmoothCurveTo {
+                cx,
+                cy,
+                x2,
+                y2,
+                x,
+                y,
+            } => Command::SmoothCurveTo {
+                cx: cx * sx,
+                cy: cy * sy,
+                x2: x2 * sx,
+                y2: y2 * sy,
+                x: x * sx,
+                y: y * sy,
+            },
+            Command::QuadraticBezierCurveTo { x1, y1, x, y } => Command::QuadraticBezierCurveTo {
+                x1: x1 * sx,
+                y1: y1 * sy,
+                x: x * sx,
+                y: y * sy,
+            },
+            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
+                Command::SmoothQuadraticBezierCurveTo {
+                    cx: cx * sx,
+                    cy: cy * sy,
+                    x: x * sx,
+                    y: y * sy,
+                }
+            }
+            Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let (rx, ry, x_axis_rotation) =
+                    transform_ellipse(sx, 0.0, 0.0, sy, rx, ry, x_axis_rotation);
+                Command::EllipticalArc {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    // a mirror turns the arc the other way round
+                    sweep: sweep != (sx * sy < 0.0),
+                    x: x * sx,
+                    y: y * sy,
+                }
+            }
+        };
+        audit!("scale", self, result);
+        result
+    }
+
+    fn with_end(self, x: Float, y: Float) -> Command {
+        match self {
+            Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                ..
+            } => Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            },
+            cmd => cmd,
+        }
+    }
+
+    // every point moved by `f`, in the order `points` yields them
+    pub fn map_points(self, mut f: impl FnMut(Point) -> Point) -> Command {
+        match self {
+            Command::MoveTo { x, y } => {
+                let (x, y) = f((x, y));
+                Command::MoveTo { x, y }
+            }
+            Command::LineTo { x, y } => {
+                let (x, y) = f((x, y));
+                Command::LineTo { x, y }
+            }
+            Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let (x1, y1) = f((x1, y1));
+                let (x2, y2) = f((x2, y2));
+                let (x, y) = f((x, y));
+                Command::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                }
+            }
+            Command::SmoothCurveTo {
+                cx,
+                cy,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let (cx, cy) = f((cx, cy));
+                let (x2, y2) = f((x2, y2));
+                let (x, y) = f((x, y));
+                Command::SmoothCurveTo {
+                    cx,
+                    cy,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                }
+            }
+            Command::QuadraticBezierCurveTo { x1, y1, x, y } => {
+                let (x1, y1) = f((x1, y1));
+                let (x, y) = f((x, y));
+                Command::QuadraticBezierCurveTo { x1, y1, x, y }
+            }
+            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
+                let (cx, cy) = f((cx, cy));
+                let (x, y) = f((x, y));
+                Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y }
+            }
+            // only the end point, the radii are left as they are
+            arc @ Command::EllipticalArc { x, y, .. } => {
+                let (x, y) = f((x, y));
+                arc.with_end(x, y)
+            }
+            Command::ClosePath => Command::ClosePath,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseOptions {
+    // quadratic segments per elliptical arc
+    pub bezier_steps: i32,
+    // stop once this many commands were produced, cutting back to the last whole subpath
+    pub max_output_commands: Option<usize>,
+    // accept `3,5` as 3.5 when the input looks like it came from a decimal comma locale
+    pub decimal_comma: bool,
+    // emit `A` as `Command::EllipticalArc` instead of flattening it, see `Path::flatten_arcs`
+    pub preserve_arcs: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            bezier_steps: 16,
+            max_output_commands: None,
+            decimal_comma: false,
+            preserve_arcs: false,
+        }
+    }
+}
+
+pub struct Parser<'src> {
+    source: &'src str,
+    lexer: Peekable<SpannedIter<'src, Token>>,
+
+    px: Float,
+    py: Float,
+
+    cx: Float,
+    cy: Float,
+
+    sx: Float,
+    sy: Float,
+
+    options: ParseOptions,
+
+    last_command: Option<Cmd>,
+    // the command whose arguments may go on repeating
+    repeat: Option<(Cmd, bool)>,
+
+    commands: Vec<Command>,
+    // the commands each flattened arc became
+    arcs: Vec<ArcSpan>,
+
+    // the bytes each command came from, when asked for
+    spans: Option<Vec<Range<usize>>>,
+    // where the letter of a command still waiting for its first arguments starts
+    letter_start: Option<usize>,
+    // end of the last token read
+    consumed: usize,
+
+    // join `digits,digits` into one number
+    merge_commas: bool,
+    merged: usize,
+    warnings: Vec<Warning>,
+
+    // where the parser is, for errors: the command letter being read, how many numbers it took
+    // so far and the token that did not fit
+    letter: Option<char>,
+    arguments: usize,
+    error_span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expected {
+    Command,
+    Number,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub expected: Expected,
+    // bytes of the token that was found instead, empty at the end of the input
+    pub span: Range<usize>,
+    // the command letter being read, as written
+    pub command: Option<char>,
+    // for a missing number, which of the command's arguments it is (0 for the first x of every
+    // repeat of `C`, 5 for its last y)
+    pub argument: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.expected, self.command) {
+            (Expected::Number, Some(c)) => write!(
+                f,
+                "expected argument {} of `{c}` at byte {}",
+                self.argument + 1,
+                self.span.start
+            ),
+            (Expected::Number, None) => write!(f, "expected a number at byte {}", self.span.start),
+            (Expected::Command, _) => write!(f, "expected a command at byte {}", self.span.start),
+        }
+    }
+}
+
+// things the parser recovered from instead of failing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Warning {
+    // the input was read as using decimal commas, `count` of them became decimal points
+    DecimalCommas { count: usize },
+    // commas between digits were found but nothing pointed to decimal commas, so they were kept
+    // as separators
+    AmbiguousCommas,
+}
+
+impl<'src> Parser<'src> {
+    pub fn new(path: &'src str) -> Parser<'src> {
+        let lexer = Token::lexer(path);
+
+        Parser {
+            source: path,
+            lexer: lexer.spanned().peekable(),
+
+            px: 0.0,
+            py: 0.0,
+
+            cx: 0.0,
+            cy: 0.0,
+
+            sx: 0.0,
+            sy: 0.0,
+
+            options: ParseOptions::default(),
+
+            last_command: None,
+            repeat: None,
+
+            commands: Vec::new(),
+            arcs: Vec::new(),
+
+            spans: None,
+            letter_start: None,
+            consumed: 0,
+
+            merge_commas: false,
+            merged: 0,
+            warnings: Vec::new(),
+
+            letter: None,
+            arguments: 0,
+            error_span: 0..0,
+        }
+    }
+
+    pub fn with_options(path: &'src str, options: ParseOptions) -> Parser<'src> {
+        Parser {
+            options,
+            ..Parser::new(path)
+        }
+    }
+
+    pub fn bezier_steps(mut self, bezier_steps: i32) -> Self {
+        self.options.bezier_steps = bezier_steps;
+        self
+    }
+
+    pub fn max_output_commands(mut self, max_output_commands: usize) -> Self {
+        self.options.max_output_commands = Some(max_output_commands);
+        self
+    }
+
+    pub fn decimal_comma(mut self, decimal_comma: bool) -> Self {
+        self.options.decimal_comma = decimal_comma;
+        self
+    }
+
+    pub fn preserve_arcs(mut self, preserve_arcs: bool) -> Self {
+        self.options.preserve_arcs = preserve_arcs;
+        self
+    }
+
+    pub fn parse(mut self) -> Result<Vec<Command>, ParseError> {
+        self.run().map_err(|e| self.error(e))?;
+
+        Ok(self.commands)
+    }
+
+    // the commands along with the arcs they came from, as the ranges of commands each flattened
+    // arc became. `Path` keeps them to map its quadratics back to the arcs
+    pub fn parse_with_arcs(mut self) -> Result<(Vec<Command>, Vec<ArcSpan>), ParseError> {
+        self.run().map_err(|e| self.error(e))?;
+
+        Ok((self.commands, self.arcs))
+    }
+
+    // the commands along with the bytes of the source each one came from. the commands an arc was
+    // flattened into share its span. `writer::patch` uses them to write back only what changed
+    pub fn parse_with_spans(mut self) -> Result<(Vec<Command>, Vec<Range<usize>>), ParseError> {
+        self.spans = Some(Vec::new());
+        self.run().map_err(|e| self.error(e))?;
+
+        Ok((self.commands, self.spans.unwrap_or_default()))
+    }
+
+    pub fn parse_with_warnings(mut self) -> Result<(Vec<Command>, Vec<Warning>), ParseError> {
+        self.run().map_err(|e| self.error(e))?;
+
+        Ok((self.commands, self.warnings))
+    }
+
+    // everything parsed before the first error, along with that error. useful for previews where
+    // showing most of a path beats showing nothing
+    pub fn parse_partial(mut self) -> (Vec<Command>, Option<ParseError>) {
+        let err = self.run().err().map(|e| self.error(e));
+
+        (self.commands, err)
+    }
+
+    fn error(&self, expected: Expected) -> ParseError {
+        let arity = match self.letter.and_then(Cmd::map) {
+            Some((Cmd::H | Cmd::V, _)) => 1,
+            Some((Cmd::C, _)) => 6,
+            Some((Cmd::S | Cmd::Q, _)) => 4,
+            Some((Cmd::A, _)) => 7,
+            _ => 2,
+        };
+
+        ParseError {
+            expected,
+            span: self.error_span.clone(),
+            command: self.letter,
+            argument: self.arguments % arity,
+        }
+    }
+
+    // read the source's locale before the first command
+    fn start(&mut self) {
+        if self.options.decimal_comma {
+            match looks_decimal_comma(self.source) {
+                Some(true) => self.merge_commas = true,
+                Some(false) => self.warnings.push(Warning::AmbiguousCommas),
+                None => {}
+            }
+        }
+    }
+
+    fn run(&mut self) -> Result<(), Expected> {
+        trace_span!("parse");
+
+        self.start();
+        while self.advance()? {
+            if let Some(max) = self.options.max_output_commands {
+                if self.commands.len() > max {
+                    truncate_commands(&mut self.commands, max);
+                    let len = self.commands.len();
+                    self.arcs.retain(|(range, _)| range.end <= len);
+                    if let Some(spans) = &mut self.spans {
+                        spans.truncate(len);
+                    }
+                    break;
+                }
+            }
+        }
+
+        if self.merge_commas {
+            self.warnings
+                .push(Warning::DecimalCommas { count: self.merged });
+        }
+
+        trace_count!(commands = self.commands.len(), "parsed");
+
+        Ok(())
+    }
+
+    // read one more set of arguments for the current command, or the next command letter, onto
+    // `commands`. `false` once there is nothing left
+    fn advance(&mut self) -> Result<bool, Expected> {
+        let first = self.commands.len();
+
+        if let Some((command, relative)) = self.repeat {
+            let start = match (self.letter_start, self.lexer.peek()) {
+                (Some(start), _) => start,
+                (None, Some((_, span))) => span.start,
+                (None, None) => self.source.len(),
+            };
+
+            let more = match command {
+                // coordinates after a moveto are linetos
+                Cmd::M | Cmd::L => self.l(relative)?,
+                Cmd::H => self.h(relative)?,
+                Cmd::V => self.v(relative)?,
+                Cmd::C => self.c(relative)?,
+                Cmd::S => self.s(relative)?,
+                Cmd::Q => self.q(relative)?,
+                Cmd::T => self.t(relative)?,
+                Cmd::A => self.a(relative)?,
+                Cmd::Z => false,
+            };
+            if more {
+                // a repeat follows the same command, which smooth curves reflect
+                self.last_command = Some(command);
+                self.letter_start = None;
+                self.record(start);
+                return Ok(true);
+            }
+
+            self.last_command = Some(command);
+            self.repeat = None;
+        }
+
+        match self.lexer.next() {
+            Some((Ok(Token::Command((command, relative))), span)) => {
+                self.letter = self.source[span.start..].chars().next();
+                self.arguments = 0;
+                self.letter_start = Some(span.start);
+                self.consumed = span.end;
+
+                match command {
+                    Cmd::M => self.m(relative)?,
+                    Cmd::Z => {
+                        self.px = self.sx;
+                        self.py = self.sy;
+
+                        self.commands.push(Command::ClosePath);
+                    }
+                    _ => {}
+                }
+
+                self.repeat = Some((command, relative));
+                self.record(span.start);
+                // a moveto's own pair is spoken for, so its repeats start at their first number
+                if self.commands.len() > first {
+                    self.letter_start = None;
+                }
+                Ok(true)
+            }
+            Some((Ok(Token::Number(_)), span)) => {
+                self.letter = None;
+                self.error_span = span;
+                Err(Expected::Command)
+            }
+            // something the lexer does not know, reported rather than taken for the end so a
+            // typo does not quietly cut the path short
+            Some((Err(_), span)) => {
+                self.letter = None;
+                self.error_span = span;
+                Err(Expected::Command)
+            }
+            None => Ok(false),
+        }
+    }
+
+    // give the commands pushed since the last call the span from `start` to the last token read
+    fn record(&mut self, start: usize) {
+        if let Some(spans) = &mut self.spans {
+            spans.resize(self.commands.len(), start..self.consumed);
+        }
+    }
+
+    #[inline]
+    fn peek<'a>(&'a mut self) -> Option<&'a Result<Token, ()>> {
+        self.lexer.peek().map(|(token, _)| token)
+    }
+
+    #[inline]
+    fn number(&mut self) -> Result<Float, Expected> {
+        let n = match self.lexer.next() {
+            Some((Ok(Token::Number(n)), span)) if self.merge_commas => self.join_fraction(n, span),
+            Some((Ok(Token::Number(n)), span)) => {
+                self.consumed = span.end;
+                n
+            }
+            Some((_, span)) => {
+                self.error_span = span;
+                return Err(Expected::Number);
+            }
+            None => {
+                self.error_span = self.source.len()..self.source.len();
+                return Err(Expected::Number);
+            }
+        };
+
+        self.arguments += 1;
+        Ok(n)
+    }
+
+    // `3,5` lexes as 3 and 5. when the comma is the only thing between them and what follows is a
+    // plain run of digits, read both as 3.5. the lexer splits runs like `05` into several numbers,
+    // so the run is taken from the source and every token inside it consumed
+    fn join_fraction(&mut self, n: Float, span: Range<usize>) -> Float {
+        self.consumed = span.end;
+        let whole = &self.source[span.clone()];
+        let rest = &self.source[span.end..];
+        if !rest.starts_with(',') || whole.contains('.') {
+            return n;
+        }
+
+        let digits = rest[1..].bytes().take_while(u8::is_ascii_digit).count();
+        let end = span.end + 1 + digits;
+        if digits == 0 || rest[1 + digits..].starts_with('.') {
+            return n;
+        }
+
+        let Ok(joined) = format!("{whole}.{}", &rest[1..1 + digits]).parse() else {
+            return n;
+        };
+
+        while let Some((_, next)) = self.lexer.peek() {
+            if next.end > end {
+                break;
+            }
+            self.lexer.next();
+        }
+
+        self.consumed = end;
+        self.merged += 1;
+        joined
+    }
+
+    // arc flags are a single `0` or `1` and need nothing between them and what follows, so
+    // exporters write `a5 5 0 0110 10` and the lexer reads `0110` as one number. the flag is taken
+    // from the first character and the lexer restarted right after it. anything not starting with
+    // `0` or `1` is read as a number, nonzero for set, as before
+    fn flag(&mut self) -> Result<bool, Expected> {
+        let span = match self.lexer.peek() {
+            Some((Ok(Token::Number(_)), span)) => span.clone(),
+            _ => return self.number().map(|n| n != 0.0),
+        };
+
+        let flag = match self.source.as_bytes()[span.start] {
+            b'0' => false,
+            b'1' => true,
+            _ => return self.number().map(|n| n != 0.0),
+        };
+
+        if span.len() > 1 {
+            let mut lexer = Token::lexer(self.source);
+            lexer.bump(span.start + 1);
+            self.lexer = lexer.spanned().peekable();
+        } else {
+            self.lexer.next();
+        }
+
+        self.consumed = span.start + 1;
+        self.arguments += 1;
+        Ok(flag)
+    }
+
+    #[inline]
+    fn delta(&self, relative: bool) -> (Float, Float) {
+        if relative {
+            (self.px, self.py)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    #[inline]
+    fn try_number(&mut self) -> Result<Float, Expected> {
+        match self.peek() {
+            Some(Ok(Token::Number(_))) => self.number(),
+            _ => Err(Expected::Number),
+        }
+    }
+
+    #[inline]
+    fn m(&mut self, relative: bool) -> Result<(), Expected> {
+        let x = self.number()?;
+        let y = self.number()?;
+
+        self.px = x + if relative { self.px } else { 0.0 };
+        self.py = y + if relative { self.py } else { 0.0 };
+
+        self.sx = self.px;
+        self.sy = self.py;
+
+        self.commands.push(Command::MoveTo {
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(())
+    }
+
+    #[inline]
+    fn l(&mut self, relative: bool) -> Result<bool, Expected> {
+        let Ok(x) = self.try_number() else {
+            return Ok(false);
+        };
+
+        let y = self.number()?;
+
+        self.px = x + if relative { self.px } else { 0.0 };
+        self.py = y + if relative { self.py } else { 0.0 };
+
+        self.commands.push(Command::LineTo {
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn h(&mut self, relative: bool) -> Result<bool, Expected> {
+        let Ok(x) = self.try_number() else {
+            return Ok(false);
+        };
+
+        self.px = x + if relative { self.px } else { 0.0 };
+
+        self.commands.push(Command::LineTo {
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn v(&mut self, relative: bool) -> Result<bool, Expected> {
+        let Ok(y) = self.try_number() else {
+            return Ok(false);
+        };
+
+        self.py = y + if relative { self.py } else { 0.0 };
+
+        self.commands.push(Command::LineTo {
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn c(&mut self, relative: bool) -> Result<bool, Expected> {
+        let (dx, dy) = self.delta(relative);
+        let Ok(x1) = self.try_number() else {
+            return Ok(false);
+        };
+
+        let y1 = self.number()?;
+        let x2 = self.number()?;
+        let y2 = self.number()?;
+        let x = self.number()?;
+        let y = self.number()?;
+
+        self.px = x + dx;
+        self.py = y + dy;
+
+        self.cx = x2 + dx;
+        self.cy = y2 + dy;
+
+        self.commands.push(Command::CurveTo {
+            x1: x1 + dx,
+            y1: y1 + dy,
+            x2: x2 + dx,
+            y2: y2 + dy,
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn s(&mut self, relative: bool) -> Result<bool, Expected> {
+        let (dx, dy) = self.delta(relative);
+        let Ok(x2) = self.try_number() else {
+            return Ok(false);
+        };
+
+        let y2 = self.number()?;
+        let x = self.number()?;
+        let y = self.number()?;
+
+        if let Some(Cmd::C | Cmd::S) = self.last_command {
+            self.cx = self.px + (self.px - self.cx);
+            self.cy = self.py + (self.py - self.cy);
+        } else {
+            self.cx = self.px;
+            self.cy = self.py;
+        }
+
+        self.px = x + dx;
+        self.py = y + dy;
+
+        self.commands.push(Command::SmoothCurveTo {
+            cx: self.cx,
+            cy: self.cy,
+
+            x2: x2 + dx,
+            y2: y2 + dy,
+            x: self.px,
+            y: self.py,
+        });
+
+        self.cx = x2 + dx;
+        self.cy = y2 + dy;
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn q(&mut self, relative: bool) -> Result<bool, Expected> {
+        let (dx, dy) = self.delta(relative);
+
+        let Ok(x1) = self.try_number() else {
+            return Ok(false);
+        };
+
+        let y1 = self.number()?;
+        let x = self.number()?;
+        let y = self.number()?;
+
+        self.px = x + dx;
+        self.py = y + dy;
+
+        self.cx = x1 + dx;
+        self.cy = y1 + dy;
+
+        self.commands.push(Command::QuadraticBezierCurveTo {
+            x1: x1 + dx,
+            y1: y1 + dy,
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn t(&mut self, relative: bool) -> Result<bool, Expected> {
+        let (dx, dy) = self.delta(relative);
+        let Ok(x) = self.try_number() else {
+            return Ok(false);
+        };
+
+        let y = self.number()?;
+
+        if let Some(Cmd::Q | Cmd::T) = self.last_command {
+            self.cx = self.px + (self.px - self.cx);
+            self.cy = self.py + (self.py - self.cy);
+        } else {
+            self.cx = self.px;
+            self.cy = self.py;
+        }
+
+        self.px = x + dx;
+        self.py = y + dy;
+
+        // the control point stays in cx, cy for a following T to reflect
+        self.commands.push(Command::SmoothQuadraticBezierCurveTo {
+            cx: self.cx,
+            cy: self.cy,
+
+            x: self.px,
+            y: self.py,
+        });
+
+        Ok(true)
+    }
+
+    #[inline]
+    fn a(&mut self, relative: bool) -> Result<bool, Expected> {
+        let (dx, dy) = self.delta(relative);
+
+        let Ok(rx) = self.try_number() else {
+            return Ok(false);
+        };
+
+        let ry = self.number()?;
+        let x_axis_rotation = self.number()?;
+        let large_arc_flag = self.flag()?;
+        let sweep_flag = self.flag()?;
+        let x = self.number()?;
+        let y = self.number()?;
+
+        let x2 = self.px;
+        let y2 = self.py;
+
+        self.px = dx + x;
+        self.py = dy + y;
+
+        if self.options.preserve_arcs {
+            self.commands.push(Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc: large_arc_flag,
+                sweep: sweep_flag,
+                x: self.px,
+                y: self.py,
+            });
+        } else if let Some((cx, cy, start_angle, delta_angle)) = calculate_ellipse_parameters(
+            x2,
+            y2,
+            self.px,
+            self.py,
+            rx,
+            ry,
+            x_axis_rotation,
+            large_arc_flag,
+            sweep_flag,
+        ) {
+            let first = self.commands.len();
+            push_eliptical_cmds(
+                &mut self.commands,
+                cx,
+                cy,
+                rx,
+                ry,
+                start_angle,
+                start_angle + delta_angle,
+                x_axis_rotation,
+                self.options.bezier_steps,
+            );
+
+            self.arcs.push((
+                first..self.commands.len(),
+                EllipticalArc {
+                    from: (x2, y2),
+                    rx,
+                    ry,
+                    rotation: x_axis_rotation,
+                    large_arc: large_arc_flag,
+                    sweep: sweep_flag,
+                    to: (self.px, self.py),
+                },
+            ));
+        }
+
+        self.cx = self.px;
+        self.cy = self.py;
+
+        Ok(true)
+    }
+}
+
+// the commands of a path one at a time, without collecting them. only the commands of one set of
+// arguments are held at once (an arc's quadratics). after an error the iterator ends, and
+// `max_output_commands` stops it without cutting back to a whole subpath, since what came before
+// was already handed out
+pub struct CommandIterator<'src> {
+    parser: Parser<'src>,
+    // how many of `parser.commands` were handed out
+    taken: usize,
+    produced: usize,
+    done: bool,
+}
+
+impl<'src> IntoIterator for Parser<'src> {
+    type Item = Result<Command, ParseError>;
+    type IntoIter = CommandIterator<'src>;
+
+    fn into_iter(mut self) -> CommandIterator<'src> {
+        self.start();
+        CommandIterator {
+            parser: self,
+            taken: 0,
+            produced: 0,
+            done: false,
+        }
+    }
+}
+
+impl CommandIterator<'_> {
+    pub fn warnings(&self) -> &[Warning] {
+        &self.parser.warnings
+    }
+}
+
+impl Iterator for CommandIterator<'_> {
+    type Item = Result<Command, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done || self.parser.options.max_output_commands == Some(self.produced) {
+                return None;
+            }
+
+            if let Some(&cmd) = self.parser.commands.get(self.taken) {
+                self.taken += 1;
+                self.produced += 1;
+                return Some(Ok(cmd));
+            }
+
+            self.parser.commands.clear();
+            self.parser.arcs.clear();
+            self.taken = 0;
+
+            match self.parser.advance() {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(self.parser.error(e)));
+                }
+            }
+        }
+    }
+}
+
+pub fn parse_path_str(path: &str) -> Result<Vec<Command>, ParseError> {
+    Parser::new(path).parse()
+}
+
+// keep as many whole subpaths as fit in `n` commands. when not even the first one fits it is cut
+// short instead, so there is always something left to draw
+pub fn truncate_commands(commands: &mut Vec<Command>, n: usize) {
+    if commands.len() <= n {
+        return;
+    }
+
+    // subpaths end where the next one moves away and right after a close
+    let mut cut = 0;
+    for (i, cmd) in commands[..=n].iter().enumerate() {
+        match cmd {
+            Command::MoveTo { .. } => cut = i,
+            Command::ClosePath if i < n => cut = i + 1,
+            _ => {}
+        }
+    }
+
+    commands.truncate(if cut == 0 { n } else { cut });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCES: &[&str] = &[
+        "M10 10 L 20 20 H 5 V 0 Z",
+        "m1 2 3 4 5 6 z l 1 1",
+        "M0 0 C 1 2 3 4 5 6 S 7 8 9 10 s 1 1 2 2 1 1 2 2",
+        "M0 0 Q 1 1 2 0 T 4 0 6 0 t 2 0",
+        "M10 80a25 25 0 0145 0 A 5 10 30 1 0 20 20",
+        "M-.5.5l.25-.25,1 2",
+        "",
+        // failing ones, parity covers the errors too
+        "M 10",
+        "M0 0 L 1 2 3",
+        "M0 0 Q1 1 x",
+        "L 1 1",
+        "M0 0 L1 2 # L3 4",
+        "M0 0 L1.5 1e-7 L-0.0001 3",
+    ];
+
+    fn streamed(source: &str) -> (Vec<Command>, Option<ParseError>) {
+        let mut commands = Vec::new();
+        for cmd in Parser::new(source) {
+            match cmd {
+                Ok(cmd) => commands.push(cmd),
+                Err(e) => return (commands, Some(e)),
+            }
+        }
+        (commands, None)
+    }
+
+    // every way of running the parser reads the same commands and fails with the same error
+    #[test]
+    fn entry_points_agree() {
+        for &source in SOURCES {
+            let parsed = Parser::new(source).parse();
+            let (partial, error) = Parser::new(source).parse_partial();
+            assert_eq!(parsed.clone().err(), error, "{source:?}");
+            if let Ok(commands) = &parsed {
+                assert_eq!(*commands, partial, "{source:?}");
+            }
+
+            assert_eq!(
+                streamed(source),
+                (partial.clone(), error.clone()),
+                "{source:?}"
+            );
+
+            let spanned = Parser::new(source).parse_with_spans();
+            let warned = Parser::new(source).parse_with_warnings();
+            let arcs = Parser::new(source).parse_with_arcs();
+            match &parsed {
+                Ok(commands) => {
+                    let (with_spans, spans) = spanned.unwrap();
+                    assert_eq!(with_spans, *commands, "{source:?}");
+                    assert_eq!(spans.len(), commands.len(), "{source:?}");
+                    assert!(spans.iter().all(|span| span.end <= source.len()));
+                    assert_eq!(warned.unwrap().0, *commands, "{source:?}");
+                    assert_eq!(arcs.unwrap().0, *commands, "{source:?}");
+                }
+                Err(e) => {
+                    assert_eq!(spanned.err().as_ref(), Some(e), "{source:?}");
+                    assert_eq!(warned.err().as_ref(), Some(e), "{source:?}");
+                    assert_eq!(arcs.err().as_ref(), Some(e), "{source:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_stops_at_the_command_limit() {
+        let source = SOURCES[2];
+        let all = Parser::new(source).parse().unwrap();
+        let limited: Vec<_> = Parser::new(source)
+            .max_output_commands(3)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(limited, all[..3]);
+    }
+
+    // a T reflects the control point of the curve before it, which after another T is the point
+    // that one reflected. repeated arguments are read as the command again and reflect the same way
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        let commands = parse_path_str("M0 0 Q1 1 2 0 T4 0 T6 0").unwrap();
+        assert_eq!(
+            commands[2..],
+            [
+                Command::SmoothQuadraticBezierCurveTo {
+                    cx: 3.0,
+                    cy: -1.0,
+                    x: 4.0,
+                    y: 0.0
+                },
+                Command::SmoothQuadraticBezierCurveTo {
+                    cx: 5.0,
+                    cy: 1.0,
+                    x: 6.0,
+                    y: 0.0
+                },
+            ]
+        );
+        assert_eq!(parse_path_str("M0 0 Q1 1 2 0 T4 0 6 0").unwrap(), commands);
+
+        let commands = parse_path_str("M0 0 C0 1 1 1 1 0 S2 -1 2 0 3 1 3 0").unwrap();
+        assert_eq!(
+            commands[2..],
+            [
+                Command::SmoothCurveTo {
+                    cx: 1.0,
+                    cy: -1.0,
+                    x2: 2.0,
+                    y2: -1.0,
+                    x: 2.0,
+                    y: 0.0
+                },
+                Command::SmoothCurveTo {
+                    cx: 2.0,
+                    cy: 1.0,
+                    x2: 3.0,
+                    y2: 1.0,
+                    x: 3.0,
+                    y: 0.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_missing_argument() {
+        let e = parse_path_str("M0 0 C 1 2 3 4 5").unwrap_err();
+        assert_eq!(e.expected, Expected::Number);
+        assert_eq!(e.command, Some('C'));
+        assert_eq!(e.argument, 5);
+        assert_eq!(e.span, 16..16);
+
+        // bytes the lexer does not know are errors too, not the end of the path
+        let source = "M0 0 L1 2 # L3 4";
+        let (commands, e) = Parser::new(source).parse_partial();
+        assert_eq!(commands.len(), 2);
+        let e = e.unwrap();
+        assert_eq!(e.expected, Expected::Command);
+        assert_eq!(e.span, 10..11);
+        assert_eq!(e.to_string(), "expected a command at byte 10");
+
+        let source = "M0 0 L1.5 1e-7 L-0.0001 3";
+        let e = parse_path_str(source).unwrap_err();
+        assert_eq!(e.span.start, source.find('e').unwrap());
+    }
+
+    #[cfg(feature = "corpus")]
+    #[test]
+    fn reads_the_corpus() {
+        for case in corpus::all() {
+            let result = case.check(case.options());
+            // exponents are not read yet, see `Quirk::Exponents`
+            if case.quirk == corpus::Quirk::Exponents {
+                assert!(result.is_err(), "{:?} ({})", case.quirk, case.exporter);
+            } else {
+                result.unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::Parser;
+
+// whether commas sitting between digits are decimal commas. `None` when there is nothing to
+// decide: no such commas, or the input already uses decimal points
+pub(crate) fn looks_decimal_comma(source: &str) -> Option<bool> {
+    if source.contains('.') {
+        return None;
+    }
+
+    let bytes = source.as_bytes();
+    let digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+
+    let mut joined = 0;
+    let mut padded = false;
+    for i in 1..bytes.len() {
+        if bytes[i] == b',' && digit(i - 1) && digit(i + 1) {
+            joined += 1;
+            // `,05` is a fraction, nobody pads a separate coordinate with a leading zero. `,50`
+            // could be either, `M10,50` is an ordinary pair
+            padded |= bytes[i + 1] == b'0' && digit(i + 2);
+        }
+    }
+
+    if joined == 0 {
+        return None;
+    }
+    if padded {
+        return Some(true);
+    }
+
+    // otherwise only trust it when reading commas as separators leaves a command short of numbers
+    // and reading them as decimal points does not
+    let separated = Parser::new(source).parse();
+    let mut decimal = Parser::new(source);
+    decimal.merge_commas = true;
+
+    Some(separated.is_err() && decimal.parse().is_ok())
+}
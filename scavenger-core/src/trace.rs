@@ -0,0 +1,30 @@
+// spans and counters for the `tracing` feature and checks for `audit`, these compile to nothing
+// without them. exported for `scavenger`, the features are those of the crate using them
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! trace_span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! trace_count {
+    ($($fields:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($fields)*);
+    };
+}
+
+// a command checked by the `audit` feature after `op` made it from `source`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! audit {
+    ($op:literal, $source:expr, $result:expr) => {
+        #[cfg(feature = "audit")]
+        $crate::audit::check($op, &$source, &$result);
+    };
+}
@@ -0,0 +1,184 @@
+use crate::{arc::transform_ellipse, audit, Command, Float, Point};
+
+// the affine map (x, y) -> (a x + c y + e, b x + d y + f), the svg `matrix(a b c d e f)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: Float,
+    pub b: Float,
+    pub c: Float,
+    pub d: Float,
+    pub e: Float,
+    pub f: Float,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform::matrix(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+    }
+
+    pub fn matrix(a: Float, b: Float, c: Float, d: Float, e: Float, f: Float) -> Transform {
+        Transform { a, b, c, d, e, f }
+    }
+
+    pub fn translate(x: Float, y: Float) -> Transform {
+        Transform::matrix(1.0, 0.0, 0.0, 1.0, x, y)
+    }
+
+    pub fn scale(x: Float, y: Float) -> Transform {
+        Transform::matrix(x, 0.0, 0.0, y, 0.0, 0.0)
+    }
+
+    // about the origin, in degrees as svg writes it. positive turns x towards y, clockwise on a
+    // y-down screen
+    pub fn rotate(degrees: Float) -> Transform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Transform::matrix(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    pub fn skew_x(degrees: Float) -> Transform {
+        Transform::matrix(1.0, 0.0, degrees.to_radians().tan(), 1.0, 0.0, 0.0)
+    }
+
+    pub fn skew_y(degrees: Float) -> Transform {
+        Transform::matrix(1.0, degrees.to_radians().tan(), 0.0, 1.0, 0.0, 0.0)
+    }
+
+    // both skews in one matrix, as css `skew(x, y)` has it. not the same as `skew_x` followed by
+    // `skew_y`, which also scales y
+    pub fn skew(x_degrees: Float, y_degrees: Float) -> Transform {
+        Transform::matrix(
+            1.0,
+            y_degrees.to_radians().tan(),
+            x_degrees.to_radians().tan(),
+            1.0,
+            0.0,
+            0.0,
+        )
+    }
+
+    // this transform followed by `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    // the transform undoing this one, `None` when it flattens the plane onto a line or point
+    pub fn invert(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Transform {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f),
+        })
+    }
+
+    pub fn apply(&self, (x, y): Point) -> Point {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+}
+
+impl Command {
+    // every point, control points included, mapped through `transform`. arcs get the radii and
+    // rotation of the mapped ellipse
+    pub fn transform(self, transform: &Transform) -> Command {
+        let Transform { a, b, c, d, .. } = *transform;
+        let result = match self.map_points(|p| transform.apply(p)) {
+            Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let (rx, ry, x_axis_rotation) =
+                    transform_ellipse(a, b, c, d, rx, ry, x_axis_rotation);
+                Command::EllipticalArc {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    // a mirror turns the arc the other way round
+                    sweep: sweep != (a * d - b * c < 0.0),
+                    x,
+                    y,
+                }
+            }
+            cmd => cmd,
+        };
+        audit!("transform", self, result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Transform, b: Transform) {
+        let (a, b) = (
+            [a.a, a.b, a.c, a.d, a.e, a.f],
+            [b.a, b.b, b.c, b.d, b.e, b.f],
+        );
+        assert!(
+            a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-4),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn invert_undoes_the_transform() {
+        let transforms = [
+            Transform::identity(),
+            Transform::translate(3.0, -7.0),
+            Transform::scale(2.0, -0.5),
+            Transform::rotate(30.0).then(&Transform::translate(10.0, 4.0)),
+            Transform::skew(20.0, -10.0).then(&Transform::scale(1.5, 3.0)),
+            Transform::matrix(1.0, 2.0, 3.0, 4.0, 5.0, 6.0),
+        ];
+
+        for transform in transforms {
+            let inverse = transform.invert().unwrap();
+            assert_close(transform.then(&inverse), Transform::identity());
+            assert_close(inverse.then(&transform), Transform::identity());
+
+            let (x, y) = inverse.apply(transform.apply((12.5, -3.0)));
+            assert!((x - 12.5).abs() < 1e-4 && (y + 3.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn invert_rejects_degenerate_transforms() {
+        assert_eq!(Transform::scale(0.0, 1.0).invert(), None);
+        assert_eq!(
+            Transform::matrix(1.0, 2.0, 2.0, 4.0, 0.0, 0.0).invert(),
+            None
+        );
+        assert_eq!(Transform::scale(Float::NAN, 1.0).invert(), None);
+        assert_eq!(Transform::scale(Float::INFINITY, 1.0).invert(), None);
+    }
+}
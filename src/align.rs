@@ -0,0 +1,267 @@
+use crate::{
+    features::spread,
+    flatten::{flatten_subpaths, FlattenMethod},
+    offset::closest_on_segment,
+    path::Path,
+    transform::Transform,
+    Float, Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlignOptions {
+    // points taken evenly along each path to match up
+    pub points: usize,
+    // whether the fit may mirror `a`, for icons drawn facing the other way
+    pub reflection: bool,
+    // most rounds of closest point matching `register` runs after the first fit
+    pub iterations: usize,
+}
+
+impl Default for AlignOptions {
+    fn default() -> Self {
+        Self {
+            points: 64,
+            reflection: false,
+            iterations: 32,
+        }
+    }
+}
+
+fn flatten(path: &Path) -> Vec<(Vec<Point>, bool)> {
+    let tolerance = (path.bb.0.max(path.bb.1) * 1e-3).max(1e-3);
+    flatten_subpaths(&path.commands, tolerance, FlattenMethod::Adaptive)
+}
+
+fn samples(path: &Path, n: usize) -> (Vec<Point>, bool) {
+    let polylines = flatten(path);
+    let closed = !polylines.is_empty() && polylines.iter().all(|(_, closed)| *closed);
+    let points = spread(&polylines, n).into_iter().map(|(p, _)| p).collect();
+    (points, closed)
+}
+
+fn centroid(points: &[Point]) -> Point {
+    let n = points.len() as Float;
+    let (x, y) = points
+        .iter()
+        .fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+    (x / n, y / n)
+}
+
+// the similarity taking the points `from` onto `to` pairwise with the least squared distance,
+// with what distance is left
+fn procrustes(from: &[Point], to: &[Point], reflection: bool) -> Option<(Transform, Float)> {
+    let (pc, qc) = (centroid(from), centroid(to));
+
+    let (mut sxx, mut sxy, mut syx, mut syy) = (0.0, 0.0, 0.0, 0.0);
+    let (mut pp, mut qq) = (0.0, 0.0);
+    for (p, q) in from.iter().zip(to) {
+        let (px, py) = (p.0 - pc.0, p.1 - pc.1);
+        let (qx, qy) = (q.0 - qc.0, q.1 - qc.1);
+        sxx += px * qx;
+        sxy += px * qy;
+        syx += py * qx;
+        syy += py * qy;
+        pp += px * px + py * py;
+        qq += qx * qx + qy * qy;
+    }
+    if pp <= 0.0 || !pp.is_finite() {
+        return None;
+    }
+
+    // the turn lining the points up best, and how well it does, straight and mirrored in x
+    let turned = (sxx + syy).hypot(sxy - syx);
+    let mirrored = (sxx - syy).hypot(sxy + syx);
+
+    let (a, b, c, d, r) = if reflection && mirrored > turned {
+        let angle = (sxy + syx).atan2(sxx - syy);
+        let (sin, cos) = angle.sin_cos();
+        (cos, sin, sin, -cos, mirrored)
+    } else {
+        let angle = (sxy - syx).atan2(sxx + syy);
+        let (sin, cos) = angle.sin_cos();
+        (cos, sin, -sin, cos, turned)
+    };
+
+    let s = r / pp;
+    let (a, b, c, d) = (a * s, b * s, c * s, d * s);
+    let transform = Transform {
+        a,
+        b,
+        c,
+        d,
+        e: qc.0 - (a * pc.0 + c * pc.1),
+        f: qc.1 - (b * pc.0 + d * pc.1),
+    };
+    Some((transform, (qq - r * r / pp).max(0.0)))
+}
+
+// `Transform::align` and `Transform::register`, kept here since `Transform` lives in
+// scavenger-core which knows nothing of `Path`
+pub trait Align: Sized {
+    // the translation, uniform scale and rotation that lay `a` over `b` as closely as they can,
+    // fitted to points spread evenly along both. `None` when `a` does not span any area or
+    // length to fit, or `b` draws nothing
+    fn align(a: &Path, b: &Path) -> Option<Self>;
+
+    // outlines closed all the way round are also tried from every starting point and drawn
+    // the other way, since copies of an icon rarely agree on where their outline starts. the
+    // starts are matched to the nearest sample, so more points fit those more closely
+    fn align_with(a: &Path, b: &Path, options: &AlignOptions) -> Option<Self>;
+
+    // `align` followed by iterative closest point: every point on `a` is paired with the closest
+    // point on the flattened outline of `b` and the fit solved again, until it stops improving.
+    // this lines up paths that are alike but not the same, where no pairing by length along them
+    // holds. also gives the root mean square distance left between the points of `a` and the
+    // outline of `b`
+    fn register(a: &Path, b: &Path) -> Option<(Self, Float)>;
+
+    fn register_with(a: &Path, b: &Path, options: &AlignOptions) -> Option<(Self, Float)>;
+}
+
+impl Align for Transform {
+    fn align(a: &Path, b: &Path) -> Option<Transform> {
+        Transform::align_with(a, b, &AlignOptions::default())
+    }
+
+    fn align_with(a: &Path, b: &Path, options: &AlignOptions) -> Option<Transform> {
+        let (from, closed_a) = samples(a, options.points);
+        let (to, closed_b) = samples(b, options.points);
+        if from.is_empty() || to.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(Transform, Float)> = None;
+        let mut attempt = |from: &[Point]| {
+            if let Some(fit) = procrustes(from, &to, options.reflection) {
+                if best.is_none_or(|(_, left)| fit.1 < left) {
+                    best = Some(fit);
+                }
+            }
+        };
+
+        if closed_a && closed_b {
+            let mut reversed = from.clone();
+            reversed.reverse();
+            for points in [from, reversed] {
+                for shift in 0..points.len() {
+                    let mut shifted = points.clone();
+                    shifted.rotate_left(shift);
+                    attempt(&shifted);
+                }
+            }
+        } else {
+            attempt(&from);
+        }
+
+        best.map(|(transform, _)| transform)
+    }
+
+    fn register(a: &Path, b: &Path) -> Option<(Transform, Float)> {
+        Transform::register_with(a, b, &AlignOptions::default())
+    }
+
+    fn register_with(a: &Path, b: &Path, options: &AlignOptions) -> Option<(Transform, Float)> {
+        let mut transform = Transform::align_with(a, b, options)?;
+        let (from, _) = samples(a, options.points);
+        // the edges of `b`, a lone point is an edge that goes nowhere
+        let edges: Vec<(Point, Point)> = flatten(b)
+            .into_iter()
+            .flat_map(|(points, _)| match points.len() {
+                1 => vec![(points[0], points[0])],
+                _ => points.windows(2).map(|w| (w[0], w[1])).collect(),
+            })
+            .collect();
+        if edges.is_empty() {
+            return None;
+        }
+
+        let nearest = |transform: &Transform| -> (Vec<Point>, Float) {
+            let mut total = 0.0;
+            let matched = from
+                .iter()
+                .map(|&p| {
+                    let p = transform.apply(p);
+                    let (q, d) = edges
+                        .iter()
+                        .map(|&(a, b)| {
+                            let q = closest_on_segment(p, a, b);
+                            (q, (q.0 - p.0).powi(2) + (q.1 - p.1).powi(2))
+                        })
+                        .min_by(|x, y| x.1.total_cmp(&y.1))
+                        .expect("edges");
+                    total += d;
+                    q
+                })
+                .collect();
+            (matched, (total / from.len() as Float).sqrt())
+        };
+
+        let (mut matched, mut error) = nearest(&transform);
+        for _ in 0..options.iterations {
+            let Some((next, _)) = procrustes(&from, &matched, options.reflection) else {
+                break;
+            };
+            let (next_matched, next_error) = nearest(&next);
+            // a step can land where no better pairing is left, keep the fit before it then
+            if next_error.is_nan() || next_error >= error {
+                break;
+            }
+            let settled = error - next_error <= error * 1e-4;
+            (transform, matched, error) = (next, next_matched, next_error);
+            if settled {
+                break;
+            }
+        }
+
+        Some((transform, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_path_str;
+
+    #[test]
+    fn register_recovers_a_transform() {
+        // nothing about the outline repeats, so only one fit lays it over itself
+        let a = Path::new(parse_path_str("M0 0 L12 0 C 14 4 9 6 10 9 L3 11 Q 1 6 -2 4 Z").unwrap());
+        let t = Transform::rotate(30.0).then(&Transform::translate(3.0, 4.0));
+        let mut b = a.fork();
+        b.transform(&t);
+
+        let (found, error) = Transform::register(&a, &b).unwrap();
+        for (x, y) in [
+            (found.a, t.a),
+            (found.b, t.b),
+            (found.c, t.c),
+            (found.d, t.d),
+            (found.e, t.e),
+            (found.f, t.f),
+        ] {
+            assert!((x - y).abs() < 1e-2, "{found:?} found, {t:?} applied");
+        }
+        assert!(error < 1e-2, "{error}");
+    }
+
+    #[test]
+    fn register_measures_to_the_outline() {
+        // the same square drawn from another start, so no sample of `a` falls on one of `b`
+        let square = Path::new(parse_path_str("M0 0 L10 0 L10 10 L0 10 Z").unwrap());
+        let shifted = Path::new(parse_path_str("M5 0 L10 0 L10 10 L0 10 L0 0 Z").unwrap());
+        let options = AlignOptions {
+            points: 7,
+            ..AlignOptions::default()
+        };
+        let (_, error) = Transform::register_with(&square, &shifted, &options).unwrap();
+        assert!(error < 1e-3, "{error}");
+
+        // an open line registered onto a longer one lies along it
+        let short = Path::new(parse_path_str("M0 0 L4 0").unwrap());
+        let long = Path::new(parse_path_str("M0 1 L0 9").unwrap());
+        let (found, error) = Transform::register(&short, &long).unwrap();
+        assert!(error < 1e-3, "{error}");
+        let (x, _) = found.apply((2.0, 0.0));
+        assert!(x.abs() < 1e-3, "{found:?}");
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{join::tangent, path::Path, segment::Segment, Command, Float, Point};
+
+// a point on the outline with the direction the path travels there
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointAt {
+    pub point: Point,
+    // unit length
+    pub tangent: Point,
+}
+
+impl PointAt {
+    // the unit normal pointing to the left of the direction of travel on screen
+    pub fn normal(&self) -> Point {
+        (self.tangent.1, -self.tangent.0)
+    }
+}
+
+fn at(seg: &Segment, t: Float) -> PointAt {
+    PointAt {
+        point: seg.eval(t),
+        tangent: tangent(seg, t).unwrap_or((1.0, 0.0)),
+    }
+}
+
+impl Path {
+    // the point `length` along the outline, measured like `Path::length` with the jumps between
+    // subpaths left out, as svg's `getPointAtLength`. `None` past either end
+    pub fn point_at_length(&self, length: Float) -> Option<PointAt> {
+        if length.is_nan() || length < 0.0 {
+            return None;
+        }
+
+        let mut start = (0.0, 0.0);
+        let mut pen = start;
+        let mut walked = 0.0;
+        let mut last = None;
+        for cmd in self.commands.iter() {
+            if let Command::MoveTo { x, y } = *cmd {
+                (start, pen) = ((x, y), (x, y));
+                continue;
+            }
+
+            let closing = matches!(cmd, Command::ClosePath).then_some(Segment::Line(pen, start));
+            for seg in Segment::pieces(pen, cmd).chain(closing) {
+                let len = seg.length();
+                if len <= 0.0 {
+                    continue;
+                }
+                if walked + len >= length {
+                    return Some(at(&seg, seg.t_at_length(length - walked)));
+                }
+                walked += len;
+                last = Some(seg);
+            }
+
+            pen = match *cmd {
+                Command::ClosePath => start,
+                ref cmd => cmd.points().last().unwrap_or(pen),
+            };
+        }
+
+        // the end itself can come out a rounding error past the lengths added up here
+        last.filter(|_| length - walked <= walked * 1e-6)
+            .map(|seg| at(&seg, 1.0))
+    }
+
+    // the point `t` of the way along the outline, 0 at the start and 1 at the end
+    pub fn point_at(&self, t: Float) -> Option<PointAt> {
+        if !(0.0..=1.0).contains(&t) {
+            return None;
+        }
+        self.point_at_length(t * self.length())
+    }
+}
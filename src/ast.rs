@@ -0,0 +1,156 @@
+use std::fmt::Write;
+
+use crate::{path::Path, Command, Float, Point};
+
+// the json written by `Path::to_ast_json`, for tools outside rust. it is spelled out here rather
+// than derived from the types so that reworking them does not change it. bump the version whenever
+// a field is renamed, removed or changes meaning, new fields may be added freely
+//
+// {
+//   "version": 1,
+//   "commands": [
+//     { "type": "move" | "line" | "cubic" | "quadratic" | "arc" | "close",
+//       "subpath": index into "subpaths",
+//       "from": [x, y], the pen before the command,
+//       "to": [x, y], the pen after it (the subpath start for "close"),
+//       cubic and quadratic only:
+//       "controls": [[x, y], ...], two for cubic, one for quadratic, smooth ones resolved,
+//       "smooth": written as S or T,
+//       arc only:
+//       "radii": [rx, ry], "rotation": degrees, "large_arc": bool, "sweep": bool }
+//   ],
+//   "subpaths": [
+//     { "commands": [first, end], the commands it spans, end exclusive,
+//       "start": [x, y], "closed": bool }
+//   ],
+//   "bounds": { "min": [x, y], "max": [x, y] } measured at curve extremes, null when empty
+// }
+//
+// coordinates are absolute, numbers that are not finite are written as null
+pub const AST_VERSION: u32 = 1;
+
+fn number(out: &mut String, n: Float) {
+    if n.is_finite() {
+        let _ = write!(out, "{n}");
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn point(out: &mut String, (x, y): Point) {
+    out.push('[');
+    number(out, x);
+    out.push(',');
+    number(out, y);
+    out.push(']');
+}
+
+fn command(out: &mut String, cmd: &Command, subpath: usize, from: Point, to: Point) {
+    let (kind, controls, smooth) = match *cmd {
+        Command::MoveTo { .. } => ("move", [None; 2], false),
+        Command::LineTo { .. } => ("line", [None; 2], false),
+        Command::CurveTo { x1, y1, x2, y2, .. } => {
+            ("cubic", [Some((x1, y1)), Some((x2, y2))], false)
+        }
+        Command::SmoothCurveTo { cx, cy, x2, y2, .. } => {
+            ("cubic", [Some((cx, cy)), Some((x2, y2))], true)
+        }
+        Command::QuadraticBezierCurveTo { x1, y1, .. } => {
+            ("quadratic", [Some((x1, y1)), None], false)
+        }
+        Command::SmoothQuadraticBezierCurveTo { cx, cy, .. } => {
+            ("quadratic", [Some((cx, cy)), None], true)
+        }
+        Command::EllipticalArc { .. } => ("arc", [None; 2], false),
+        Command::ClosePath => ("close", [None; 2], false),
+    };
+
+    let _ = write!(out, "{{\"type\":\"{kind}\",\"subpath\":{subpath},\"from\":");
+    point(out, from);
+    out.push_str(",\"to\":");
+    point(out, to);
+
+    if kind == "cubic" || kind == "quadratic" {
+        out.push_str(",\"controls\":[");
+        for (i, p) in controls.into_iter().flatten().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            point(out, p);
+        }
+        let _ = write!(out, "],\"smooth\":{smooth}");
+    }
+
+    if let Command::EllipticalArc {
+        rx,
+        ry,
+        x_axis_rotation,
+        large_arc,
+        sweep,
+        ..
+    } = *cmd
+    {
+        out.push_str(",\"radii\":");
+        point(out, (rx, ry));
+        out.push_str(",\"rotation\":");
+        number(out, x_axis_rotation);
+        let _ = write!(out, ",\"large_arc\":{large_arc},\"sweep\":{sweep}");
+    }
+
+    out.push('}');
+}
+
+impl Path {
+    // the path as the json described at `AST_VERSION`
+    pub fn to_ast_json(&self) -> String {
+        let mut out = String::with_capacity(self.commands.len() * 64);
+        let _ = write!(out, "{{\"version\":{AST_VERSION},\"commands\":[");
+
+        let subpaths = self.subpaths();
+        let mut first = true;
+        let mut current = (0.0, 0.0);
+        for (i, sp) in subpaths.iter().enumerate() {
+            for cmd in sp.commands {
+                let to = match *cmd {
+                    Command::ClosePath => sp.start,
+                    ref cmd => cmd.points().last().unwrap_or(current),
+                };
+
+                if !first {
+                    out.push(',');
+                }
+                first = false;
+                command(&mut out, cmd, i, current, to);
+                current = to;
+            }
+        }
+
+        out.push_str("],\"subpaths\":[");
+        let mut index = 0;
+        for (i, sp) in subpaths.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let end = index + sp.commands.len();
+            let _ = write!(out, "{{\"commands\":[{index},{end}],\"start\":");
+            point(&mut out, sp.start);
+            let _ = write!(out, ",\"closed\":{}}}", sp.closed);
+            index = end;
+        }
+
+        out.push_str("],\"bounds\":");
+        match self.tight_bounds() {
+            Some(rect) => {
+                out.push_str("{\"min\":");
+                point(&mut out, (rect.min_x, rect.min_y));
+                out.push_str(",\"max\":");
+                point(&mut out, (rect.max_x, rect.max_y));
+                out.push('}');
+            }
+            None => out.push_str("null"),
+        }
+
+        out.push('}');
+        out
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    join::tangent,
+    offset::segment_distance,
+    path::Path,
+    segment::{dist, Segment},
+    Float,
+};
+
+// recursion guard, 2^12 biarcs per curve is far past any sensible tolerance
+const MAX_DEPTH: u32 = 12;
+
+// curve samples checked against each biarc
+const SAMPLES: usize = 8;
+
+// what line/arc-only controllers understand, `from` is always where the previous motion ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Motion {
+    // travel without drawing
+    Move {
+        to: (Float, Float),
+    },
+    Line {
+        to: (Float, Float),
+    },
+    // circular arc around `center`. `clockwise` is meant for y-up coordinates, as g-code uses, so
+    // flip the path with `to_y_up` first or swap G2/G3 when staying in svg space
+    Arc {
+        to: (Float, Float),
+        center: (Float, Float),
+        clockwise: bool,
+    },
+}
+
+fn sub(a: (Float, Float), b: (Float, Float)) -> (Float, Float) {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn dot(a: (Float, Float), b: (Float, Float)) -> Float {
+    a.0 * b.0 + a.1 * b.1
+}
+
+// the circular arc leaving `from` along `dir` and ending at `to`, a line when there is none
+fn arc(from: (Float, Float), dir: (Float, Float), to: (Float, Float)) -> Motion {
+    let chord = sub(to, from);
+    let normal = (-dir.1, dir.0);
+
+    // distance from `from` to the center along the normal
+    let along = dot(chord, chord) / (2.0 * dot(normal, chord));
+    if !along.is_finite() || along.abs() > 1e6 * dot(chord, chord).sqrt() {
+        return Motion::Line { to };
+    }
+
+    Motion::Arc {
+        to,
+        center: (from.0 + normal.0 * along, from.1 + normal.1 * along),
+        clockwise: along < 0.0,
+    }
+}
+
+// two arcs meeting at a shared tangent that leave `p0` along `t0` and arrive at `p1` along `t1`,
+// with the joint placed so both tangent lengths are equal
+fn biarc(
+    p0: (Float, Float),
+    t0: (Float, Float),
+    p1: (Float, Float),
+    t1: (Float, Float),
+) -> [Motion; 2] {
+    let v = sub(p1, p0);
+    let t = (t0.0 + t1.0, t0.1 + t1.1);
+    let denom = 2.0 * (1.0 - dot(t0, t1));
+
+    let d = if denom.abs() < 1e-6 {
+        dot(v, v) / (4.0 * dot(v, t1))
+    } else {
+        (-dot(v, t) + (dot(v, t).powi(2) + denom * dot(v, v)).sqrt()) / denom
+    };
+
+    let joint = (
+        (p0.0 + p1.0 + d * (t0.0 - t1.0)) / 2.0,
+        (p0.1 + p1.1 + d * (t0.1 - t1.1)) / 2.0,
+    );
+    if !d.is_finite() || !joint.0.is_finite() || !joint.1.is_finite() {
+        return [Motion::Line { to: p1 }, Motion::Line { to: p1 }];
+    }
+
+    // the second arc is built backwards from p1 and then turned around
+    let second = match arc(p1, (-t1.0, -t1.1), joint) {
+        Motion::Arc {
+            center, clockwise, ..
+        } => Motion::Arc {
+            to: p1,
+            center,
+            clockwise: !clockwise,
+        },
+        _ => Motion::Line { to: p1 },
+    };
+
+    [arc(p0, t0, joint), second]
+}
+
+fn distance(p: (Float, Float), from: (Float, Float), motion: &Motion) -> Float {
+    match *motion {
+        Motion::Move { .. } => Float::INFINITY,
+        Motion::Line { to } => segment_distance(p, from, to),
+        Motion::Arc { to, center, .. } => {
+            // the arc's own circle, close enough since samples fall within its sweep
+            (dist(p, center) - dist(to, center)).abs()
+        }
+    }
+}
+
+fn approximate(seg: &Segment, tolerance: Float, depth: u32, out: &mut Vec<Motion>) {
+    let (from, to) = (seg.from(), seg.to());
+
+    if let Segment::Line(..) = seg {
+        out.push(Motion::Line { to });
+        return;
+    }
+
+    let (Some(t0), Some(t1)) = (tangent(seg, 0.0), tangent(seg, 1.0)) else {
+        out.push(Motion::Line { to });
+        return;
+    };
+
+    let [a, b] = biarc(from, t0, to, t1);
+    let joint = match a {
+        Motion::Arc { to, .. } | Motion::Line { to } | Motion::Move { to } => to,
+    };
+
+    // samples before the joint are measured against the first arc and the rest against the
+    // second. the joint falls next to the sample nearest it, on the side of its nearer neighbour
+    let samples: Vec<_> = (0..=SAMPLES)
+        .map(|i| seg.eval(i as Float / SAMPLES as Float))
+        .collect();
+    let nearest = (1..SAMPLES)
+        .min_by(|&i, &j| dist(samples[i], joint).total_cmp(&dist(samples[j], joint)))
+        .unwrap_or(1);
+    let first = if dist(samples[nearest - 1], joint) < dist(samples[nearest + 1], joint) {
+        nearest
+    } else {
+        nearest + 1
+    };
+
+    let error = (1..SAMPLES)
+        .map(|i| {
+            if i < first {
+                distance(samples[i], from, &a)
+            } else {
+                distance(samples[i], joint, &b)
+            }
+        })
+        .fold(0.0, Float::max);
+
+    if error <= tolerance || depth >= MAX_DEPTH {
+        if joint != to {
+            out.push(a);
+        }
+        out.push(b);
+        return;
+    }
+
+    let (left, right) = seg.split(0.5);
+    approximate(&left, tolerance, depth + 1, out);
+    approximate(&right, tolerance, depth + 1, out);
+}
+
+impl Path {
+    // the path as lines and circular arcs only, curves are replaced by biarcs that stay within
+    // `tolerance` of them
+    pub fn to_biarcs(&self, tolerance: Float) -> Vec<Motion> {
+        let tolerance = tolerance.max(Float::EPSILON);
+        let mut out = Vec::new();
+
+        for sp in self.subpaths() {
+            out.push(Motion::Move { to: sp.start });
+            for seg in sp.segments() {
+                approximate(&seg, tolerance, 0, &mut out);
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{consts::TAU, parse_path_str, FlattenMethod};
+
+    // points along a motion leaving `from`, arcs turning the way `clockwise` says in y-up terms
+    fn trace(from: (Float, Float), motion: &Motion) -> Vec<(Float, Float)> {
+        let (to, sweep) = match *motion {
+            Motion::Move { to } | Motion::Line { to } => (to, None),
+            Motion::Arc {
+                to,
+                center,
+                clockwise,
+            } => (to, Some((center, clockwise))),
+        };
+        (0..=32)
+            .map(|i| {
+                let t = i as Float / 32.0;
+                let Some((center, clockwise)) = sweep else {
+                    return (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+                };
+                let angle = |p: (Float, Float)| (p.1 - center.1).atan2(p.0 - center.0);
+                let (start, end) = (angle(from), angle(to));
+                let turn = if clockwise {
+                    -(start - end).rem_euclid(TAU)
+                } else {
+                    (end - start).rem_euclid(TAU)
+                };
+                let (sin, cos) = (start + turn * t).sin_cos();
+                let r = dist(from, center);
+                (center.0 + r * cos, center.1 + r * sin)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn biarcs_stay_within_tolerance() {
+        let sources = [
+            "M0 0 C 30 60 70 -60 100 0",
+            "M0 0 C 100 0 100 100 0 100",
+            "M0 0 C 0 80 20 -40 90 30",
+            "M0 0 Q 50 100 100 0",
+        ];
+
+        for source in sources {
+            let path = Path::new(parse_path_str(source).unwrap());
+            let curve = &path.flatten_with(0.001, FlattenMethod::Adaptive)[0].points;
+
+            for tolerance in [1.0, 0.1, 0.01] {
+                let motions = path.to_biarcs(tolerance);
+                let mut from = (0.0, 0.0);
+                for motion in &motions {
+                    for p in trace(from, motion) {
+                        let error = curve
+                            .windows(2)
+                            .map(|w| segment_distance(p, w[0], w[1]))
+                            .fold(Float::INFINITY, Float::min);
+                        assert!(
+                            error <= tolerance * 1.05 + 0.001,
+                            "{source} at {tolerance}: {p:?} is {error} off"
+                        );
+                    }
+                    from = match *motion {
+                        Motion::Move { to } | Motion::Line { to } | Motion::Arc { to, .. } => to,
+                    };
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{path::Path, segment::Segment, Command, Float, Point};
+
+enum Element {
+    Move(Point),
+    Draw(Segment),
+    Close,
+}
+
+// the path as moves, segments with their start resolved, and closes. a path that does not open
+// with a MoveTo starts at the origin
+fn elements(path: &Path) -> Vec<Element> {
+    let mut out = Vec::with_capacity(path.commands.len() + 1);
+    if !matches!(path.commands.first(), Some(Command::MoveTo { .. }) | None) {
+        out.push(Element::Move((0.0, 0.0)));
+    }
+
+    let mut start = (0.0, 0.0);
+    let mut pen = start;
+    for cmd in path.commands.iter() {
+        match *cmd {
+            Command::MoveTo { x, y } => {
+                start = (x, y);
+                pen = start;
+                out.push(Element::Move(start));
+            }
+            Command::ClosePath => {
+                pen = start;
+                out.push(Element::Close);
+            }
+            ref cmd => {
+                for seg in Segment::pieces(pen, cmd) {
+                    pen = seg.to();
+                    out.push(Element::Draw(seg));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn controls(seg: &Segment) -> Vec<Point> {
+    match *seg {
+        Segment::Line(p0, p1) => vec![p0, p1],
+        Segment::Quad(p0, p1, p2) => vec![p0, p1, p2],
+        Segment::Cubic(p0, p1, p2, p3) => vec![p0, p1, p2, p3],
+    }
+}
+
+fn weighted(points: impl Iterator<Item = (Point, Float)>, total: Float) -> Point {
+    let (x, y) = points.fold((0.0, 0.0), |acc, (p, w)| (acc.0 + p.0 * w, acc.1 + p.1 * w));
+    (x / total, y / total)
+}
+
+impl Path {
+    // the weighted average of paths drawn with the same structure: the same moves, closes and
+    // segments in the same order, like the masters of a variable font. segments that are lines in
+    // one path and curves in another are blended as cubics. weights are normalized, `None` when
+    // they sum to zero or the paths do not line up
+    pub fn blend(paths: &[(&Path, Float)]) -> Option<Path> {
+        let total: Float = paths.iter().map(|(_, w)| w).sum();
+        if paths.is_empty() || total == 0.0 || !total.is_finite() {
+            return None;
+        }
+
+        let all: Vec<Vec<Element>> = paths.iter().map(|(path, _)| elements(path)).collect();
+        let len = all[0].len();
+        if all.iter().any(|els| els.len() != len) {
+            return None;
+        }
+
+        let mut commands = Vec::with_capacity(len);
+        for i in 0..len {
+            match &all[0][i] {
+                Element::Move(_) => {
+                    let mut points = Vec::with_capacity(paths.len());
+                    for (els, (_, w)) in all.iter().zip(paths) {
+                        let Element::Move(p) = els[i] else {
+                            return None;
+                        };
+                        points.push((p, *w));
+                    }
+                    let (x, y) = weighted(points.into_iter(), total);
+                    commands.push(Command::MoveTo { x, y });
+                }
+                Element::Close => {
+                    if !all.iter().all(|els| matches!(els[i], Element::Close)) {
+                        return None;
+                    }
+                    commands.push(Command::ClosePath);
+                }
+                Element::Draw(first) => {
+                    let mut segs = Vec::with_capacity(paths.len());
+                    for els in &all {
+                        let Element::Draw(seg) = els[i] else {
+                            return None;
+                        };
+                        segs.push(seg);
+                    }
+
+                    let kind = std::mem::discriminant(first);
+                    if segs.iter().any(|seg| std::mem::discriminant(seg) != kind) {
+                        segs.iter_mut().for_each(|seg| *seg = seg.to_cubic());
+                    }
+
+                    let controls: Vec<Vec<Point>> = segs.iter().map(controls).collect();
+                    let mixed: Vec<Point> = (0..controls[0].len())
+                        .map(|k| {
+                            let points = controls.iter().zip(paths).map(|(c, (_, w))| (c[k], *w));
+                            weighted(points, total)
+                        })
+                        .collect();
+
+                    commands.push(
+                        match mixed[..] {
+                            [a, b] => Segment::Line(a, b),
+                            [a, b, c] => Segment::Quad(a, b, c),
+                            [a, b, c, d] => Segment::Cubic(a, b, c, d),
+                            _ => unreachable!(),
+                        }
+                        .to_command(),
+                    );
+                }
+            }
+        }
+
+        Some(Path::new(commands))
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    path::Path,
+    roots::{coefficients, unit_roots},
+    segment::Segment,
+    Float, Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub min_x: Float,
+    pub min_y: Float,
+    pub max_x: Float,
+    pub max_y: Float,
+}
+
+impl Rect {
+    pub fn width(&self) -> Float {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> Float {
+        self.max_y - self.min_y
+    }
+
+    fn around(p: Point) -> Rect {
+        Rect {
+            min_x: p.0,
+            min_y: p.1,
+            max_x: p.0,
+            max_y: p.1,
+        }
+    }
+
+    fn include(&mut self, p: Point) {
+        self.min_x = self.min_x.min(p.0);
+        self.min_y = self.min_y.min(p.1);
+        self.max_x = self.max_x.max(p.0);
+        self.max_y = self.max_y.max(p.1);
+    }
+}
+
+// the ends of `seg` and wherever it turns back along x or y, where the derivative of that
+// coordinate is zero
+fn extremes(seg: &Segment, out: &mut impl FnMut(Point)) {
+    out(seg.from());
+    out(seg.to());
+
+    for axis in [|p: Point| p.0, |p: Point| p.1] {
+        let [a, b, c, _] = coefficients(seg, axis);
+        for t in unit_roots([0.0, 3.0 * a, 2.0 * b, c]) {
+            out(seg.eval(t));
+        }
+    }
+}
+
+impl Path {
+    // the box the drawn path actually covers, curves measured at their extremes rather than by
+    // their control points. subpath starts count even when nothing is drawn from them. `None`
+    // for a path without commands
+    pub fn tight_bounds(&self) -> Option<Rect> {
+        let mut rect: Option<Rect> = None;
+        let mut include = |p: Point| match &mut rect {
+            Some(rect) => rect.include(p),
+            None => rect = Some(Rect::around(p)),
+        };
+
+        for sp in self.subpaths() {
+            include(sp.start);
+            for seg in sp.segments() {
+                extremes(&seg, &mut include);
+            }
+        }
+
+        rect
+    }
+}
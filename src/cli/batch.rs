@@ -0,0 +1,177 @@
+use std::{
+    fs,
+    path::{Path as FsPath, PathBuf},
+};
+
+use scavenger::{writer::write_svg, FillRule};
+
+use super::{
+    glob::glob,
+    load_path,
+    ops::Op,
+    toml::{self, Table, Value},
+};
+
+// a pipeline file holds any number of jobs:
+//
+//     [[job]]
+//     inputs = ["icons/**/*.svg"]
+//     ops = ["normalize", "fit:64x64", "optimize"]
+//     output = "out/{dir}/{stem}.svg"
+//
+// relative paths are resolved against the pipeline file. `output` may use {stem}, {name}, {ext}
+// and {dir} (the input's directory relative to the pipeline file). outputs ending in .svg are
+// written as documents, a trailing `rasterize:WxH` op writes .png or .pgm images and anything else
+// gets the bare `d` string
+struct Job {
+    inputs: Vec<String>,
+    ops: Vec<Op>,
+    output: String,
+}
+
+fn strings(table: &Table, key: &str) -> Result<Vec<String>, String> {
+    match table.get(key) {
+        Some(Value::String(s)) => Ok(vec![s.clone()]),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("`{key}` must hold strings"))
+            })
+            .collect(),
+        Some(_) => Err(format!("`{key}` must be a string or an array of strings")),
+        None => Ok(Vec::new()),
+    }
+}
+
+impl Job {
+    fn from_table(table: &Table) -> Result<Job, String> {
+        let inputs = strings(table, "inputs")?;
+        if inputs.is_empty() {
+            return Err("missing `inputs`".to_string());
+        }
+
+        let mut ops = Vec::new();
+        for op in strings(table, "ops")? {
+            ops.extend(Op::parse_list(&op)?);
+        }
+
+        if let Some(i) = ops.iter().position(|op| matches!(op, Op::Rasterize(..))) {
+            if ops[i + 1..].iter().any(|op| *op != Op::Write) {
+                return Err("`rasterize` must be the last op".to_string());
+            }
+        }
+
+        let output = table
+            .get("output")
+            .and_then(Value::as_str)
+            .ok_or("missing `output`")?
+            .to_string();
+
+        Ok(Job {
+            inputs,
+            ops,
+            output,
+        })
+    }
+}
+
+fn expand(template: &str, base: &FsPath, input: &FsPath) -> PathBuf {
+    let part = |s: Option<&std::ffi::OsStr>| s.map(|s| s.to_string_lossy().into_owned());
+
+    let dir = input
+        .parent()
+        .map(|p| {
+            p.strip_prefix(base)
+                .unwrap_or(p)
+                .to_string_lossy()
+                .into_owned()
+        })
+        .unwrap_or_default();
+
+    let expanded = template
+        .replace("{stem}", &part(input.file_stem()).unwrap_or_default())
+        .replace("{name}", &part(input.file_name()).unwrap_or_default())
+        .replace("{ext}", &part(input.extension()).unwrap_or_default())
+        .replace("{dir}", &dir);
+
+    base.join(expanded)
+}
+
+fn process(job: &Job, input: &FsPath, output: &FsPath) -> Result<(), String> {
+    let mut path = load_path(input)?;
+
+    let mut raster = None;
+    for op in &job.ops {
+        match *op {
+            Op::Rasterize(w, h) => raster = Some(path.rasterize(w, h, FillRule::NonZero)),
+            op => op.apply(&mut path),
+        }
+    }
+
+    let ext = output.extension().and_then(|e| e.to_str());
+    let bytes = match (raster, ext) {
+        (Some(mask), Some("pgm")) => mask.to_pgm(),
+        (Some(mask), _) => mask.to_png(),
+        (None, Some("svg")) => {
+            let (w, h) = path.bb();
+            write_svg(&[path], w, h).into_bytes()
+        }
+        (None, _) => format!("{}\n", path.to_d()).into_bytes(),
+    };
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+    }
+    fs::write(output, bytes).map_err(|e| format!("{}: {e}", output.display()))
+}
+
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let [file] = args else {
+        return Err("usage: scavenger batch <pipeline.toml>".to_string());
+    };
+
+    let file = FsPath::new(file);
+    let source = fs::read_to_string(file).map_err(|e| format!("{}: {e}", file.display()))?;
+    let doc = toml::parse(&source).map_err(|e| format!("{}: {e}", file.display()))?;
+    let base = file.parent().unwrap_or(FsPath::new(""));
+
+    let tables = doc.arrays.get("job").map(Vec::as_slice).unwrap_or_default();
+    if tables.is_empty() {
+        return Err(format!("{}: no [[job]] tables", file.display()));
+    }
+
+    let mut failed = 0;
+    let mut written = 0;
+
+    for (n, table) in tables.iter().enumerate() {
+        let job = Job::from_table(table).map_err(|e| format!("job {}: {e}", n + 1))?;
+
+        let mut inputs: Vec<PathBuf> = job.inputs.iter().flat_map(|p| glob(base, p)).collect();
+        inputs.sort();
+        inputs.dedup();
+
+        if inputs.is_empty() {
+            eprintln!("job {}: no inputs matched", n + 1);
+        }
+
+        for input in &inputs {
+            let output = expand(&job.output, base, input);
+
+            match process(&job, input, &output) {
+                Ok(()) => {
+                    written += 1;
+                    eprintln!("{} -> {}", input.display(), output.display());
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("error: {e}");
+                }
+            }
+        }
+    }
+
+    eprintln!("{written} written, {failed} failed");
+    Ok(i32::from(failed > 0))
+}
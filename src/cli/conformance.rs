@@ -0,0 +1,274 @@
+use std::{
+    fs,
+    path::{Path as FsPath, PathBuf},
+};
+
+use scavenger::{parse_path_str, svg, Float};
+
+use super::{is_svg, svg_error};
+
+// past this a Float (f32 unless built with `f64`) can no longer represent every integer, so
+// coordinates start to lose precision
+const PRECISION_LIMIT: Float = (1u64 << Float::MANTISSA_DIGITS) as Float;
+
+#[derive(Default)]
+struct FileReport {
+    paths: usize,
+    failures: Vec<String>,
+    warnings: Vec<String>,
+    max_magnitude: Float,
+}
+
+// every .svg, .svgz and .d file under `dir`, sorted so reports are stable between runs
+fn load_corpus(dir: &FsPath, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            load_corpus(&path, out)?;
+        } else if is_svg(&path) || path.extension().and_then(|e| e.to_str()) == Some("d") {
+            out.push(path);
+        }
+    }
+
+    out.sort();
+    Ok(())
+}
+
+fn check_file(file: &FsPath) -> FileReport {
+    let mut report = FileReport::default();
+
+    let bytes = match fs::read(file) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            report.failures.push(format!("unreadable: {e}"));
+            return report;
+        }
+    };
+    let source = if is_svg(file) {
+        svg::decode(bytes).map_err(svg_error)
+    } else {
+        String::from_utf8(bytes).map_err(|_| "not valid utf-8".to_string())
+    };
+    let source = match source {
+        Ok(source) => source,
+        Err(e) => {
+            report.failures.push(format!("unreadable: {e}"));
+            return report;
+        }
+    };
+
+    let data = if is_svg(file) {
+        match svg::path_data(&source) {
+            Ok(data) => data,
+            Err(e) => {
+                report
+                    .failures
+                    .push(format!("malformed svg at byte {}", e.offset));
+                return report;
+            }
+        }
+    } else {
+        vec![source]
+    };
+
+    if data.is_empty() {
+        report.warnings.push("no path data".to_string());
+    }
+
+    for (i, d) in data.iter().enumerate() {
+        report.paths += 1;
+
+        let commands = match parse_path_str(d) {
+            Ok(commands) => commands,
+            Err(e) => {
+                report.failures.push(format!("path {i}: {e}"));
+                continue;
+            }
+        };
+
+        if commands.is_empty() {
+            report.warnings.push(format!("path {i}: empty"));
+        }
+
+        let mut non_finite = false;
+        for (x, y) in commands.iter().flat_map(|cmd| cmd.points()) {
+            if !x.is_finite() || !y.is_finite() {
+                non_finite = true;
+                continue;
+            }
+            report.max_magnitude = report.max_magnitude.max(x.abs()).max(y.abs());
+        }
+
+        if non_finite {
+            report
+                .warnings
+                .push(format!("path {i}: non-finite coordinates"));
+        }
+    }
+
+    if report.max_magnitude > PRECISION_LIMIT {
+        report.warnings.push(format!(
+            "coordinates up to {} lose {} precision",
+            report.max_magnitude,
+            std::any::type_name::<Float>()
+        ));
+    }
+
+    report
+}
+
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let [dir] = args else {
+        return Err("usage: scavenger conformance <dir>".to_string());
+    };
+
+    let dir = FsPath::new(dir);
+    let mut files = Vec::new();
+    load_corpus(dir, &mut files).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let mut total_paths = 0;
+    let mut total_failures = 0;
+    let mut total_warnings = 0;
+    let mut failed_files = 0;
+    let mut max_magnitude: Float = 0.0;
+
+    let mut rows = Vec::new();
+
+    for file in &files {
+        let report = check_file(file);
+        let name = file.strip_prefix(dir).unwrap_or(file).display().to_string();
+
+        for failure in &report.failures {
+            println!("FAIL {name}: {failure}");
+        }
+        for warning in &report.warnings {
+            println!("WARN {name}: {warning}");
+        }
+
+        total_paths += report.paths;
+        total_failures += report.failures.len();
+        total_warnings += report.warnings.len();
+        failed_files += usize::from(!report.failures.is_empty());
+        max_magnitude = max_magnitude.max(report.max_magnitude);
+
+        if !report.failures.is_empty() || !report.warnings.is_empty() {
+            rows.push((name, report));
+        }
+    }
+
+    if !rows.is_empty() {
+        let width = rows
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        println!();
+        println!(
+            "{:<width$}  {:>6}  {:>6}  {:>6}  {:>12}",
+            "file", "paths", "failed", "warned", "max |coord|"
+        );
+        for (name, report) in &rows {
+            println!(
+                "{:<width$}  {:>6}  {:>6}  {:>6}  {:>12}",
+                name,
+                report.paths,
+                report.failures.len(),
+                report.warnings.len(),
+                report.max_magnitude
+            );
+        }
+    }
+
+    println!();
+    println!("files:          {}", files.len());
+    println!("paths:          {total_paths}");
+    println!("failed files:   {failed_files}");
+    println!("failures:       {total_failures}");
+    println!("warnings:       {total_warnings}");
+    println!("max |coord|:    {max_magnitude}");
+
+    Ok(i32::from(total_failures > 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> PathBuf {
+        FsPath::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/conformance")
+    }
+
+    fn check(name: &str) -> FileReport {
+        check_file(&fixtures().join(name))
+    }
+
+    #[test]
+    fn loads_path_files_recursively_in_order() {
+        let dir = fixtures();
+        let mut files = Vec::new();
+        load_corpus(&dir, &mut files).unwrap();
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| file.strip_prefix(&dir).unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "broken.d",
+                "huge.d",
+                "icons/arrow.svg",
+                "icons/blank.svg",
+                "icons/unclosed.svg",
+                "square.d",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_clean_files() {
+        let report = check("square.d");
+        assert_eq!(report.paths, 1);
+        assert!(report.failures.is_empty() && report.warnings.is_empty());
+        assert_eq!(report.max_magnitude, 10.0);
+
+        let report = check("icons/arrow.svg");
+        assert_eq!(report.paths, 2);
+        assert!(report.failures.is_empty() && report.warnings.is_empty());
+        assert_eq!(report.max_magnitude, 19.0);
+    }
+
+    #[test]
+    fn reports_failures() {
+        let report = check("broken.d");
+        assert_eq!(report.paths, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].starts_with("path 0: "));
+
+        let report = check("icons/unclosed.svg");
+        assert_eq!(report.paths, 0);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].starts_with("malformed svg at byte "));
+
+        let report = check("missing.d");
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].starts_with("unreadable: "));
+    }
+
+    #[test]
+    fn reports_warnings() {
+        let report = check("icons/blank.svg");
+        assert!(report.failures.is_empty());
+        assert_eq!(report.warnings, ["no path data"]);
+
+        // past what f64 holds exactly too, so the warning does not depend on the features
+        let report = check("huge.d");
+        assert!(report.failures.is_empty());
+        assert!(report.max_magnitude > PRECISION_LIMIT);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("coordinates up to "));
+    }
+}
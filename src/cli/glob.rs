@@ -0,0 +1,74 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// `*` and `?` within a single path component
+fn matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+fn walk(dir: &Path, components: &[&str], out: &mut Vec<PathBuf>) {
+    let Some((first, rest)) = components.split_first() else {
+        if dir.is_file() {
+            out.push(dir.to_path_buf());
+        }
+        return;
+    };
+
+    if *first == "**" {
+        // zero directories, then one more level deep with `**` still in place
+        walk(dir, rest, out);
+
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(&path, components, out);
+                }
+            }
+        }
+        return;
+    }
+
+    if !first.contains(['*', '?']) {
+        walk(&dir.join(first), rest, out);
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if matches(first.as_bytes(), name.to_string_lossy().as_bytes()) {
+            walk(&entry.path(), rest, out);
+        }
+    }
+}
+
+// files under `base` matching a `/` separated pattern with `*`, `?` and `**`, sorted
+pub fn glob(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let (base, pattern) = match pattern.strip_prefix('/') {
+        Some(absolute) => (Path::new("/"), absolute),
+        None => (base, pattern),
+    };
+
+    let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+
+    let mut out = Vec::new();
+    walk(base, &components, &mut out);
+
+    out.sort();
+    out.dedup();
+    out
+}
@@ -0,0 +1,42 @@
+use std::path::Path as FsPath;
+
+use super::{
+    load_paths,
+    output::{self, Format, Record},
+};
+
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let mut format = Format::Text;
+    let mut files = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = Format::parse(args.next().ok_or("--format expects a value")?)?,
+            _ => files.push(arg.as_str()),
+        }
+    }
+
+    if files.is_empty() {
+        return Err("usage: scavenger inspect <input>... [--format text|json|ndjson|csv]".into());
+    }
+
+    let mut loaded = Vec::new();
+    for file in &files {
+        loaded.push((*file, load_paths(FsPath::new(file))?));
+    }
+
+    let records: Vec<Record> = loaded
+        .iter()
+        .flat_map(|(source, paths)| {
+            paths.iter().enumerate().map(|(index, path)| Record {
+                source,
+                index,
+                path,
+            })
+        })
+        .collect();
+
+    print!("{}", output::write(format, &records));
+    Ok(0)
+}
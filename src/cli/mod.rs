@@ -0,0 +1,139 @@
+use std::{fs, path::Path as FsPath};
+
+use scavenger::{
+    parse_path_str,
+    path::Path,
+    svg::{self, SvgError},
+    Float,
+};
+
+mod batch;
+mod conformance;
+mod glob;
+mod inspect;
+mod ops;
+mod output;
+mod pipe;
+mod toml;
+mod watch;
+
+const USAGE: &str = "usage: scavenger <command> [args]
+
+commands:
+    batch <pipeline.toml>
+                         run the jobs declared in a pipeline file
+    conformance <dir>    parse every .svg/.svgz/.d file under <dir> and report problems
+    inspect <input>...   report commands, bounds, length and subpaths of every path
+        --format <fmt>       text (default), json, ndjson or csv
+    pipe                 transform `d` strings read line by line from stdin
+        --ops <list>         comma separated steps: fit:WxH, cover:WxH, scale:S,
+                             translate:X:Y, normalize, optimize, write
+                             (batch jobs may end with rasterize:WxH)
+    watch <input>        rebuild an svg whenever <input> changes
+        --fit <W>x<H>        scale to fit inside W by H
+        --out <file>         write to <file> instead of stdout";
+
+pub fn run(args: &[String]) -> i32 {
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return 2;
+    };
+
+    let result = match command.as_str() {
+        "batch" => batch::run(rest),
+        "conformance" => conformance::run(rest),
+        "inspect" => inspect::run(rest),
+        "pipe" => pipe::run(rest),
+        "watch" => watch::run(rest),
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            return 0;
+        }
+        _ => Err(format!("unknown command `{command}`\n\n{USAGE}")),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            2
+        }
+    }
+}
+
+// bounds of every point (anchors and control points) as [min_x, min_y, max_x, max_y]
+pub(crate) fn bounds(path: &Path) -> Option<[Float; 4]> {
+    path.commands()
+        .iter()
+        .flat_map(|cmd| cmd.points())
+        .fold(None, |acc, (x, y)| {
+            Some(match acc {
+                None => [x, y, x, y],
+                Some([x0, y0, x1, y1]) => [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+            })
+        })
+}
+
+// "64x64" -> (64.0, 64.0)
+pub(crate) fn parse_size(s: &str) -> Result<(Float, Float), String> {
+    let (w, h) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected <width>x<height>, got `{s}`"))?;
+
+    match (w.parse(), h.parse()) {
+        (Ok(w), Ok(h)) => Ok((w, h)),
+        _ => Err(format!("expected <width>x<height>, got `{s}`")),
+    }
+}
+
+// .svg and gzip compressed .svgz files
+pub(crate) fn is_svg(file: &FsPath) -> bool {
+    matches!(
+        file.extension().and_then(|e| e.to_str()),
+        Some("svg" | "svgz")
+    )
+}
+
+pub(crate) fn svg_error(e: SvgError) -> String {
+    match e {
+        SvgError::Xml(e) => format!("malformed svg at byte {}", e.offset),
+        SvgError::Path(e) => e.to_string(),
+        SvgError::Selector { offset } => format!("malformed selector at byte {offset}"),
+        SvgError::Io(kind) => kind.to_string(),
+        SvgError::Compressed => {
+            "compressed svg, build with the `flate2` feature to read it".to_string()
+        }
+    }
+}
+
+// the text of a file, inflated first when it is an .svgz
+pub(crate) fn read_source(file: &FsPath) -> Result<String, String> {
+    let bytes = fs::read(file).map_err(|e| format!("{}: {e}", file.display()))?;
+    if is_svg(file) {
+        return svg::decode(bytes).map_err(|e| format!("{}: {}", file.display(), svg_error(e)));
+    }
+    String::from_utf8(bytes).map_err(|_| format!("{}: not valid utf-8", file.display()))
+}
+
+// every path in an svg file, or a file holding a single `d` string
+pub(crate) fn load_paths(file: &FsPath) -> Result<Vec<Path>, String> {
+    let source = read_source(file)?;
+
+    if is_svg(file) {
+        return svg::paths(&source).map_err(|e| format!("{}: {}", file.display(), svg_error(e)));
+    }
+
+    parse_path_str(&source)
+        .map(|commands| vec![Path::new(commands)])
+        .map_err(|e| format!("{}: {e}", file.display()))
+}
+
+// every path in a file merged into one
+pub(crate) fn load_path(file: &FsPath) -> Result<Path, String> {
+    let commands = load_paths(file)?
+        .into_iter()
+        .flat_map(|path| path.take_commands())
+        .collect();
+
+    Ok(Path::new(commands))
+}
@@ -0,0 +1,77 @@
+use scavenger::{path::Path, Float};
+
+use super::{bounds, parse_size};
+
+// a single step of a pipeline, written as `name` or `name:arg:arg`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Fit(Float, Float),
+    Cover(Float, Float),
+    Scale(Float),
+    Translate(Float, Float),
+    // move the top left of the bounds to the origin
+    Normalize,
+    Optimize,
+    // render to a WxH coverage image, only valid as the last step of a batch job
+    Rasterize(usize, usize),
+    Write,
+}
+
+fn number(s: &str) -> Result<Float, String> {
+    s.parse()
+        .map_err(|_| format!("expected a number, got `{s}`"))
+}
+
+impl Op {
+    pub fn parse(s: &str) -> Result<Op, String> {
+        let mut parts = s.trim().split(':');
+        let name = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+
+        Ok(match (name, args.as_slice()) {
+            ("fit", [size]) => {
+                let (w, h) = parse_size(size)?;
+                Op::Fit(w, h)
+            }
+            ("cover", [size]) => {
+                let (w, h) = parse_size(size)?;
+                Op::Cover(w, h)
+            }
+            ("scale", [s]) => Op::Scale(number(s)?),
+            ("translate", [x, y]) => Op::Translate(number(x)?, number(y)?),
+            ("normalize", []) => Op::Normalize,
+            ("optimize", []) => Op::Optimize,
+            ("rasterize", [size]) => {
+                let (w, h) = parse_size(size)?;
+                Op::Rasterize(w as usize, h as usize)
+            }
+            ("write", []) => Op::Write,
+            _ => return Err(format!("unknown op `{s}`")),
+        })
+    }
+
+    // "fit:64x64,normalize,write"
+    pub fn parse_list(s: &str) -> Result<Vec<Op>, String> {
+        s.split(',')
+            .filter(|op| !op.trim().is_empty())
+            .map(Op::parse)
+            .collect()
+    }
+
+    // apply to `path`, `Rasterize` and `Write` are left to the caller
+    pub fn apply(&self, path: &mut Path) {
+        match *self {
+            Op::Fit(w, h) => path.fit(w, h),
+            Op::Cover(w, h) => path.cover(w, h),
+            Op::Scale(s) => path.scale(s),
+            Op::Translate(x, y) => path.translate(x, y),
+            Op::Normalize => {
+                if let Some([x, y, _, _]) = bounds(path) {
+                    path.translate(-x, -y);
+                }
+            }
+            Op::Optimize => path.optimize_start_points(),
+            Op::Rasterize(..) | Op::Write => {}
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt::Write;
+
+use scavenger::{path::Path, writer::write_d, Command, Float};
+
+use super::bounds;
+
+// bump whenever a field is renamed, removed or changes meaning. new fields may be added freely
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Format, String> {
+        Ok(match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "ndjson" => Format::Ndjson,
+            "csv" => Format::Csv,
+            _ => {
+                return Err(format!(
+                    "unknown format `{s}`, expected text|json|ndjson|csv"
+                ))
+            }
+        })
+    }
+}
+
+// what gets reported for every parsed path
+pub struct Record<'a> {
+    pub source: &'a str,
+    pub index: usize,
+    pub path: &'a Path,
+}
+
+fn number(out: &mut String, n: Float) {
+    // json has no representation for nan/inf
+    if n.is_finite() {
+        let _ = write!(out, "{n}");
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn letter(cmd: &Command) -> char {
+    match cmd {
+        Command::MoveTo { .. } => 'M',
+        Command::LineTo { .. } => 'L',
+        Command::CurveTo { .. } => 'C',
+        Command::SmoothCurveTo { .. } => 'S',
+        Command::QuadraticBezierCurveTo { .. } => 'Q',
+        Command::SmoothQuadraticBezierCurveTo { .. } => 'T',
+        Command::EllipticalArc { .. } => 'A',
+        Command::ClosePath => 'Z',
+    }
+}
+
+// one json object. commands are arrays of a letter followed by absolute coordinates, with S and T
+// carrying their resolved first control point: ["S", cx, cy, x2, y2, x, y], ["T", cx, cy, x, y].
+// arcs are written out in full: ["A", rx, ry, rotation, large_arc, sweep, x, y]
+fn json(out: &mut String, record: &Record) {
+    let path = record.path;
+
+    let _ = write!(out, "{{\"schema\":{SCHEMA_VERSION},\"source\":");
+    string(out, record.source);
+    let _ = write!(out, ",\"index\":{},\"commands\":[", record.index);
+
+    for (i, cmd) in path.commands().iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(out, "[\"{}\"", letter(cmd));
+        if let Command::EllipticalArc {
+            rx,
+            ry,
+            x_axis_rotation,
+            large_arc,
+            sweep,
+            ..
+        } = *cmd
+        {
+            for n in [rx, ry, x_axis_rotation] {
+                out.push(',');
+                number(out, n);
+            }
+            let _ = write!(out, ",{},{}", large_arc as u8, sweep as u8);
+        }
+        for (x, y) in cmd.points() {
+            out.push(',');
+            number(out, x);
+            out.push(',');
+            number(out, y);
+        }
+        out.push(']');
+    }
+
+    out.push_str("],\"bounds\":");
+    match bounds(path) {
+        Some(b) => {
+            out.push('[');
+            for (i, n) in b.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                number(out, n);
+            }
+            out.push(']');
+        }
+        None => out.push_str("null"),
+    }
+
+    out.push_str(",\"length\":");
+    number(out, path.length());
+    let _ = write!(out, ",\"subpaths\":{}}}", path.subpaths().len());
+}
+
+fn csv_field(out: &mut String, s: &str) {
+    if s.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&s.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(s);
+    }
+}
+
+pub fn write(format: Format, records: &[Record]) -> String {
+    let mut out = String::new();
+
+    match format {
+        Format::Text => {
+            for record in records {
+                let path = record.path;
+                let _ = writeln!(out, "{} #{}", record.source, record.index);
+                let _ = writeln!(out, "  commands: {}", path.commands().len());
+                let _ = writeln!(out, "  subpaths: {}", path.subpaths().len());
+                let _ = writeln!(out, "  length:   {}", path.length());
+                if let Some([x0, y0, x1, y1]) = bounds(path) {
+                    let _ = writeln!(out, "  bounds:   {x0} {y0} {x1} {y1}");
+                }
+                let _ = writeln!(out, "  d:        {}", write_d(path.commands()));
+            }
+        }
+        Format::Json => {
+            out.push('[');
+            for (i, record) in records.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push('\n');
+                json(&mut out, record);
+            }
+            out.push_str("\n]\n");
+        }
+        Format::Ndjson => {
+            for record in records {
+                json(&mut out, record);
+                out.push('\n');
+            }
+        }
+        Format::Csv => {
+            out.push_str(
+                "schema,source,index,commands,subpaths,length,min_x,min_y,max_x,max_y,d\n",
+            );
+            for record in records {
+                let path = record.path;
+                let _ = write!(out, "{SCHEMA_VERSION},");
+                csv_field(&mut out, record.source);
+                let _ = write!(
+                    out,
+                    ",{},{},{},{},",
+                    record.index,
+                    path.commands().len(),
+                    path.subpaths().len(),
+                    path.length()
+                );
+                match bounds(path) {
+                    Some([x0, y0, x1, y1]) => {
+                        let _ = write!(out, "{x0},{y0},{x1},{y1},");
+                    }
+                    None => out.push_str(",,,,"),
+                }
+                csv_field(&mut out, &write_d(path.commands()));
+                out.push('\n');
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,61 @@
+use std::io::{self, BufRead, BufWriter, Write};
+
+use scavenger::{parse_path_str, path::Path, writer::write_io};
+
+use super::ops::Op;
+
+// one `d` string per line in, one per line out. a line that fails to parse is reported on stderr
+// and written as an empty line so output lines keep matching input lines
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let mut ops = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ops" => ops = Op::parse_list(args.next().ok_or("--ops expects a list")?)?,
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    if ops.iter().any(|op| matches!(op, Op::Rasterize(..))) {
+        return Err("rasterize is not supported when piping `d` strings".to_string());
+    }
+
+    // each input line is written once, after every other op
+    if ops.iter().rev().skip(1).any(|op| *op == Op::Write) {
+        return Err("write can only be the last op when piping `d` strings".to_string());
+    }
+    if ops.last() != Some(&Op::Write) {
+        ops.push(Op::Write);
+    }
+
+    let stdin = io::stdin().lock();
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let mut failed = false;
+
+    for (n, line) in stdin.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+
+        let mut path = match parse_path_str(&line) {
+            Ok(commands) => Path::new(commands),
+            Err(e) => {
+                eprintln!("line {}: {e}", n + 1);
+                failed = true;
+                writeln!(stdout).map_err(|e| e.to_string())?;
+                continue;
+            }
+        };
+
+        for op in &ops {
+            match op {
+                Op::Write => write_io(&mut stdout, path.commands())
+                    .and_then(|()| writeln!(stdout))
+                    .map_err(|e| e.to_string())?,
+                op => op.apply(&mut path),
+            }
+        }
+    }
+
+    stdout.flush().map_err(|e| e.to_string())?;
+    Ok(i32::from(failed))
+}
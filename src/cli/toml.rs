@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+// the small part of toml pipeline files need: [tables], [[arrays of tables]], and keys holding
+// strings, numbers, booleans or (nested) arrays of those. inline tables and dates are not supported
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+pub type Table = BTreeMap<String, Value>;
+
+#[derive(Debug, Default)]
+pub struct Document {
+    pub root: Table,
+    pub tables: BTreeMap<String, Table>,
+    pub arrays: BTreeMap<String, Vec<Table>>,
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn error(&self, msg: &str) -> String {
+        format!("line {}: {msg}", self.line)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    // spaces and tabs, plus newlines and comments when `newlines` is set
+    fn skip(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' => {}
+                '\n' if newlines => {}
+                '#' => {
+                    while !matches!(self.peek(), Some('\n') | None) {
+                        self.bump();
+                    }
+                    continue;
+                }
+                _ => break,
+            }
+            self.bump();
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip(false);
+        match self.bump() {
+            None | Some('\n') => Ok(()),
+            Some(c) => Err(self.error(&format!("unexpected `{c}`"))),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        self.skip(false);
+        if let Some(q @ ('"' | '\'')) = self.peek() {
+            return self.string(q);
+        }
+
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        {
+            self.bump();
+        }
+
+        if start == self.pos {
+            return Err(self.error("expected a key"));
+        }
+        Ok(self.src[start..self.pos].to_string())
+    }
+
+    fn string(&mut self, quote: char) -> Result<String, String> {
+        self.bump();
+        let mut out = String::new();
+
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error("unterminated string")),
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') if quote == '"' => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('\\') => out.push('\\'),
+                    Some('"') => out.push('"'),
+                    _ => return Err(self.error("unsupported escape")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip(false);
+
+        match self.peek() {
+            Some(q @ ('"' | '\'')) => self.string(q).map(Value::String),
+            Some('[') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip(true);
+                    if self.peek() == Some(']') {
+                        self.bump();
+                        return Ok(Value::Array(items));
+                    }
+
+                    items.push(self.value()?);
+
+                    self.skip(true);
+                    match self.bump() {
+                        Some(',') => {}
+                        Some(']') => return Ok(Value::Array(items)),
+                        _ => return Err(self.error("expected `,` or `]`")),
+                    }
+                }
+            }
+            _ => {
+                let start = self.pos;
+                while matches!(self.peek(), Some(c) if !matches!(c, ',' | ']' | '#' | '\n' | ' ' | '\t' | '\r'))
+                {
+                    self.bump();
+                }
+
+                let word = &self.src[start..self.pos];
+                match word {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    _ => word
+                        .replace('_', "")
+                        .parse()
+                        .map(Value::Number)
+                        .map_err(|_| self.error(&format!("unexpected value `{word}`"))),
+                }
+            }
+        }
+    }
+}
+
+pub fn parse(src: &str) -> Result<Document, String> {
+    let mut doc = Document::default();
+    let mut cursor = Cursor {
+        src,
+        pos: 0,
+        line: 1,
+    };
+
+    enum Target {
+        Root,
+        Table(String),
+        Array(String),
+    }
+    let mut target = Target::Root;
+
+    loop {
+        cursor.skip(true);
+        let Some(c) = cursor.peek() else {
+            break;
+        };
+
+        if c == '[' {
+            cursor.bump();
+            let array = cursor.peek() == Some('[');
+            if array {
+                cursor.bump();
+            }
+
+            let name = cursor.key()?;
+            cursor.skip(false);
+            let close = if array { "]]" } else { "]" };
+            if !cursor.src[cursor.pos..].starts_with(close) {
+                return Err(cursor.error(&format!("expected `{close}`")));
+            }
+            cursor.pos += close.len();
+            cursor.end_of_line()?;
+
+            target = if array {
+                doc.arrays
+                    .entry(name.clone())
+                    .or_default()
+                    .push(Table::new());
+                Target::Array(name)
+            } else {
+                doc.tables.entry(name.clone()).or_default();
+                Target::Table(name)
+            };
+            continue;
+        }
+
+        let key = cursor.key()?;
+        cursor.skip(false);
+        if cursor.bump() != Some('=') {
+            return Err(cursor.error("expected `=`"));
+        }
+        let value = cursor.value()?;
+        cursor.end_of_line()?;
+
+        let table = match &target {
+            Target::Root => &mut doc.root,
+            Target::Table(name) => doc.tables.get_mut(name).expect("table"),
+            Target::Array(name) => doc
+                .arrays
+                .get_mut(name)
+                .and_then(|tables| tables.last_mut())
+                .expect("array table"),
+        };
+
+        if table.insert(key.clone(), value).is_some() {
+            return Err(cursor.error(&format!("duplicate key `{key}`")));
+        }
+    }
+
+    Ok(doc)
+}
@@ -0,0 +1,88 @@
+use std::{
+    fs,
+    path::Path as FsPath,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use scavenger::{writer::write_svg, Float};
+
+use super::{load_path, parse_size};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Options<'a> {
+    input: &'a str,
+    fit: Option<(Float, Float)>,
+    out: Option<&'a str>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options<'_>, String> {
+    let mut input = None;
+    let mut fit = None;
+    let mut out = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fit" => {
+                let size = args.next().ok_or("--fit expects <width>x<height>")?;
+                fit = Some(parse_size(size)?);
+            }
+            "--out" => out = Some(args.next().ok_or("--out expects a file")?.as_str()),
+            _ if input.is_none() => input = Some(arg.as_str()),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    Ok(Options {
+        input: input.ok_or("usage: scavenger watch <input> [--fit WxH] [--out file]")?,
+        fit,
+        out,
+    })
+}
+
+fn build(options: &Options) -> Result<String, String> {
+    let mut path = load_path(FsPath::new(options.input))?;
+
+    let (width, height) = match options.fit {
+        Some((width, height)) => {
+            path.fit(width, height);
+            (width, height)
+        }
+        None => path.bb(),
+    };
+
+    Ok(write_svg(&[path], width, height))
+}
+
+fn modified(file: &str) -> Option<SystemTime> {
+    fs::metadata(file).and_then(|m| m.modified()).ok()
+}
+
+// rebuild whenever the input's modification time changes, until interrupted
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_args(args)?;
+    let mut last = None;
+
+    loop {
+        let current = modified(options.input);
+
+        if current.is_some() && current != last {
+            last = current;
+
+            match build(&options) {
+                Ok(svg) => match options.out {
+                    Some(out) => match fs::write(out, svg) {
+                        Ok(()) => eprintln!("wrote {out}"),
+                        Err(e) => eprintln!("error: {out}: {e}"),
+                    },
+                    None => print!("{svg}"),
+                },
+                Err(e) => eprintln!("error: {e}"),
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
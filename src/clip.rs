@@ -0,0 +1,132 @@
+use crate::{
+    offset::signed_area,
+    path::Path,
+    scanline::FillRule,
+    segment::{push_segments, Segment},
+    slice::split_at_line,
+    Float, Point,
+};
+
+// a filled area, such as the geometry of an svg <clipPath>
+pub struct Region {
+    pub path: Path,
+    pub fill_rule: FillRule,
+}
+
+impl Clone for Region {
+    fn clone(&self) -> Region {
+        Region {
+            path: self.path.fork(),
+            fill_rule: self.fill_rule,
+        }
+    }
+}
+
+// everything on the left of the line from `from` to `to` (the inside of a ring with positive
+// signed area), the line itself included
+#[derive(Clone, Copy)]
+struct Plane {
+    from: Point,
+    to: Point,
+}
+
+impl Plane {
+    fn contains(&self, p: Point) -> bool {
+        let (dx, dy) = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        dx * (p.1 - self.from.1) - dy * (p.0 - self.from.0) >= 0.0
+    }
+}
+
+// the runs of `segments` inside the plane. a closed contour stays one closed contour, wherever it
+// leaves the plane it is carried along the line to where it comes back
+fn clip(runs: Vec<Vec<Segment>>, closed: bool, plane: Plane) -> Vec<Vec<Segment>> {
+    let mut out = Vec::new();
+    let mut pieces = Vec::new();
+
+    for run in runs {
+        let mut kept: Vec<Segment> = Vec::new();
+
+        for seg in &run {
+            pieces.clear();
+            split_at_line(seg, plane.from, plane.to, &mut pieces);
+
+            for piece in &pieces {
+                if !plane.contains(piece.eval(0.5)) {
+                    if !closed && !kept.is_empty() {
+                        out.push(std::mem::take(&mut kept));
+                    }
+                    continue;
+                }
+
+                if let Some(last) = kept.last() {
+                    if closed && last.to() != piece.from() {
+                        kept.push(Segment::Line(last.to(), piece.from()));
+                    }
+                }
+                kept.push(*piece);
+            }
+        }
+
+        if let (true, Some(first), Some(last)) = (closed, kept.first(), kept.last()) {
+            if last.to() != first.from() {
+                kept.push(Segment::Line(last.to(), first.from()));
+            }
+        }
+        if !kept.is_empty() {
+            out.push(kept);
+        }
+    }
+
+    out
+}
+
+// the runs of a subpath's `segments` inside the convex `polygon` (positive signed area). closed
+// contours are closed again along the polygon's edges, those only grazing it are dropped
+pub(crate) fn clip_convex(
+    segments: &[Segment],
+    closed: bool,
+    polygon: &[Point],
+) -> Vec<Vec<Segment>> {
+    let mut runs = vec![segments.to_vec()];
+    for (i, &from) in polygon.iter().enumerate() {
+        let to = polygon[(i + 1) % polygon.len()];
+        runs = clip(runs, closed, Plane { from, to });
+    }
+
+    if closed {
+        runs.retain(|run| {
+            let outline: Vec<Point> = run.iter().flat_map(|s| [s.from(), s.eval(0.5)]).collect();
+            signed_area(&outline).abs() > Float::EPSILON
+        });
+    }
+    runs
+}
+
+impl Path {
+    // the part of the path inside `region`. open subpaths are cut into the runs inside it, closed
+    // ones are closed again along its outline so they still fill. the region is cut into convex
+    // pieces (holes are kept, nested outlines count by even/odd depth) and the path clipped to
+    // each, so fills come back as one contour per piece that meet edge to edge
+    pub fn clip_to(&self, region: &Region) -> Path {
+        let tolerance = (region.path.bb.0.max(region.path.bb.1) * 1e-3).max(1e-3);
+        let pieces = region.path.to_collider(tolerance);
+
+        let mut commands = Vec::new();
+        for sp in self.subpaths() {
+            let segments: Vec<Segment> = sp.segments().collect();
+            if segments.is_empty() {
+                continue;
+            }
+
+            for piece in &pieces {
+                for run in clip_convex(&segments, sp.closed, &piece.points) {
+                    push_segments(&run, sp.closed, &mut commands);
+                }
+            }
+        }
+
+        let mut path = self.fork();
+        path.set_commands(commands);
+        path
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{
+    fill::fill_rings,
+    offset::{ring_contains, signed_area},
+    path::Path,
+    Float, Point,
+};
+use std::collections::HashMap;
+
+// box2d's b2_maxPolygonVertices, rapier takes any count but small hulls are cheaper there too
+const MAX_VERTICES: usize = 8;
+
+// a convex piece of a collider, counter-clockwise with y up (positive signed area) as physics
+// engines expect
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvexPolygon {
+    pub points: Vec<Point>,
+}
+
+#[inline(always)]
+fn cross(o: Point, a: Point, b: Point) -> Float {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+// a and b strictly cross c and d
+fn crosses(a: Point, b: Point, c: Point, d: Point) -> bool {
+    cross(c, d, a) * cross(c, d, b) < 0.0 && cross(a, b, c) * cross(a, b, d) < 0.0
+}
+
+// drop repeated points and points on a straight line between their neighbours
+fn clean(ring: &[Point]) -> Vec<Point> {
+    let mut out: Vec<Point> = Vec::with_capacity(ring.len());
+    for &p in ring {
+        if out.last() != Some(&p) {
+            out.push(p);
+        }
+    }
+    while out.len() > 1 && out.first() == out.last() {
+        out.pop();
+    }
+
+    let mut changed = true;
+    while changed && out.len() > 2 {
+        changed = false;
+        let mut i = 0;
+        while i < out.len() && out.len() > 2 {
+            let n = out.len();
+            let (a, b, c) = (out[(i + n - 1) % n], out[i], out[(i + 1) % n]);
+            if cross(a, b, c).abs() <= Float::EPSILON * (a.0 - c.0).hypot(a.1 - c.1).powi(2) {
+                out.remove(i);
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+// outlines with the holes directly inside them, outlines turned positive and holes negative
+fn group(rings: Vec<Vec<Point>>) -> Vec<(Vec<Point>, Vec<Vec<Point>>)> {
+    let depth: Vec<usize> = (0..rings.len())
+        .map(|i| {
+            (0..rings.len())
+                .filter(|&j| j != i && ring_contains(&rings[j], rings[i][0]))
+                .count()
+        })
+        .collect();
+
+    let mut shapes: Vec<(usize, Vec<Point>, Vec<Vec<Point>>)> = Vec::new();
+    for (i, ring) in rings.iter().enumerate() {
+        if depth[i] % 2 == 1 {
+            continue;
+        }
+        let mut ring = ring.clone();
+        if signed_area(&ring) < 0.0 {
+            ring.reverse();
+        }
+        shapes.push((i, ring, Vec::new()));
+    }
+
+    for (i, ring) in rings.iter().enumerate() {
+        if depth[i] % 2 == 1 {
+            let parent = shapes.iter_mut().find(|(j, outline, _)| {
+                depth[*j] + 1 == depth[i] && ring_contains(outline, ring[0])
+            });
+            if let Some((_, _, holes)) = parent {
+                let mut ring = ring.clone();
+                if signed_area(&ring) > 0.0 {
+                    ring.reverse();
+                }
+                holes.push(ring);
+            }
+        }
+    }
+
+    shapes
+        .into_iter()
+        .map(|(_, outline, holes)| (outline, holes))
+        .collect()
+}
+
+// cut from each hole to a visible outline vertex, giving one ring that runs around the hole and
+// back along the cut
+fn bridge(mut outline: Vec<Point>, mut holes: Vec<Vec<Point>>) -> Vec<Point> {
+    let rightmost = |ring: &[Point]| {
+        (0..ring.len())
+            .max_by(|&a, &b| ring[a].0.total_cmp(&ring[b].0))
+            .unwrap_or(0)
+    };
+    holes.sort_by(|a, b| b[rightmost(b)].0.total_cmp(&a[rightmost(a)].0));
+
+    for h in 0..holes.len() {
+        let hole = &holes[h];
+        let m = rightmost(hole);
+        let from = hole[m];
+
+        let blocked = |to: Point| {
+            let mut rings = std::iter::once(&outline).chain(&holes[h..]);
+            rings.any(|ring| {
+                (0..ring.len()).any(|i| {
+                    let (c, d) = (ring[i], ring[(i + 1) % ring.len()]);
+                    crosses(from, to, c, d)
+                })
+            })
+        };
+
+        let mut candidates: Vec<usize> = (0..outline.len()).collect();
+        candidates.sort_by(|&a, &b| {
+            let da = (outline[a].0 - from.0).hypot(outline[a].1 - from.1);
+            let db = (outline[b].0 - from.0).hypot(outline[b].1 - from.1);
+            da.total_cmp(&db)
+        });
+        let Some(v) = candidates.into_iter().find(|&v| !blocked(outline[v])) else {
+            continue;
+        };
+
+        let mut joined = Vec::with_capacity(outline.len() + hole.len() + 2);
+        joined.extend_from_slice(&outline[..=v]);
+        joined.extend_from_slice(&hole[m..]);
+        joined.extend_from_slice(&hole[..=m]);
+        joined.extend_from_slice(&outline[v..]);
+        outline = joined;
+    }
+
+    outline
+}
+
+// triangles of a positive ring as indices into it
+fn ear_clip(ring: &[Point]) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::new();
+    let mut left: Vec<usize> = (0..ring.len()).collect();
+
+    while left.len() > 3 {
+        let n = left.len();
+        let corner = |i: usize| (left[(i + n - 1) % n], left[i], left[(i + 1) % n]);
+
+        let is_ear = |i: usize| {
+            let (a, b, c) = corner(i);
+            let (pa, pb, pc) = (ring[a], ring[b], ring[c]);
+            if cross(pa, pb, pc) <= 0.0 {
+                return false;
+            }
+            // only reflex corners can poke into an ear. points the cut visits twice sit on top
+            // of the ear's own corners
+            (0..n).all(|j| {
+                let (_, q, _) = corner(j);
+                let p = ring[q];
+                p == pa
+                    || p == pb
+                    || p == pc
+                    || cross(ring[corner(j).0], p, ring[corner(j).2]) > 0.0
+                    || !in_triangle(p, pa, pb, pc)
+            })
+        };
+
+        // degenerate leftovers have no proper ear, cut the least bad corner to keep going
+        let i = (0..n).find(|&i| is_ear(i)).unwrap_or_else(|| {
+            (0..n)
+                .max_by(|&x, &y| {
+                    let (a, b, c) = corner(x);
+                    let (d, e, f) = corner(y);
+                    cross(ring[a], ring[b], ring[c]).total_cmp(&cross(ring[d], ring[e], ring[f]))
+                })
+                .unwrap_or(0)
+        });
+
+        let (a, b, c) = corner(i);
+        if cross(ring[a], ring[b], ring[c]) > 0.0 {
+            triangles.push([a, b, c]);
+        }
+        left.remove(i);
+    }
+
+    if let [a, b, c] = left[..] {
+        if cross(ring[a], ring[b], ring[c]) > 0.0 {
+            triangles.push([a, b, c]);
+        }
+    }
+
+    triangles
+}
+
+// hertel-mehlhorn: merge neighbouring pieces across their shared diagonal while they stay
+// convex and small enough
+fn merge(ring: &[Point], triangles: Vec<[usize; 3]>) -> Vec<Vec<usize>> {
+    let mut pieces: Vec<Vec<usize>> = triangles.iter().map(|t| t.to_vec()).collect();
+    let mut owner: Vec<usize> = (0..pieces.len()).collect();
+
+    let mut diagonals: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (t, tri) in triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (tri[k], tri[(k + 1) % 3]);
+            diagonals.entry((a.min(b), a.max(b))).or_default().push(t);
+        }
+    }
+    let mut diagonals: Vec<((usize, usize), Vec<usize>)> = diagonals
+        .into_iter()
+        .filter(|(_, tris)| tris.len() == 2)
+        .collect();
+    diagonals.sort();
+
+    let find = |owner: &[usize], mut t: usize| {
+        while owner[t] != t {
+            t = owner[t];
+        }
+        t
+    };
+
+    for ((u, v), tris) in diagonals {
+        let (a, b) = (find(&owner, tris[0]), find(&owner, tris[1]));
+        if a == b || pieces[a].len() + pieces[b].len() - 2 > MAX_VERTICES {
+            continue;
+        }
+
+        // turn a to run v .. u and b to run u .. v, then splice them
+        let rotate = |piece: &[usize], first: usize| {
+            let at = piece.iter().position(|&i| i == first).unwrap_or(0);
+            let mut out = piece[at..].to_vec();
+            out.extend_from_slice(&piece[..at]);
+            out
+        };
+        let (from_a, from_b) = if rotate(&pieces[a], u)[1] == v {
+            (rotate(&pieces[a], v), rotate(&pieces[b], u))
+        } else {
+            (rotate(&pieces[a], u), rotate(&pieces[b], v))
+        };
+
+        let mut merged = from_a;
+        merged.extend_from_slice(&from_b[1..from_b.len() - 1]);
+
+        let n = merged.len();
+        let convex = (0..n).all(|i| {
+            cross(
+                ring[merged[(i + n - 1) % n]],
+                ring[merged[i]],
+                ring[merged[(i + 1) % n]],
+            ) >= 0.0
+        });
+        if convex {
+            pieces[a] = merged;
+            pieces[b].clear();
+            owner[b] = a;
+        }
+    }
+
+    pieces.retain(|piece| piece.len() > 2);
+    pieces
+}
+
+impl Path {
+    // the filled (non-zero) region flattened within `tolerance` and cut into convex polygons of
+    // at most 8 points, ready to become box2d or rapier shapes. holes are kept, overlapping
+    // outlines each get their own pieces
+    pub fn to_collider(&self, tolerance: Float) -> Vec<ConvexPolygon> {
+        let rings: Vec<Vec<Point>> = fill_rings(self, tolerance)
+            .iter()
+            .map(|ring| clean(ring))
+            .filter(|ring| ring.len() > 2 && signed_area(ring) != 0.0)
+            .collect();
+
+        let mut out = Vec::new();
+        for (outline, holes) in group(rings) {
+            let ring = bridge(outline, holes);
+            let triangles = ear_clip(&ring);
+
+            for piece in merge(&ring, triangles) {
+                out.push(ConvexPolygon {
+                    points: clean(&piece.iter().map(|&i| ring[i]).collect::<Vec<_>>()),
+                });
+            }
+        }
+
+        out
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    path::Path,
+    segment::{subpaths, Segment},
+    transform::Transform,
+    Command, Float,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Usvg,
+    Skia,
+    CoreGraphics,
+    Pdf,
+}
+
+// something in a path the target has no direct equivalent for, `index` is the command's position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Issue {
+    // S, every target wants the first control point spelled out
+    SmoothCubic { index: usize },
+    // T, same as S
+    SmoothQuadratic { index: usize },
+    // Q, pdf only draws cubics
+    Quadratic { index: usize },
+    // A, kept by `Parser::preserve_arcs`. only skia takes svg style arcs
+    Arc { index: usize },
+    // nan or infinite coordinates, rejected everywhere
+    NonFinite { index: usize },
+    // a coordinate past what the target represents exactly
+    HugeCoordinate { index: usize, value: Float },
+}
+
+impl Target {
+    // largest coordinate magnitude the target takes without loss
+    fn coordinate_limit(self) -> Option<Float> {
+        match self {
+            // f32 paths, past 2^24 not every integer is representable
+            Target::Usvg | Target::Skia => Some(16_777_216.0),
+            // CGFloat is an f64 on every current platform
+            Target::CoreGraphics => None,
+            // the implementation limit for reals in pdf content streams
+            Target::Pdf => Some(32_767.0),
+        }
+    }
+}
+
+impl Path {
+    // everything `make_compatible` would have to change for `target`
+    pub fn compatibility_report(&self, target: Target) -> Vec<Issue> {
+        let mut issues = Vec::new();
+
+        for (index, cmd) in self.commands.iter().enumerate() {
+            match cmd {
+                Command::SmoothCurveTo { .. } => issues.push(Issue::SmoothCubic { index }),
+                Command::SmoothQuadraticBezierCurveTo { .. } => {
+                    issues.push(Issue::SmoothQuadratic { index })
+                }
+                Command::QuadraticBezierCurveTo { .. } if target == Target::Pdf => {
+                    issues.push(Issue::Quadratic { index })
+                }
+                Command::EllipticalArc { .. } if target != Target::Skia => {
+                    issues.push(Issue::Arc { index })
+                }
+                _ => {}
+            }
+
+            if cmd.points().any(|(x, y)| !x.is_finite() || !y.is_finite()) {
+                issues.push(Issue::NonFinite { index });
+                continue;
+            }
+
+            if let Some(limit) = target.coordinate_limit() {
+                let value = cmd
+                    .points()
+                    .map(|(x, y)| x.abs().max(y.abs()))
+                    .fold(0.0, Float::max);
+                if value > limit {
+                    issues.push(Issue::HugeCoordinate { index, value });
+                }
+            }
+        }
+
+        issues
+    }
+
+    // rewrite the path so `compatibility_report(target)` comes back empty. smooth curves get
+    // their control points spelled out, quadratics become cubics for pdf, arcs become cubics
+    // everywhere but skia, subpaths with non-finite coordinates are dropped and a path with huge
+    // coordinates is scaled about the origin until it fits the target's limit
+    pub fn make_compatible(&mut self, target: Target) {
+        let finite = |(x, y): (Float, Float)| x.is_finite() && y.is_finite();
+
+        // a subpath missing a point has no shape left to keep, and dropping only the command
+        // would run the next subpath into it
+        let mut kept = Vec::with_capacity(self.commands.len());
+        let mut dropped = false;
+        for sp in subpaths(&self.commands) {
+            if !finite(sp.start) || sp.commands.iter().any(|cmd| !cmd.points().all(finite)) {
+                dropped = true;
+                continue;
+            }
+            // after a close the subpath starts where the dropped one did
+            if dropped && !matches!(sp.commands.first(), Some(Command::MoveTo { .. })) {
+                kept.push(Command::MoveTo {
+                    x: sp.start.0,
+                    y: sp.start.1,
+                });
+            }
+            dropped = false;
+            kept.extend_from_slice(sp.commands);
+        }
+
+        let mut commands = Vec::with_capacity(kept.len());
+        let mut pen = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+
+        for cmd in kept {
+            let cmd = match cmd {
+                Command::MoveTo { x, y } => {
+                    start = (x, y);
+                    pen = start;
+                    commands.push(cmd);
+                    continue;
+                }
+                Command::ClosePath => {
+                    pen = start;
+                    commands.push(cmd);
+                    continue;
+                }
+                Command::EllipticalArc { .. } if target == Target::Skia => {
+                    pen = cmd.points().next().unwrap_or(pen);
+                    commands.push(cmd);
+                    continue;
+                }
+                cmd => cmd,
+            };
+
+            for seg in Segment::pieces(pen, &cmd) {
+                let seg = match seg {
+                    Segment::Quad(..) if target == Target::Pdf => seg.to_cubic(),
+                    seg => seg,
+                };
+
+                pen = seg.to();
+                commands.push(seg.to_command());
+            }
+        }
+
+        if let Some(limit) = target.coordinate_limit() {
+            let largest = commands
+                .iter()
+                .flat_map(|cmd| cmd.points())
+                .map(|(x, y)| x.abs().max(y.abs()))
+                .fold(0.0, Float::max);
+
+            if largest > limit {
+                let scale = Transform::scale(limit / largest, limit / largest);
+                // scaling can leave the largest coordinate past the limit by a rounding error
+                let clamp =
+                    |(x, y): (Float, Float)| (x.clamp(-limit, limit), y.clamp(-limit, limit));
+                for cmd in commands.iter_mut() {
+                    *cmd = cmd.transform(&scale).map_points(clamp);
+                }
+            }
+        }
+
+        self.set_commands(commands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_path_str, Parser};
+
+    fn compatible(commands: Vec<Command>, target: Target) -> Path {
+        let mut path = Path::new(commands);
+        path.make_compatible(target);
+        assert_eq!(path.compatibility_report(target), []);
+        path
+    }
+
+    #[test]
+    fn huge_paths_are_scaled_into_range() {
+        let commands = parse_path_str("M0 0 L65534 0 L0 100 Z").unwrap();
+        let path = compatible(commands, Target::Pdf);
+        assert_eq!(path.to_d(), "M0 0 L32767 0 L0 50 Z");
+
+        // arcs kept for skia scale their radii along
+        let commands = Parser::new("M0 0 A 16777216 16777216 0 0 1 33554432 0")
+            .preserve_arcs(true)
+            .parse()
+            .unwrap();
+        let path = compatible(commands, Target::Skia);
+        assert_eq!(path.to_d(), "M0 0 A8388608 8388608 0 0 1 16777216 0");
+    }
+
+    #[test]
+    fn subpaths_with_non_finite_points_are_dropped() {
+        let nan = Float::NAN;
+        let commands = vec![
+            Command::MoveTo { x: 0.0, y: 0.0 },
+            Command::LineTo { x: nan, y: 1.0 },
+            Command::MoveTo { x: 5.0, y: 5.0 },
+            Command::LineTo { x: 6.0, y: 6.0 },
+            Command::LineTo { x: 7.0, y: 5.0 },
+            Command::ClosePath,
+            Command::LineTo {
+                x: 1.0,
+                y: Float::INFINITY,
+            },
+            Command::ClosePath,
+            Command::LineTo { x: 2.0, y: 2.0 },
+        ];
+        let path = compatible(commands, Target::Skia);
+        assert_eq!(path.to_d(), "M5 5 L6 6 L7 5 Z M5 5 L2 2");
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    path::Path,
+    progressive::{arity, command, opcode, read_f32, read_varint, varint},
+    to_f32, Command, Float, Point,
+};
+
+const MAGIC: [u8; 4] = *b"SCZ\x01";
+// set in the flags byte when the body is zstd compressed
+#[cfg(feature = "zstd")]
+const ZSTD: u8 = 1;
+// opcodes below this are the ones `progressive` uses, arcs carry their flags in the bits above
+const ARC: u8 = 7;
+pub(crate) const LARGE_ARC: u8 = 1 << 3;
+pub(crate) const SWEEP: u8 = 1 << 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressError {
+    // not written by `Path::compress`, or needs the `zstd` feature to be read
+    Format,
+    // the data ends early
+    Truncated,
+}
+
+// the opcode byte stored for `cmd`
+pub(crate) fn command_opcode(cmd: &Command) -> u8 {
+    match *cmd {
+        Command::EllipticalArc {
+            large_arc, sweep, ..
+        } => ARC | if large_arc { LARGE_ARC } else { 0 } | if sweep { SWEEP } else { 0 },
+        ref cmd => opcode(cmd),
+    }
+}
+
+// whether `op` is an arc opcode, with nothing set but its flags
+pub(crate) fn is_arc(op: u8) -> bool {
+    op & 7 == ARC && op & !(ARC | LARGE_ARC | SWEEP) == 0
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+// the body after the header: the quantization step, one opcode byte per command, then every
+// number as a zigzag varint. points are the difference from the point before them, radii and
+// rotations are written as they are
+fn encode(commands: &[Command], precision: Float) -> Vec<u8> {
+    // stored as an f32, quantize with the step a reader will get back
+    let step = to_f32(precision * 2.0).max(f32::MIN_POSITIVE);
+    let mut out = Vec::with_capacity(commands.len() * 6);
+    out.extend(step.to_le_bytes());
+
+    let step = Float::from(step);
+    let quantize = |v: Float| (v / step).round() as i64;
+    varint(&mut out, commands.len() as u64);
+    out.extend(commands.iter().map(command_opcode));
+
+    let mut last = (0, 0);
+    for cmd in commands {
+        if let Command::EllipticalArc {
+            rx,
+            ry,
+            x_axis_rotation,
+            ..
+        } = *cmd
+        {
+            for n in [rx, ry, x_axis_rotation] {
+                varint(&mut out, zigzag(quantize(n)));
+            }
+        }
+        for (x, y) in cmd.points() {
+            let q = (quantize(x), quantize(y));
+            varint(&mut out, zigzag(q.0.wrapping_sub(last.0)));
+            varint(&mut out, zigzag(q.1.wrapping_sub(last.1)));
+            last = q;
+        }
+    }
+
+    out
+}
+
+fn decode(body: &[u8]) -> Result<Vec<Command>, CompressError> {
+    let mut at = 0;
+    let step = read_f32(body, &mut at).ok_or(CompressError::Truncated)?;
+    let count = read_varint(body, &mut at).ok_or(CompressError::Truncated)? as usize;
+    let ops = body
+        .get(at..at.saturating_add(count))
+        .ok_or(CompressError::Truncated)?;
+    at += count;
+
+    let mut number = || {
+        read_varint(body, &mut at)
+            .map(unzigzag)
+            .ok_or(CompressError::Truncated)
+    };
+
+    let mut commands = Vec::with_capacity(count);
+    let mut last = (0i64, 0i64);
+    let mut points: Vec<Point> = Vec::with_capacity(3);
+    for &op in ops {
+        let mut arc = None;
+        let arity = if is_arc(op) {
+            arc = Some((number()?, number()?, number()?));
+            1
+        } else {
+            arity(op).ok_or(CompressError::Format)?
+        };
+
+        points.clear();
+        for _ in 0..arity {
+            last = (
+                last.0.wrapping_add(number()?),
+                last.1.wrapping_add(number()?),
+            );
+            points.push((last.0 as Float * step, last.1 as Float * step));
+        }
+
+        commands.push(match arc {
+            Some((rx, ry, rotation)) => Command::EllipticalArc {
+                rx: rx as Float * step,
+                ry: ry as Float * step,
+                x_axis_rotation: rotation as Float * step,
+                large_arc: op & LARGE_ARC != 0,
+                sweep: op & SWEEP != 0,
+                x: points[0].0,
+                y: points[0].1,
+            },
+            None => command(op, &points),
+        });
+    }
+
+    Ok(commands)
+}
+
+impl Path {
+    // the commands in a compact form for storage, every number within `precision` of where it
+    // was. arcs are kept as arcs. numbers that are not finite do not survive the trip. with the
+    // `zstd` feature the result is also run through zstd, which `decompress` needs to read it back
+    pub fn compress(&self, precision: Float) -> Vec<u8> {
+        let body = encode(&self.commands, precision);
+        let mut out = MAGIC.to_vec();
+
+        #[cfg(feature = "zstd")]
+        if let Ok(packed) = zstd::encode_all(body.as_slice(), 3) {
+            out.push(ZSTD);
+            out.extend(packed);
+            return out;
+        }
+
+        out.push(0);
+        out.extend(body);
+        out
+    }
+
+    // a path back from `compress`
+    pub fn decompress(bytes: &[u8]) -> Result<Path, CompressError> {
+        let header = MAGIC.len() + 1;
+        if bytes.len() < header {
+            return Err(if MAGIC.starts_with(bytes) {
+                CompressError::Truncated
+            } else {
+                CompressError::Format
+            });
+        }
+        if bytes[..MAGIC.len()] != MAGIC {
+            return Err(CompressError::Format);
+        }
+
+        let body = &bytes[header..];
+        let commands = match bytes[MAGIC.len()] {
+            0 => decode(body)?,
+            #[cfg(feature = "zstd")]
+            ZSTD => decode(&zstd::decode_all(body).map_err(|_| CompressError::Format)?)?,
+            _ => return Err(CompressError::Format),
+        };
+
+        Ok(Path::new(commands))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, Rng, SplitMix64};
+
+    // every number a command holds, arcs with their radii and rotation first
+    fn numbers(cmd: &Command) -> Vec<Float> {
+        let mut out = Vec::new();
+        if let Command::EllipticalArc {
+            rx,
+            ry,
+            x_axis_rotation,
+            ..
+        } = *cmd
+        {
+            out.extend([rx, ry, x_axis_rotation]);
+        }
+        out.extend(cmd.points().flat_map(|(x, y)| [x, y]));
+        out
+    }
+
+    #[test]
+    fn round_trips_within_precision() {
+        let source = "M10 10 C 20 -5 37.25 40 50 12.5 S 80 3 91 77 Q 3 4 5 6 T 0 .5 Z \
+                      A 12.5 7 30 1 0 -40 2 a 3 3 0 0 1 1000 0 L 3 3";
+        let original = Parser::new(source).preserve_arcs(true).parse().unwrap();
+
+        let mut rng = SplitMix64::new(0xc0);
+        for precision in [1.0, 0.01, 0.0001] {
+            // far from the origin too, where the deltas carry the precision
+            let offset = rng.below(1_000_000) as Float;
+            let original: Vec<_> = original
+                .iter()
+                .map(|cmd| cmd.translate(offset, -offset))
+                .collect();
+            let path = Path::new(original.clone());
+            let read = Path::decompress(&path.compress(precision)).unwrap();
+
+            assert_eq!(read.commands().len(), original.len());
+            for (a, b) in read.commands().iter().zip(&original) {
+                assert_eq!(command_opcode(a), command_opcode(b));
+                for (x, y) in numbers(a).into_iter().zip(numbers(b)) {
+                    // an f32 holds the far coordinates only to its own precision
+                    let slack = y.abs() * 1e-7 * Float::from(u8::from(cfg!(not(feature = "f64"))));
+                    assert!(
+                        (x - y).abs() <= precision + slack,
+                        "{a:?} read back, {b:?} written"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_what_it_did_not_write() {
+        let bytes = Path::new(
+            Parser::new("M0 0 L1 1 A 2 2 0 0 1 4 4")
+                .preserve_arcs(true)
+                .parse()
+                .unwrap(),
+        )
+        .compress(0.1);
+
+        assert_eq!(Path::decompress(&[]).err(), Some(CompressError::Truncated));
+        assert_eq!(
+            Path::decompress(b"SC").err(),
+            Some(CompressError::Truncated)
+        );
+        assert_eq!(Path::decompress(b"no").err(), Some(CompressError::Format));
+        assert_eq!(
+            Path::decompress(b"SCP\x01 progressive").err(),
+            Some(CompressError::Format)
+        );
+        // a flag byte nothing is written with
+        let mut bad = bytes.clone();
+        bad[MAGIC.len()] = 0x80;
+        assert_eq!(Path::decompress(&bad).err(), Some(CompressError::Format));
+
+        // every cut short body is an error, never a shorter path
+        for end in MAGIC.len() + 1..bytes.len() {
+            assert!(Path::decompress(&bytes[..end]).is_err(), "{end}");
+        }
+
+        // garbage after a valid header never panics
+        let mut rng = SplitMix64::new(0xbad);
+        for _ in 0..1000 {
+            let mut garbage = bytes[..MAGIC.len() + 1].to_vec();
+            garbage.extend((0..rng.below(64)).map(|_| rng.below(256) as u8));
+            let _ = Path::decompress(&garbage);
+        }
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn reports_bad_opcodes_and_truncation() {
+        let bytes = Path::new(Parser::new("M0 0 L1 1").parse().unwrap()).compress(0.1);
+        let body = MAGIC.len() + 1;
+        for end in body..bytes.len() {
+            assert_eq!(
+                Path::decompress(&bytes[..end]).err(),
+                Some(CompressError::Truncated)
+            );
+        }
+
+        // the opcodes follow the f32 step and the one byte count
+        let mut bad = bytes.clone();
+        bad[body + 5] = 0x3f;
+        assert_eq!(Path::decompress(&bad).err(), Some(CompressError::Format));
+    }
+}
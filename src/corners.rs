@@ -0,0 +1,88 @@
+use crate::{
+    flatten::segment_length, join::tangent, path::Path, segment::Segment, Command, Float, Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corner {
+    // the command drawing the segment that runs into the corner, a ClosePath for closing lines
+    pub index: usize,
+    pub point: Point,
+    // distance along the path, measured like `Path::length`
+    pub length: Float,
+    // how far the direction turns, in degrees: 0 runs straight on and 180 doubles back
+    pub angle: Float,
+}
+
+fn turn(a: Point, b: Point) -> Float {
+    let dot = (a.0 * b.0 + a.1 * b.1).clamp(-1.0, 1.0);
+    dot.acos().to_degrees()
+}
+
+impl Path {
+    // every join between segments where the direction turns by more than `angle_threshold`
+    // degrees, in drawing order. the start of a closed subpath counts as a join, the ends of an
+    // open one do not
+    pub fn detect_corners(&self, angle_threshold: Float) -> Vec<Corner> {
+        let tolerance = (self.bb.0.max(self.bb.1) * 1e-4).max(1e-4);
+        let mut corners = Vec::new();
+
+        // segments of the current subpath with the command that draws each and where it starts
+        let mut run: Vec<(usize, Segment, Float)> = Vec::new();
+        let mut walked = 0.0;
+        let mut start = (0.0, 0.0);
+        let mut pen = start;
+
+        let mut finish = |run: &mut Vec<(usize, Segment, Float)>, closed: bool, walked: Float| {
+            let joins = run.windows(2).map(|w| (w[0], w[1].1, w[1].2));
+            let wrap = closed
+                .then(|| run.last().zip(run.first()))
+                .flatten()
+                .map(|(&last, first)| (last, first.1, walked));
+
+            for ((index, seg, _), next, length) in joins.chain(wrap) {
+                let (Some(out), Some(into)) = (tangent(&seg, 1.0), tangent(&next, 0.0)) else {
+                    continue;
+                };
+
+                let angle = turn(out, into);
+                if angle > angle_threshold {
+                    corners.push(Corner {
+                        index,
+                        point: seg.to(),
+                        length,
+                        angle,
+                    });
+                }
+            }
+            run.clear();
+        };
+
+        for (i, cmd) in self.commands.iter().enumerate() {
+            match *cmd {
+                Command::MoveTo { x, y } => {
+                    finish(&mut run, false, walked);
+                    start = (x, y);
+                    pen = start;
+                }
+                Command::ClosePath => {
+                    if pen != start {
+                        run.push((i, Segment::Line(pen, start), walked));
+                        walked += segment_length(&Segment::Line(pen, start), tolerance);
+                    }
+                    finish(&mut run, true, walked);
+                    pen = start;
+                }
+                ref cmd => {
+                    for seg in Segment::pieces(pen, cmd) {
+                        run.push((i, seg, walked));
+                        walked += segment_length(&seg, tolerance);
+                        pen = seg.to();
+                    }
+                }
+            }
+        }
+        finish(&mut run, false, walked);
+
+        corners
+    }
+}
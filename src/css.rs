@@ -0,0 +1,486 @@
+use crate::{path::Path, units::Unit, Command, FillRule, Float, ParseError, Parser};
+
+// bezier handle length for a quarter ellipse
+const KAPPA: Float = 0.552_284_8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssError {
+    // unexpected input at this byte offset
+    Syntax { offset: usize },
+    // a relative unit such as em or vw, at this byte offset
+    Unit { offset: usize },
+    // the string inside path() is not valid path data
+    Path(ParseError),
+}
+
+// a css shape value resolved against its reference box
+pub struct Shape {
+    pub path: Path,
+    pub fill_rule: FillRule,
+}
+
+#[derive(Clone, Copy)]
+enum Length {
+    Px(Float),
+    Percent(Float),
+}
+
+impl Length {
+    fn resolve(self, basis: Float) -> Float {
+        match self {
+            Length::Px(v) => v,
+            Length::Percent(p) => p / 100.0 * basis,
+        }
+    }
+}
+
+struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn error(&self) -> CssError {
+        CssError::Syntax { offset: self.pos }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn skip(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip();
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), CssError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn peek_ident(&mut self) -> &'a str {
+        self.skip();
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphabetic() || c == '-'))
+            .unwrap_or(rest.len());
+        // `-5px` is a number, not an identifier
+        if rest[..len].starts_with('-') && rest[len..].starts_with(|c: char| c.is_ascii_digit()) {
+            return "";
+        }
+        &rest[..len]
+    }
+
+    fn ident(&mut self) -> &'a str {
+        let ident = self.peek_ident();
+        self.pos += ident.len();
+        ident
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        if self.peek_ident().eq_ignore_ascii_case(name) {
+            self.pos += name.len();
+            return true;
+        }
+        false
+    }
+
+    fn at_length(&mut self) -> bool {
+        self.skip();
+        self.rest()
+            .starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'))
+    }
+
+    fn length(&mut self) -> Result<Length, CssError> {
+        self.skip();
+        let rest = self.rest();
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && matches!(c, '-' | '+'))))
+            .map_or(rest.len(), |(i, _)| i);
+
+        let value = rest[..len].parse::<Float>().map_err(|_| self.error())?;
+        self.pos += len;
+
+        if self.rest().starts_with('%') {
+            self.pos += 1;
+            return Ok(Length::Percent(value));
+        }
+
+        let unit_at = self.pos;
+        match self.ident() {
+            // unitless values are not valid css besides 0, but are common enough to accept
+            "" => Ok(Length::Px(value)),
+            unit => match Unit::parse(unit) {
+                Some(unit) => Ok(Length::Px(unit.convert(value, Unit::Px))),
+                None => Err(CssError::Unit { offset: unit_at }),
+            },
+        }
+    }
+
+    // `evenodd,` / `nonzero,` at the start of polygon() and path()
+    fn fill_rule(&mut self) -> Result<FillRule, CssError> {
+        let rule = match self.peek_ident() {
+            "evenodd" => FillRule::EvenOdd,
+            "nonzero" => FillRule::NonZero,
+            _ => return Ok(FillRule::default()),
+        };
+        self.ident();
+        self.expect(',')?;
+        Ok(rule)
+    }
+
+    fn string(&mut self) -> Result<String, CssError> {
+        self.skip();
+        let quote = match self.rest().chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => return Err(self.error()),
+        };
+        self.pos += 1;
+
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                c if c == quote => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => match chars.next() {
+                    // escaped newlines continue the string
+                    Some((_, '\n')) => {}
+                    Some((_, c)) => out.push(c),
+                    None => break,
+                },
+                c => out.push(c),
+            }
+        }
+
+        Err(CssError::Syntax {
+            offset: self.src.len(),
+        })
+    }
+
+    // `at <position>`, centered when missing. only the one and two value forms are supported
+    fn position(&mut self, (w, h): (Float, Float)) -> Result<(Float, Float), CssError> {
+        if !self.eat_ident("at") {
+            return Ok((w / 2.0, h / 2.0));
+        }
+
+        enum Part {
+            X(Float),
+            Y(Float),
+            Center,
+            Either(Length),
+        }
+
+        let mut parts = Vec::new();
+        while parts.len() < 2 {
+            let part = if self.at_length() {
+                Part::Either(self.length()?)
+            } else {
+                match self.peek_ident() {
+                    "left" => Part::X(0.0),
+                    "right" => Part::X(w),
+                    "top" => Part::Y(0.0),
+                    "bottom" => Part::Y(h),
+                    "center" => Part::Center,
+                    _ => break,
+                }
+            };
+            if !matches!(part, Part::Either(_)) {
+                self.ident();
+            }
+            parts.push(part);
+        }
+
+        let (mut x, mut y) = (None, None);
+        let mut pending = Vec::new();
+        for part in parts {
+            match part {
+                Part::X(v) if x.is_none() => x = Some(v),
+                Part::Y(v) if y.is_none() => y = Some(v),
+                Part::Center | Part::Either(_) => pending.push(part),
+                _ => return Err(self.error()),
+            }
+        }
+
+        // plain values fill x first, then y
+        for part in pending {
+            let (slot, basis) = if x.is_none() {
+                (&mut x, w)
+            } else {
+                (&mut y, h)
+            };
+            *slot = Some(match part {
+                Part::Either(l) => l.resolve(basis),
+                _ => basis / 2.0,
+            });
+        }
+
+        match (x, y) {
+            (None, None) => Err(self.error()),
+            (x, y) => Ok((x.unwrap_or(w / 2.0), y.unwrap_or(h / 2.0))),
+        }
+    }
+}
+
+fn ellipse(commands: &mut Vec<Command>, (cx, cy): (Float, Float), rx: Float, ry: Float) {
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+
+    commands.push(Command::MoveTo { x: cx + rx, y: cy });
+    let quarters = [
+        ((cx + rx, cy + ky), (cx + kx, cy + ry), (cx, cy + ry)),
+        ((cx - kx, cy + ry), (cx - rx, cy + ky), (cx - rx, cy)),
+        ((cx - rx, cy - ky), (cx - kx, cy - ry), (cx, cy - ry)),
+        ((cx + kx, cy - ry), (cx + rx, cy - ky), (cx + rx, cy)),
+    ];
+    for ((x1, y1), (x2, y2), (x, y)) in quarters {
+        commands.push(Command::CurveTo {
+            x1,
+            y1,
+            x2,
+            y2,
+            x,
+            y,
+        });
+    }
+    commands.push(Command::ClosePath);
+}
+
+// css shorthand expansion: 1 to 4 values for top, right, bottom, left (or the corners clockwise
+// from the top left)
+fn expand<T: Copy>(values: &[T]) -> [T; 4] {
+    match *values {
+        [a] => [a, a, a, a],
+        [a, b] => [a, b, a, b],
+        [a, b, c] => [a, b, c, b],
+        [a, b, c, d, ..] => [a, b, c, d],
+        [] => unreachable!("at least one value"),
+    }
+}
+
+fn lengths(cursor: &mut Cursor, max: usize) -> Result<Vec<Length>, CssError> {
+    let mut out = Vec::new();
+    while out.len() < max && cursor.at_length() {
+        out.push(cursor.length()?);
+    }
+    if out.is_empty() {
+        return Err(cursor.error());
+    }
+    Ok(out)
+}
+
+fn inset(cursor: &mut Cursor, (w, h): (Float, Float)) -> Result<Vec<Command>, CssError> {
+    let [top, right, bottom, left] = expand(&lengths(cursor, 4)?);
+    let (mut top, mut right, mut bottom, mut left) = (
+        top.resolve(h),
+        right.resolve(w),
+        bottom.resolve(h),
+        left.resolve(w),
+    );
+
+    // insets adding up to more than the box are scaled down until they meet
+    if left + right > w && left + right > 0.0 {
+        let f = w / (left + right);
+        (left, right) = (left * f, right * f);
+    }
+    if top + bottom > h && top + bottom > 0.0 {
+        let f = h / (top + bottom);
+        (top, bottom) = (top * f, bottom * f);
+    }
+
+    let (x0, y0, x1, y1) = (left, top, w - right, h - bottom);
+    let (rw, rh) = (x1 - x0, y1 - y0);
+
+    let radii = if cursor.eat_ident("round") {
+        expand(&lengths(cursor, 4)?).map(|r| (r.resolve(rw), r.resolve(rh)))
+    } else {
+        [(0.0, 0.0); 4]
+    };
+    let [tl, tr, br, bl] = radii;
+
+    // same for corner radii that do not fit along an edge
+    let f = [
+        rw / (tl.0 + tr.0),
+        rw / (bl.0 + br.0),
+        rh / (tl.1 + bl.1),
+        rh / (tr.1 + br.1),
+    ]
+    .into_iter()
+    .filter(|f| f.is_finite())
+    .fold(1.0, Float::min);
+    let [tl, tr, br, bl] = radii.map(|(rx, ry)| (rx * f, ry * f));
+
+    let mut commands = vec![Command::MoveTo {
+        x: x0 + tl.0,
+        y: y0,
+    }];
+    // (edge end, corner end, corner center) clockwise from the top edge
+    let corners = [
+        ((x1 - tr.0, y0), (x1, y0 + tr.1), (x1 - tr.0, y0 + tr.1)),
+        ((x1, y1 - br.1), (x1 - br.0, y1), (x1 - br.0, y1 - br.1)),
+        ((x0 + bl.0, y1), (x0, y1 - bl.1), (x0 + bl.0, y1 - bl.1)),
+        ((x0, y0 + tl.1), (x0 + tl.0, y0), (x0 + tl.0, y0 + tl.1)),
+    ];
+    for (edge, end, center) in corners {
+        // without a top left radius the last edge ends where the path started, Z draws it
+        if edge != (x0 + tl.0, y0) {
+            commands.push(Command::LineTo {
+                x: edge.0,
+                y: edge.1,
+            });
+        }
+        if edge == end {
+            continue;
+        }
+
+        // handles point from each end towards the corner of the box
+        let corner = (
+            if edge.0 == center.0 { end.0 } else { edge.0 },
+            if edge.1 == center.1 { end.1 } else { edge.1 },
+        );
+        commands.push(Command::CurveTo {
+            x1: edge.0 + (corner.0 - edge.0) * KAPPA,
+            y1: edge.1 + (corner.1 - edge.1) * KAPPA,
+            x2: end.0 + (corner.0 - end.0) * KAPPA,
+            y2: end.1 + (corner.1 - end.1) * KAPPA,
+            x: end.0,
+            y: end.1,
+        });
+    }
+    commands.push(Command::ClosePath);
+
+    Ok(commands)
+}
+
+#[derive(Clone, Copy)]
+enum Radius {
+    ClosestSide,
+    FarthestSide,
+    Length(Length),
+}
+
+impl Radius {
+    // `sides` are the distances from the center to the box edges the radius measures against
+    fn resolve(self, sides: &[Float], basis: Float) -> Float {
+        match self {
+            Radius::ClosestSide => sides.iter().copied().fold(Float::INFINITY, Float::min),
+            Radius::FarthestSide => sides.iter().copied().fold(0.0, Float::max),
+            Radius::Length(l) => l.resolve(basis),
+        }
+    }
+}
+
+fn radius(cursor: &mut Cursor) -> Result<Option<Radius>, CssError> {
+    if cursor.eat_ident("closest-side") {
+        return Ok(Some(Radius::ClosestSide));
+    }
+    if cursor.eat_ident("farthest-side") {
+        return Ok(Some(Radius::FarthestSide));
+    }
+    if cursor.at_length() {
+        return Ok(Some(Radius::Length(cursor.length()?)));
+    }
+    Ok(None)
+}
+
+// parse a css `path()` or basic shape (`inset()`, `circle()`, `ellipse()`, `polygon()`) as used
+// by `clip-path` and `offset-path`. percentages and keywords resolve against `reference`, the
+// width and height of the reference box
+pub fn parse(value: &str, reference: (Float, Float)) -> Result<Shape, CssError> {
+    let (w, h) = reference;
+    let mut cursor = Cursor { src: value, pos: 0 };
+
+    let function_at = {
+        cursor.skip();
+        cursor.pos
+    };
+    let function = cursor.ident().to_ascii_lowercase();
+    if !cursor.rest().starts_with('(') {
+        return Err(cursor.error());
+    }
+    cursor.pos += 1;
+
+    let mut fill_rule = FillRule::default();
+    let commands = match function.as_str() {
+        "path" => {
+            fill_rule = cursor.fill_rule()?;
+            let d = cursor.string()?;
+            Parser::new(&d).parse().map_err(CssError::Path)?
+        }
+        "polygon" => {
+            fill_rule = cursor.fill_rule()?;
+            let mut commands = Vec::new();
+            loop {
+                let x = cursor.length()?.resolve(w);
+                let y = cursor.length()?.resolve(h);
+                commands.push(if commands.is_empty() {
+                    Command::MoveTo { x, y }
+                } else {
+                    Command::LineTo { x, y }
+                });
+                if !cursor.eat(',') {
+                    break;
+                }
+            }
+            commands.push(Command::ClosePath);
+            commands
+        }
+        "circle" => {
+            let r = radius(&mut cursor)?.unwrap_or(Radius::ClosestSide);
+            let (cx, cy) = cursor.position(reference)?;
+
+            let sides = [cx, w - cx, cy, h - cy].map(Float::abs);
+            let r = r.resolve(&sides, (w * w + h * h).sqrt() / crate::consts::SQRT_2);
+
+            let mut commands = Vec::new();
+            ellipse(&mut commands, (cx, cy), r, r);
+            commands
+        }
+        "ellipse" => {
+            let (rx, ry) = match (radius(&mut cursor)?, radius(&mut cursor)?) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (None, None) => (Radius::ClosestSide, Radius::ClosestSide),
+                _ => return Err(cursor.error()),
+            };
+            let (cx, cy) = cursor.position(reference)?;
+
+            let rx = rx.resolve(&[cx, w - cx].map(Float::abs), w);
+            let ry = ry.resolve(&[cy, h - cy].map(Float::abs), h);
+
+            let mut commands = Vec::new();
+            ellipse(&mut commands, (cx, cy), rx, ry);
+            commands
+        }
+        "inset" => inset(&mut cursor, reference)?,
+        _ => {
+            return Err(CssError::Syntax {
+                offset: function_at,
+            })
+        }
+    };
+
+    cursor.expect(')')?;
+    cursor.skip();
+    if !cursor.rest().is_empty() {
+        return Err(cursor.error());
+    }
+
+    Ok(Shape {
+        path: Path::new(commands),
+        fill_rule,
+    })
+}
@@ -0,0 +1,310 @@
+use crate::{
+    path::Path,
+    segment::{push_segments, Segment},
+    Float,
+};
+
+// dashes and gaps allowed in one subpath, the same limit Skia puts on it. past this the pattern
+// is too fine to see and would only make an enormous path
+const MAX_INTERVALS: Float = 1_000_000.0;
+
+// the part of `seg` between two lengths along it
+fn between(seg: &Segment, from: Float, to: Float, len: Float) -> Segment {
+    if from <= 0.0 && to >= len {
+        return *seg;
+    }
+
+    let t0 = seg.t_at_length(from);
+    let t1 = seg.t_at_length(to);
+    let rest = if t0 > 0.0 { seg.split(t0).1 } else { *seg };
+    if t1 >= 1.0 {
+        return rest;
+    }
+    rest.split((t1 - t0) / (1.0 - t0)).0
+}
+
+// where in a dash pattern the pen is
+struct Pattern<'a> {
+    pattern: &'a [Float],
+    index: usize,
+    // of the current entry
+    left: Float,
+}
+
+impl<'a> Pattern<'a> {
+    // `offset` into the pattern, which wraps around
+    fn new(pattern: &'a [Float], offset: Float) -> Self {
+        let mut pattern = Pattern {
+            pattern,
+            index: 0,
+            left: pattern[0],
+        };
+
+        let total: Float = pattern.pattern.iter().sum();
+        let mut offset = offset.rem_euclid(total);
+        while offset > 0.0 {
+            let step = offset.min(pattern.left);
+            offset -= step;
+            pattern.left -= step;
+            if pattern.left <= 0.0 {
+                pattern.next();
+            }
+        }
+        pattern
+    }
+
+    // dashes are the even entries, gaps the odd ones
+    fn on(&self) -> bool {
+        self.index.is_multiple_of(2)
+    }
+
+    fn next(&mut self) {
+        self.index = (self.index + 1) % self.pattern.len();
+        self.left = self.pattern[self.index];
+    }
+}
+
+impl Path {
+    // the path cut into dashes the way `stroke-dasharray` and `stroke-dashoffset` draw it: the
+    // entries of `pattern` are dash and gap lengths in turn, repeated twice when there is an odd
+    // number of them, and the pattern starts over at every subpath, `offset` into it. a dash running
+    // over the start of a closed subpath stays one piece. patterns svg would not dash with (empty,
+    // negative or adding up to 0), or that would cut a subpath into more than `MAX_INTERVALS`
+    // pieces, give the path back as it is
+    pub fn dash(&self, pattern: &[Float], offset: Float) -> Path {
+        let invalid = pattern.iter().any(|v| !v.is_finite() || *v < 0.0);
+        if invalid || pattern.iter().sum::<Float>() <= 0.0 || !offset.is_finite() {
+            return self.fork();
+        }
+        let pattern: Vec<Float> = if pattern.len() % 2 == 1 {
+            pattern.iter().chain(pattern).copied().collect()
+        } else {
+            pattern.to_vec()
+        };
+        let total: Float = pattern.iter().sum();
+
+        let subpaths: Vec<_> = self
+            .subpaths()
+            .into_iter()
+            .map(|sp| {
+                let segments: Vec<_> = sp.segments().map(|seg| (seg, seg.length())).collect();
+                (sp, segments)
+            })
+            .collect();
+        let too_many = subpaths.iter().any(|(_, segments)| {
+            let length: Float = segments.iter().map(|(_, len)| len).sum();
+            length / total * pattern.len() as Float > MAX_INTERVALS
+        });
+        if too_many {
+            return self.fork();
+        }
+
+        let mut commands = Vec::new();
+        for (sp, segments) in subpaths {
+            let mut at = Pattern::new(&pattern, offset);
+            let mut dashes: Vec<Vec<Segment>> = Vec::new();
+            let mut dash: Option<Vec<Segment>> = None;
+            // whether the subpath starts inside a dash, and whether a gap ever interrupts it
+            let starts_on = at.on();
+            let mut broken = false;
+
+            for (seg, len) in segments {
+                let mut pos = 0.0;
+                // steps in a row that did not move `pos`
+                let mut stalled = 0;
+                loop {
+                    if at.on() && dash.is_none() {
+                        dash = Some(Vec::new());
+                    }
+
+                    let step = at.left.min(len - pos);
+                    if let Some(dash) = dash.as_mut() {
+                        if step > 0.0 || at.left == 0.0 {
+                            dash.push(between(&seg, pos, pos + step, len));
+                        }
+                    }
+                    let before = pos;
+                    pos += step;
+                    at.left -= step;
+
+                    // an entry too short to move `pos` far along a long segment never gets to its
+                    // end, so give up on the segment once a whole round of the pattern went nowhere
+                    stalled = if pos > before { 0 } else { stalled + 1 };
+                    if at.left > 0.0 || stalled > pattern.len() {
+                        break;
+                    }
+                    if let Some(done) = dash.take() {
+                        dashes.push(done);
+                        broken = true;
+                    }
+                    at.next();
+                    if pos >= len && at.left > 0.0 {
+                        break;
+                    }
+                }
+            }
+            let ends_on = dash.is_some();
+            if let Some(done) = dash.take() {
+                dashes.push(done);
+            }
+
+            // a closed outline dashed over its start draws one dash there, not two that meet
+            if sp.closed && starts_on && ends_on && dashes.len() > 1 {
+                let first = dashes.remove(0);
+                dashes.last_mut().expect("dashes").extend(first);
+            }
+            let whole = sp.closed && starts_on && !broken;
+
+            for dash in &dashes {
+                push_segments(dash, whole, &mut commands);
+            }
+        }
+
+        Path::new(commands)
+    }
+
+    // consecutive pieces that together draw the path, cut at each of `lengths` along it (measured
+    // like `Path::length`). cuts are taken in increasing order, ones at or past either end are
+    // ignored, so there is one piece more than there are cuts left. subpaths a cut goes through
+    // are opened there, whole ones keep their ClosePath. for styling each piece differently, such
+    // as a gradient along a stroke
+    pub fn split_at_lengths(&self, lengths: &[Float]) -> Vec<Path> {
+        let mut cuts: Vec<Float> = lengths
+            .iter()
+            .copied()
+            .filter(|l| *l > 0.0 && l.is_finite())
+            .collect();
+        cuts.sort_by(Float::total_cmp);
+        cuts.dedup();
+        let mut cuts = cuts.into_iter().peekable();
+
+        let mut pieces = Vec::new();
+        let mut commands = Vec::new();
+        let mut walked = 0.0;
+        for sp in self.subpaths() {
+            let mut run = Vec::new();
+            let mut cut_through = false;
+
+            for seg in sp.segments() {
+                let len = seg.length();
+                let mut pos = 0.0;
+                while let Some(cut) = cuts.next_if(|cut| cut - walked < len) {
+                    let to = cut - walked;
+                    if to > pos {
+                        run.push(between(&seg, pos, to, len));
+                    }
+                    push_segments(&run, false, &mut commands);
+                    pieces.push(Path::new(std::mem::take(&mut commands)));
+                    // a cut right at the start leaves the subpath whole
+                    cut_through |= !run.is_empty();
+                    run.clear();
+                    pos = to;
+                }
+                if pos < len || len == 0.0 {
+                    run.push(between(&seg, pos, len, len));
+                }
+                walked += len;
+            }
+
+            push_segments(&run, sp.closed && !cut_through, &mut commands);
+        }
+        pieces.push(Path::new(commands));
+
+        pieces
+    }
+
+    // the path cut every `length` along it, the last piece takes what is left. a length that is
+    // not positive gives the path back whole
+    pub fn split_every(&self, length: Float) -> Vec<Path> {
+        if length.is_nan() || length <= 0.0 {
+            return vec![self.fork()];
+        }
+
+        let total = self.length();
+        let cuts: Vec<Float> = (1..)
+            .map(|i| i as Float * length)
+            .take_while(|cut| *cut < total)
+            .collect();
+        self.split_at_lengths(&cuts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_path_str,
+        writer::{write_d_with, NumberFormat, WriteOptions},
+    };
+
+    // written to a few decimals, finding a length along a segment is not exact
+    fn dash(d: &str, pattern: &[Float], offset: Float) -> String {
+        let options = WriteOptions {
+            numbers: NumberFormat::Decimals(3),
+        };
+        let dashed = Path::new(parse_path_str(d).unwrap()).dash(pattern, offset);
+        write_d_with(dashed.commands(), &options)
+    }
+
+    #[test]
+    fn dashes() {
+        let line = "M0 0 L10 0";
+        assert_eq!(
+            dash(line, &[2.0, 1.0], 0.0),
+            "M0 0 L2 0 M3 0 L5 0 M6 0 L8 0 M9 0 L10 0"
+        );
+        // an odd pattern goes round twice, dashes turning into gaps the second time
+        assert_eq!(
+            dash(line, &[1.0, 2.0, 3.0], 0.0),
+            "M0 0 L1 0 M3 0 L6 0 M7 0 L9 0"
+        );
+        // offsets wrap around the pattern either way
+        assert_eq!(
+            dash(line, &[2.0, 1.0], 1.0),
+            "M0 0 L1 0 M2 0 L4 0 M5 0 L7 0 M8 0 L10 0"
+        );
+        assert_eq!(dash(line, &[2.0, 1.0], -1.0), dash(line, &[2.0, 1.0], 2.0));
+        assert_eq!(
+            dash(line, &[2.0, 1.0], 2.0),
+            "M1 0 L3 0 M4 0 L6 0 M7 0 L9 0"
+        );
+        // every subpath starts the pattern over
+        assert_eq!(
+            dash("M0 0 L4 0 M0 1 L4 1", &[3.0, 3.0], 0.0),
+            "M0 0 L3 0 M0 1 L3 1"
+        );
+
+        // patterns svg does not dash with
+        for pattern in [&[][..], &[0.0, 0.0], &[1.0, -1.0], &[Float::NAN, 1.0]] {
+            assert_eq!(dash(line, pattern, 0.0), "M0 0 L10 0");
+        }
+    }
+
+    #[test]
+    fn closed_subpaths_join_over_their_start() {
+        let square = "M0 0 L4 0 L4 4 L0 4 L0 0 Z";
+        // the last dash runs on into the first, 15..18 around a perimeter of 16
+        assert_eq!(
+            dash(square, &[3.0, 2.0], 0.0),
+            "M4 1 L4 4 M2 4 L0 4 L0 3 M0 1 L0 0 L3 0"
+        );
+        // a dash longer than the outline leaves it closed, the closing side drawn out
+        assert_eq!(dash(square, &[20.0, 1.0], 0.0), "M0 0 L4 0 L4 4 L0 4 L0 0 Z");
+        // one gap anywhere opens it
+        assert_eq!(dash(square, &[15.0, 1.0], 0.0), "M0 0 L4 0 L4 4 L0 4 L0 1");
+    }
+
+    #[test]
+    fn patterns_too_fine_to_see_are_ignored() {
+        // each step is under what a coordinate near 1000 can resolve, this used to never return
+        assert_eq!(dash("M0 0 L1000 0", &[1e-5, 1e-5], 0.0), "M0 0 L1000 0");
+        assert_eq!(dash("M0 0 L1000 0", &[1e-4], 0.0), "M0 0 L1000 0");
+        // right under the limit it still dashes
+        let dashed = Path::new(parse_path_str("M0 0 L1000 0").unwrap()).dash(&[1e-3], 0.0);
+        assert!(dashed.commands().len() > 900_000);
+
+        // a gap too short to move past a long dash is stepped over
+        let dashed = dash("M0 0 L2000 0", &[1000.0, 1e-5], 0.0);
+        assert!(dashed.starts_with("M0 0 L1000 0 M1000"), "{dashed}");
+    }
+}
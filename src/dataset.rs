@@ -0,0 +1,528 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use crate::{
+    compress::{command_opcode, is_arc, LARGE_ARC, SWEEP},
+    path::Path,
+    progressive::{arity, command},
+    raster::Mask,
+    to_f32,
+    transform::Transform,
+    Command, FillRule, Float, Point,
+};
+
+// many paths kept as a few flat arrays instead of one allocation each, and the file they are
+// stored in. all numbers in the file are little endian:
+//
+//   b"SCD\x01"
+//   u64 paths, u64 commands, u64 numbers
+//   u64 command offset of every path, then the total       (paths + 1)
+//   u64 number offset of every path, then the total        (paths + 1)
+//   u8 opcode of every command                             (commands)
+//   f32 numbers of every command                           (numbers)
+//
+// opcodes are the ones `Path::compress` writes: 0 M, 1 L, 2 C, 3 S, 4 Q, 5 T, 6 Z, and 7 for an
+// arc with its large arc flag in bit 3 and its sweep flag in bit 4. commands hold their points
+// in order (S and T with their resolved first control point), arcs `rx ry rotation x y`
+const MAGIC: [u8; 4] = *b"SCD\x01";
+// the magic and the three counts
+const HEADER: u64 = 4 + 8 * 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatasetError {
+    Io(io::ErrorKind),
+    // not a dataset, or the arrays in it do not agree with each other
+    Format,
+    // there is no path at this index
+    OutOfRange { index: usize },
+}
+
+impl From<io::Error> for DatasetError {
+    fn from(e: io::Error) -> Self {
+        DatasetError::Io(e.kind())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dataset {
+    // where each path's commands start in `opcodes`, with the total at the end
+    pub command_offsets: Vec<u64>,
+    // where each path's numbers start in `numbers`, with the total at the end
+    pub number_offsets: Vec<u64>,
+    pub opcodes: Vec<u8>,
+    // kept as f32 whatever `Float` is, so files read the same with and without the `f64` feature
+    pub numbers: Vec<f32>,
+}
+
+impl Default for Dataset {
+    fn default() -> Self {
+        Self {
+            command_offsets: vec![0],
+            number_offsets: vec![0],
+            opcodes: Vec::new(),
+            numbers: Vec::new(),
+        }
+    }
+}
+
+// the numbers a command with this opcode holds
+fn numbers(op: u8) -> Option<usize> {
+    if is_arc(op) {
+        Some(5)
+    } else {
+        arity(op).map(|n| n * 2)
+    }
+}
+
+// the commands of one path from its columns, which must already agree with each other
+fn decode(opcodes: &[u8], numbers: &[f32]) -> Vec<Command> {
+    let mut at = 0;
+    let mut points: Vec<Point> = Vec::with_capacity(3);
+    opcodes
+        .iter()
+        .map(|&op| {
+            if is_arc(op) {
+                let n = &numbers[at..at + 5];
+                at += 5;
+                return Command::EllipticalArc {
+                    rx: n[0] as Float,
+                    ry: n[1] as Float,
+                    x_axis_rotation: n[2] as Float,
+                    large_arc: op & LARGE_ARC != 0,
+                    sweep: op & SWEEP != 0,
+                    x: n[3] as Float,
+                    y: n[4] as Float,
+                };
+            }
+
+            let arity = arity(op).unwrap_or(0);
+            points.clear();
+            points.extend(
+                numbers[at..at + arity * 2]
+                    .chunks(2)
+                    .map(|p| (p[0] as Float, p[1] as Float)),
+            );
+            at += arity * 2;
+            command(op, &points)
+        })
+        .collect()
+}
+
+// whether `opcodes` hold exactly `count` numbers
+fn holds(opcodes: &[u8], count: u64) -> bool {
+    let mut total = 0u64;
+    for &op in opcodes {
+        match numbers(op) {
+            Some(n) => total += n as u64,
+            None => return false,
+        }
+    }
+    total == count
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_u64s(r: &mut impl Read, n: u64) -> io::Result<Vec<u64>> {
+    (0..n).map(|_| read_u64(r)).collect()
+}
+
+fn read_bytes(r: &mut impl Read, n: u64) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    r.take(n).read_to_end(&mut out)?;
+    if (out.len() as u64) < n {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(out)
+}
+
+fn read_f32s(r: &mut impl Read, n: u64) -> io::Result<Vec<f32>> {
+    let bytes = read_bytes(r, n.saturating_mul(4))?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+// the magic and the counts of paths, commands and numbers
+fn read_header(r: &mut impl Read) -> Result<(u64, u64, u64), DatasetError> {
+    let mut magic = [0; 4];
+    r.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(DatasetError::Format);
+    }
+    Ok((read_u64(r)?, read_u64(r)?, read_u64(r)?))
+}
+
+impl Dataset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.command_offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, path: &Path) {
+        for cmd in path.commands() {
+            self.opcodes.push(command_opcode(cmd));
+            if let Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                ..
+            } = *cmd
+            {
+                self.numbers.extend([rx, ry, x_axis_rotation].map(to_f32));
+            }
+            self.numbers
+                .extend(cmd.points().flat_map(|(x, y)| [to_f32(x), to_f32(y)]));
+        }
+
+        self.command_offsets.push(self.opcodes.len() as u64);
+        self.number_offsets.push(self.numbers.len() as u64);
+    }
+
+    // the path at `index`, `None` past the end or when the columns were edited into disagreeing
+    pub fn get(&self, index: usize) -> Option<Path> {
+        let end = index.checked_add(2)?;
+        let c = self.command_offsets.get(index..end)?;
+        let n = self.number_offsets.get(index..end)?;
+        let opcodes = self.opcodes.get(c[0] as usize..c[1] as usize)?;
+        let numbers = self.numbers.get(n[0] as usize..n[1] as usize)?;
+
+        holds(opcodes, numbers.len() as u64).then(|| Path::new(decode(opcodes, numbers)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Path> + '_ {
+        (0..self.len()).filter_map(|i| self.get(i))
+    }
+
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        out.write_all(&MAGIC)?;
+        for n in [self.len(), self.opcodes.len(), self.numbers.len()] {
+            out.write_all(&(n as u64).to_le_bytes())?;
+        }
+        for n in self.command_offsets.iter().chain(&self.number_offsets) {
+            out.write_all(&n.to_le_bytes())?;
+        }
+        out.write_all(&self.opcodes)?;
+        for n in &self.numbers {
+            out.write_all(&n.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    // a whole dataset from what `write_to` wrote. a file that ends early is an
+    // `UnexpectedEof` error
+    pub fn read(r: &mut impl Read) -> Result<Dataset, DatasetError> {
+        let (paths, commands, numbers) = read_header(r)?;
+        let offsets = paths.checked_add(1).ok_or(DatasetError::Format)?;
+
+        let dataset = Dataset {
+            command_offsets: read_u64s(r, offsets)?,
+            number_offsets: read_u64s(r, offsets)?,
+            opcodes: read_bytes(r, commands)?,
+            numbers: read_f32s(r, numbers)?,
+        };
+
+        // every path has to be readable on its own
+        let agree = |offsets: &[u64], total| {
+            offsets[0] == 0
+                && offsets.windows(2).all(|w| w[0] <= w[1])
+                && offsets.last() == Some(&total)
+        };
+        let paths_agree = || {
+            dataset
+                .command_offsets
+                .windows(2)
+                .zip(dataset.number_offsets.windows(2))
+                .all(|(c, n)| holds(&dataset.opcodes[c[0] as usize..c[1] as usize], n[1] - n[0]))
+        };
+        if !agree(&dataset.command_offsets, commands)
+            || !agree(&dataset.number_offsets, numbers)
+            || !paths_agree()
+        {
+            return Err(DatasetError::Format);
+        }
+
+        Ok(dataset)
+    }
+
+    // only the path at `index` from a dataset file, reading its offsets and columns and skipping
+    // everything else
+    pub fn read_path<R: Read + Seek>(r: &mut R, index: usize) -> Result<Path, DatasetError> {
+        let start = r.stream_position()?;
+        let (paths, commands, numbers) = read_header(r)?;
+        if index as u64 >= paths {
+            return Err(DatasetError::OutOfRange { index });
+        }
+
+        // where the columns start. a header claiming more than a file can hold is not one
+        // `write_to` wrote
+        let offsets = start + HEADER;
+        let layout = paths
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(16))
+            .and_then(|n| n.checked_add(offsets))
+            .and_then(|opcodes| {
+                let numbers_at = opcodes.checked_add(commands)?;
+                numbers_at.checked_add(numbers.checked_mul(4)?)?;
+                Some((opcodes, numbers_at))
+            });
+        let (opcodes_at, numbers_at) = layout.ok_or(DatasetError::Format)?;
+
+        let mut pair = |column: u64| -> Result<(u64, u64), DatasetError> {
+            r.seek(SeekFrom::Start(offsets + (column + index as u64) * 8))?;
+            Ok((read_u64(r)?, read_u64(r)?))
+        };
+        let (c0, c1) = pair(0)?;
+        let (n0, n1) = pair(paths + 1)?;
+        if c0 > c1 || c1 > commands || n0 > n1 || n1 > numbers {
+            return Err(DatasetError::Format);
+        }
+
+        r.seek(SeekFrom::Start(opcodes_at + c0))?;
+        let opcodes = read_bytes(r, c1 - c0)?;
+        r.seek(SeekFrom::Start(numbers_at + n0 * 4))?;
+        let values = read_f32s(r, n1 - n0)?;
+
+        if !holds(&opcodes, values.len() as u64) {
+            return Err(DatasetError::Format);
+        }
+        Ok(Path::new(decode(&opcodes, &values)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    // empty pixels kept between the shape and each edge
+    pub padding: Float,
+    pub fill_rule: FillRule,
+    // threads rendering, 0 for one per core. the masks come out in the order of the paths
+    // either way
+    pub threads: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            padding: 2.0,
+            fill_rule: FillRule::NonZero,
+            threads: 0,
+        }
+    }
+}
+
+// `path` scaled to fill a `size` square inside the padding, keeping its proportions, and drawn
+// in the middle of it
+fn render(path: &Path, size: usize, options: &RenderOptions) -> Mask {
+    let Some(bounds) = path.tight_bounds() else {
+        return Mask {
+            width: size,
+            height: size,
+            data: vec![0; size * size],
+        };
+    };
+
+    let room = (size as Float - options.padding * 2.0).max(0.0);
+    let extent = bounds.width().max(bounds.height());
+    let scale = if extent > 0.0 { room / extent } else { 1.0 };
+    let center = (
+        (bounds.min_x + bounds.max_x) / 2.0,
+        (bounds.min_y + bounds.max_y) / 2.0,
+    );
+
+    let mut path = path.fork();
+    path.transform(
+        &Transform::translate(-center.0, -center.1)
+            .then(&Transform::scale(scale, scale))
+            .then(&Transform::translate(
+                size as Float / 2.0,
+                size as Float / 2.0,
+            )),
+    );
+    path.rasterize(size, size, options.fill_rule)
+}
+
+// every path drawn into a `size` by `size` mask of its own, centered and scaled to fit, as
+// training images for the shapes. masks line up with `paths`, so labels kept alongside them
+// still apply
+pub fn render_batch(paths: &[Path], size: usize) -> Vec<Mask> {
+    render_batch_with(paths, size, &RenderOptions::default())
+}
+
+pub fn render_batch_with(paths: &[Path], size: usize, options: &RenderOptions) -> Vec<Mask> {
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .clamp(1, paths.len().max(1));
+    if threads == 1 {
+        return paths.iter().map(|p| render(p, size, options)).collect();
+    }
+
+    // each thread takes a run of paths, the runs are put back together in order
+    std::thread::scope(|s| {
+        let runs: Vec<_> = paths
+            .chunks(paths.len().div_ceil(threads))
+            .map(|run| {
+                s.spawn(move || {
+                    run.iter()
+                        .map(|p| render(p, size, options))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        runs.into_iter()
+            .flat_map(|run| run.join().expect("render thread"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Parser;
+
+    const SOURCES: [&str; 4] = [
+        "M10 10 C 20 -5 37.25 40 50 12.5 S 80 3 91 77 Q 3 4 5 6 T 0 .5 Z",
+        "",
+        "M0 0 A 12.5 7 30 1 0 -40 2 a 3 3 0 0 1 100 0 L 3 3",
+        "M.5 .25 L 1 1 Z M 2 2 L 3 3",
+    ];
+
+    fn dataset() -> (Vec<Path>, Dataset) {
+        let paths: Vec<_> = SOURCES
+            .iter()
+            .map(|d| Path::new(Parser::new(d).preserve_arcs(true).parse().unwrap()))
+            .collect();
+        let mut dataset = Dataset::new();
+        for path in &paths {
+            dataset.push(path);
+        }
+        (paths, dataset)
+    }
+
+    fn written(dataset: &Dataset) -> Vec<u8> {
+        let mut out = Vec::new();
+        dataset.write_to(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn paths_come_back() {
+        // every number in the sources is exact as an f32
+        let (paths, dataset) = dataset();
+        assert_eq!(dataset.len(), paths.len());
+        for (i, path) in paths.iter().enumerate() {
+            assert_eq!(dataset.get(i).unwrap().commands(), path.commands());
+        }
+        assert!(dataset
+            .iter()
+            .map(|p| p.commands().to_vec())
+            .eq(paths.iter().map(|p| p.commands().to_vec())));
+
+        let bytes = written(&dataset);
+        assert_eq!(Dataset::read(&mut bytes.as_slice()), Ok(dataset));
+
+        // a path is found the same way behind whatever came before it in the file
+        let mut file = b"ahead".to_vec();
+        file.extend(&bytes);
+        for (i, path) in paths.iter().enumerate() {
+            let mut r = Cursor::new(&file);
+            r.set_position(5);
+            assert_eq!(
+                Dataset::read_path(&mut r, i).unwrap().commands(),
+                path.commands()
+            );
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let (paths, dataset) = dataset();
+        assert!(dataset.get(paths.len()).is_none());
+        assert!(dataset.get(usize::MAX).is_none());
+        assert!(Dataset::new().is_empty());
+
+        let bytes = written(&dataset);
+        for index in [paths.len(), usize::MAX] {
+            assert_eq!(
+                Dataset::read_path(&mut Cursor::new(&bytes), index).err(),
+                Some(DatasetError::OutOfRange { index })
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_what_it_did_not_write() {
+        let (_, dataset) = dataset();
+        let bytes = written(&dataset);
+        let eof = DatasetError::Io(io::ErrorKind::UnexpectedEof);
+
+        // every cut short file is an error, never fewer paths
+        for end in 0..bytes.len() {
+            assert_eq!(
+                Dataset::read(&mut &bytes[..end]).err(),
+                Some(eof.clone()),
+                "{end}"
+            );
+        }
+        // the last path ends where the file does
+        let mut r = Cursor::new(&bytes[..bytes.len() - 1]);
+        assert_eq!(
+            Dataset::read_path(&mut r, dataset.len() - 1).err(),
+            Some(eof)
+        );
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(
+            Dataset::read(&mut bad.as_slice()),
+            Err(DatasetError::Format)
+        );
+
+        // a header claiming more than any file holds
+        let mut bad = bytes.clone();
+        bad[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            Dataset::read(&mut bad.as_slice()),
+            Err(DatasetError::Format)
+        );
+        assert_eq!(
+            Dataset::read_path(&mut Cursor::new(&bad), 0).err(),
+            Some(DatasetError::Format)
+        );
+
+        // an opcode nothing writes, and a C turned into an L that holds fewer numbers
+        let opcodes = (HEADER + 16 * (dataset.len() as u64 + 1)) as usize;
+        for op in [0x3f, 1] {
+            let mut bad = bytes.clone();
+            bad[opcodes + 1] = op;
+            assert_eq!(
+                Dataset::read(&mut bad.as_slice()),
+                Err(DatasetError::Format)
+            );
+            assert_eq!(
+                Dataset::read_path(&mut Cursor::new(&bad), 0).err(),
+                Some(DatasetError::Format)
+            );
+        }
+
+        // columns edited into disagreeing are not read as a path
+        let mut edited = dataset.clone();
+        edited.numbers.pop();
+        assert!(edited.get(dataset.len() - 1).is_none());
+        assert!(edited.get(0).is_some());
+    }
+}
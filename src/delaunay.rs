@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::{
+    path::Path,
+    predicates::{incircle, orient2d},
+    to_f64, Float, Point,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangulation {
+    pub points: Vec<Point>,
+    // indices into `points`, counter clockwise with y up (clockwise on screen)
+    pub triangles: Vec<[usize; 3]>,
+}
+
+fn circumcenter(a: Point, b: Point, c: Point) -> Point {
+    let (bx, by) = (to_f64(b.0) - to_f64(a.0), to_f64(b.1) - to_f64(a.1));
+    let (cx, cy) = (to_f64(c.0) - to_f64(a.0), to_f64(c.1) - to_f64(a.1));
+    let d = 2.0 * (bx * cy - by * cx);
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+
+    (
+        a.0 + ((cy * b2 - by * c2) / d) as Float,
+        a.1 + ((bx * c2 - cx * b2) / d) as Float,
+    )
+}
+
+// bowyer-watson, inserting points one by one and re-triangulating the cavity of triangles whose
+// circumcircle they fall into. quadratic in the worst case, plenty for a few thousand stipples
+fn bowyer_watson(points: &[Point]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in points {
+        min = (min.0.min(p.0), min.1.min(p.1));
+        max = (max.0.max(p.0), max.1.max(p.1));
+    }
+    let size = (max.0 - min.0).max(max.1 - min.1).max(1.0);
+    let mid = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+
+    // a triangle far enough out to contain every point, its corners go last
+    let mut all = points.to_vec();
+    all.push((mid.0 - 20.0 * size, mid.1 - size));
+    all.push((mid.0 + 20.0 * size, mid.1 - size));
+    all.push((mid.0, mid.1 + 20.0 * size));
+
+    let mut triangles = vec![[n, n + 1, n + 2]];
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for (i, &p) in points.iter().enumerate() {
+        edges.clear();
+        triangles.retain(|&[a, b, c]| {
+            if incircle(all[a], all[b], all[c], p) <= 0.0 {
+                return true;
+            }
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                *edges.entry((u.min(v), u.max(v))).or_default() += 1;
+            }
+            false
+        });
+
+        // the cavity's boundary is every edge only one removed triangle had
+        for (&(u, v), &count) in &edges {
+            if count != 1 {
+                continue;
+            }
+            if orient2d(all[u], all[v], p) > 0.0 {
+                triangles.push([u, v, i]);
+            } else {
+                triangles.push([v, u, i]);
+            }
+        }
+    }
+
+    triangles.retain(|t| t.iter().all(|&v| v < n));
+    triangles
+}
+
+fn dedup(points: &[Point]) -> Vec<Point> {
+    let mut out: Vec<Point> = points
+        .iter()
+        .copied()
+        .filter(|p| p.0.is_finite() && p.1.is_finite())
+        .collect();
+    out.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    out.dedup();
+    out
+}
+
+// delaunay triangulation of `points`, duplicates and non-finite points are dropped
+pub fn triangulate(points: &[Point]) -> Triangulation {
+    let points = dedup(points);
+    let triangles = bowyer_watson(&points);
+    Triangulation { points, triangles }
+}
+
+// keep the part of a convex polygon inside [min, max]
+fn clip(polygon: Vec<Point>, min: Point, max: Point) -> Vec<Point> {
+    // (axis, bound, whether the bound is a minimum)
+    let planes = [
+        (0, min.0, true),
+        (0, max.0, false),
+        (1, min.1, true),
+        (1, max.1, false),
+    ];
+
+    let mut polygon = polygon;
+    for (axis, bound, lower) in planes {
+        let axis = |p: Point| if axis == 0 { p.0 } else { p.1 };
+        let inside = |p: Point| {
+            if lower {
+                axis(p) >= bound
+            } else {
+                axis(p) <= bound
+            }
+        };
+
+        let mut out = Vec::with_capacity(polygon.len() + 1);
+        for i in 0..polygon.len() {
+            let a = polygon[i];
+            let b = polygon[(i + 1) % polygon.len()];
+            if inside(a) {
+                out.push(a);
+            }
+            if inside(a) != inside(b) {
+                let t = (bound - axis(a)) / (axis(b) - axis(a));
+                out.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+            }
+        }
+        polygon = out;
+    }
+    polygon
+}
+
+// voronoi cell of every point of `triangulate(points).points` (same order), clipped to the
+// rectangle from `min` to `max`
+pub fn voronoi(points: &[Point], min: Point, max: Point) -> Vec<Vec<Point>> {
+    let points = dedup(points);
+    let n = points.len();
+
+    // far away guards give the cells of outer points a finite shape to clip
+    let size = (max.0 - min.0).max(max.1 - min.1).max(1.0) * 10.0;
+    let (cx, cy) = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+    let mut all = points.clone();
+    all.extend([
+        (cx - size, cy - size),
+        (cx + size, cy - size),
+        (cx + size, cy + size),
+        (cx - size, cy + size),
+    ]);
+
+    let mut around: Vec<Vec<Point>> = vec![Vec::new(); n];
+    for [a, b, c] in bowyer_watson(&all) {
+        let center = circumcenter(all[a], all[b], all[c]);
+        for v in [a, b, c] {
+            if v < n {
+                around[v].push(center);
+            }
+        }
+    }
+
+    around
+        .into_iter()
+        .zip(&points)
+        .map(|(mut cell, &p)| {
+            cell.sort_by(|a, b| {
+                let angle = |q: &Point| (q.1 - p.1).atan2(q.0 - p.0);
+                angle(a).total_cmp(&angle(b))
+            });
+            cell.dedup();
+            clip(cell, min, max)
+        })
+        .collect()
+}
+
+impl Path {
+    // delaunay triangulation of points spread `spacing` apart along the outline
+    pub fn delaunay(&self, spacing: Float) -> Triangulation {
+        let points: Vec<Point> = self
+            .resample(spacing)
+            .into_iter()
+            .flat_map(|polyline| polyline.points)
+            .collect();
+        triangulate(&points)
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt;
+
+use crate::{path::Path, viewbox::calculate_bb, Command, Float};
+
+// `remove` commands at `index` replaced by `insert`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub index: usize,
+    pub remove: usize,
+    pub insert: Vec<Command>,
+}
+
+// the changes that turn one path into another, applied in order with every index counted in the
+// path the changes before it left. written out it is one `index,remove` header per change followed
+// by the inserted commands, changes separated by `;`:
+//
+//   3,1 L10 20;8,2 C1 2 3 4 5 6 Z
+//
+// commands are absolute, S and T carry their resolved first control point (`S cx cy x2 y2 x y`)
+// and arcs their flags as 0 or 1, so the commands come back exactly as they were
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathPatch {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    // the change at this position in the patch reaches past the end of the path
+    OutOfRange { change: usize },
+    // unexpected input at this byte offset
+    Syntax { offset: usize },
+}
+
+// the changes from `a` to `b`. what both start and end with is left alone, and when the commands
+// in between are as many on both sides only the runs that differ are sent
+pub fn create(a: &Path, b: &Path) -> PathPatch {
+    let (a, b) = (a.commands(), b.commands());
+
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a, b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut changes = Vec::new();
+    if a.len() != b.len() {
+        changes.push(Change {
+            index: prefix,
+            remove: a.len(),
+            insert: b.to_vec(),
+        });
+    } else {
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] == b[i] {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < a.len() && a[i] != b[i] {
+                i += 1;
+            }
+            changes.push(Change {
+                index: prefix + start,
+                remove: i - start,
+                insert: b[start..i].to_vec(),
+            });
+        }
+    }
+
+    PathPatch { changes }
+}
+
+fn letter(cmd: &Command) -> char {
+    match cmd {
+        Command::MoveTo { .. } => 'M',
+        Command::LineTo { .. } => 'L',
+        Command::CurveTo { .. } => 'C',
+        Command::SmoothCurveTo { .. } => 'S',
+        Command::QuadraticBezierCurveTo { .. } => 'Q',
+        Command::SmoothQuadraticBezierCurveTo { .. } => 'T',
+        Command::EllipticalArc { .. } => 'A',
+        Command::ClosePath => 'Z',
+    }
+}
+
+// the numbers written after each letter
+fn arity(letter: char) -> Option<usize> {
+    Some(match letter {
+        'M' | 'L' => 2,
+        'C' | 'S' => 6,
+        'Q' | 'T' => 4,
+        'A' => 7,
+        'Z' => 0,
+        _ => return None,
+    })
+}
+
+fn command(letter: char, n: &[Float]) -> Command {
+    match letter {
+        'M' => Command::MoveTo { x: n[0], y: n[1] },
+        'L' => Command::LineTo { x: n[0], y: n[1] },
+        'C' => Command::CurveTo {
+            x1: n[0],
+            y1: n[1],
+            x2: n[2],
+            y2: n[3],
+            x: n[4],
+            y: n[5],
+        },
+        'S' => Command::SmoothCurveTo {
+            cx: n[0],
+            cy: n[1],
+            x2: n[2],
+            y2: n[3],
+            x: n[4],
+            y: n[5],
+        },
+        'Q' => Command::QuadraticBezierCurveTo {
+            x1: n[0],
+            y1: n[1],
+            x: n[2],
+            y: n[3],
+        },
+        'T' => Command::SmoothQuadraticBezierCurveTo {
+            cx: n[0],
+            cy: n[1],
+            x: n[2],
+            y: n[3],
+        },
+        'A' => Command::EllipticalArc {
+            rx: n[0],
+            ry: n[1],
+            x_axis_rotation: n[2],
+            large_arc: n[3] != 0.0,
+            sweep: n[4] != 0.0,
+            x: n[5],
+            y: n[6],
+        },
+        _ => Command::ClosePath,
+    }
+}
+
+impl fmt::Display for PathPatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{},{}", change.index, change.remove)?;
+
+            for cmd in &change.insert {
+                write!(f, " {}", letter(cmd))?;
+
+                let mut first = true;
+                let mut number = |f: &mut fmt::Formatter<'_>, n: Float| {
+                    let sep = if first { "" } else { " " };
+                    first = false;
+                    write!(f, "{sep}{n}")
+                };
+
+                if let Command::EllipticalArc {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    ..
+                } = *cmd
+                {
+                    for n in [rx, ry, x_axis_rotation] {
+                        number(f, n)?;
+                    }
+                    number(f, Float::from(u8::from(large_arc)))?;
+                    number(f, Float::from(u8::from(sweep)))?;
+                }
+                for (x, y) in cmd.points() {
+                    number(f, x)?;
+                    number(f, y)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PathPatch {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    // read a patch back from its written form
+    pub fn parse(s: &str) -> Result<PathPatch, PatchError> {
+        let mut changes = Vec::new();
+        if s.trim().is_empty() {
+            return Ok(PathPatch { changes });
+        }
+
+        let mut offset = 0;
+        for part in s.split(';') {
+            // byte offset of a token inside `s`
+            let at = |token: &str| token.as_ptr() as usize - s.as_ptr() as usize;
+            let mut tokens = part.split_whitespace().peekable();
+
+            let header = tokens.next().ok_or(PatchError::Syntax { offset })?;
+            let (index, remove) = header
+                .split_once(',')
+                .and_then(|(i, r)| Some((i.parse().ok()?, r.parse().ok()?)))
+                .ok_or(PatchError::Syntax { offset: at(header) })?;
+
+            let mut insert = Vec::new();
+            while let Some(token) = tokens.next() {
+                let syntax = PatchError::Syntax { offset: at(token) };
+                let letter = token.chars().next().ok_or(syntax.clone())?;
+                let arity = arity(letter).ok_or(syntax.clone())?;
+
+                // the first number may be written against the letter
+                let mut numbers = Vec::with_capacity(arity);
+                let rest = &token[1..];
+                if !rest.is_empty() {
+                    numbers.push(rest.parse().map_err(|_| syntax.clone())?);
+                }
+                while numbers.len() < arity {
+                    let token = tokens.next().ok_or(syntax.clone())?;
+                    let n = token
+                        .parse()
+                        .map_err(|_| PatchError::Syntax { offset: at(token) })?;
+                    numbers.push(n);
+                }
+                if numbers.len() > arity {
+                    return Err(syntax);
+                }
+
+                insert.push(command(letter, &numbers));
+            }
+
+            changes.push(Change {
+                index,
+                remove,
+                insert,
+            });
+            offset += part.len() + 1;
+        }
+
+        Ok(PathPatch { changes })
+    }
+
+    // make the changes to `path`. when one of them does not fit the path is left as it was
+    pub fn apply(&self, path: &mut Path) -> Result<(), PatchError> {
+        let mut len = path.commands.len();
+        for (i, change) in self.changes.iter().enumerate() {
+            if change
+                .index
+                .checked_add(change.remove)
+                .is_none_or(|end| end > len)
+            {
+                return Err(PatchError::OutOfRange { change: i });
+            }
+            len = len - change.remove + change.insert.len();
+        }
+
+        let commands = path.commands_mut();
+        for change in &self.changes {
+            let end = change.index + change.remove;
+            commands.splice(change.index..end, change.insert.iter().copied());
+        }
+        path.bb = calculate_bb(path.commands.iter());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, Rng, SplitMix64};
+
+    fn path(d: &str) -> Path {
+        Path::new(Parser::new(d).preserve_arcs(true).parse().unwrap())
+    }
+
+    fn assert_round_trips(a: &Path, b: &Path) {
+        let patch = create(a, b);
+        let written = patch.to_string();
+        assert_eq!(PathPatch::parse(&written), Ok(patch.clone()), "{written:?}");
+
+        let mut patched = a.fork();
+        patch.apply(&mut patched).unwrap();
+        assert_eq!(patched.commands(), b.commands(), "{written:?}");
+    }
+
+    #[test]
+    fn patches_round_trip() {
+        let a = path("M0 0 C1 2 3 4 5 6 S7 8 9 10 Q1 1 2 2 T3 .5 A5 6 30 1 0 7 8 Z");
+        assert!(create(&a, &a).is_empty());
+
+        for b in [
+            // the same length, runs that differ
+            "M0 0 C1 2 3 4 5 6 S7 8 9 11 Q1 1 2 2 T3 .5 A5 6 30 0 1 7 8 Z",
+            "M1 0 C1 2 3 4 5 6 S7 8 9 10 Q1 1 2 2 T3 -.25 A5 6 30 1 0 7 8 Z",
+            // commands in and out
+            "M0 0 C1 2 3 4 5 6 L1 1 S7 8 9 10 Q1 1 2 2 T3 .5 A5 6 30 1 0 7 8 Z",
+            "M0 0 A5 6 30 1 0 7 8 Z",
+            "M0 0 Z M1 1 L2 2",
+            "",
+        ] {
+            assert_round_trips(&a, &path(b));
+            assert_round_trips(&path(b), &a);
+        }
+
+        // numbers that need every digit come back exactly
+        let mut rng = SplitMix64::new(0x9a7c);
+        for _ in 0..200 {
+            let mut commands = a.commands().to_vec();
+            let at = rng.below(commands.len());
+            let (dx, dy) = (
+                rng.below(1000) as Float / 7.0,
+                rng.below(1000) as Float / 3.0,
+            );
+            commands[at] = commands[at].map_points(|(x, y)| (x + dx, y - dy));
+            if rng.below(2) == 0 {
+                commands.remove(rng.below(commands.len()));
+            }
+            assert_round_trips(&a, &Path::new(commands));
+        }
+    }
+
+    #[test]
+    fn apply_rejects_changes_past_the_end() {
+        let mut a = path("M0 0 L1 1 L2 2");
+        let patch = PathPatch::parse("1,1 L5 5;3,1").unwrap();
+        assert_eq!(
+            patch.apply(&mut a),
+            Err(PatchError::OutOfRange { change: 1 })
+        );
+        // nothing is applied when one change does not fit
+        assert_eq!(a.commands(), path("M0 0 L1 1 L2 2").commands());
+
+        let patch = PathPatch::parse(&format!("{},1", usize::MAX)).unwrap();
+        assert_eq!(
+            patch.apply(&mut a),
+            Err(PatchError::OutOfRange { change: 0 })
+        );
+    }
+
+    #[test]
+    fn parse_reports_where_it_stopped() {
+        let syntax = |s: &str, at: &str| {
+            let offset = s.rfind(at).unwrap();
+            assert_eq!(
+                PathPatch::parse(s),
+                Err(PatchError::Syntax { offset }),
+                "{s:?}"
+            );
+        };
+
+        syntax("1 L2 3", "1");
+        syntax("0,x L2 3", "0,x");
+        syntax("0,0 L2 3;1,0 K1 2", "K1");
+        syntax("0,0 L2 3;1,0 L1 y", "y");
+        // a number where a letter is expected, and a command short of numbers
+        syntax("0,0 L2 3 4", "4");
+        syntax("0,0 C1 2 3", "C1");
+        // an empty change between separators
+        assert_eq!(
+            PathPatch::parse("0,0 L1 1;;1,0"),
+            Err(PatchError::Syntax { offset: 9 })
+        );
+    }
+}
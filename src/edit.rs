@@ -0,0 +1,149 @@
+use std::ops::Range;
+
+use crate::{path::Path, viewbox::calculate_bb, Command, Float};
+
+// every edit is stored as the commands it took out and put in at `index`, which undoes exactly
+// without relying on float transforms running backwards
+#[derive(Debug, Clone, PartialEq)]
+struct Splice {
+    index: usize,
+    removed: Vec<Command>,
+    inserted: Vec<Command>,
+}
+
+impl Splice {
+    fn apply(&self, path: &mut Path) {
+        let end = self.index + self.removed.len();
+        path.commands_mut()
+            .splice(self.index..end, self.inserted.iter().copied());
+        path.bb = calculate_bb(path.commands.iter());
+    }
+
+    fn inverse(&self) -> Splice {
+        Splice {
+            index: self.index,
+            removed: self.inserted.clone(),
+            inserted: self.removed.clone(),
+        }
+    }
+}
+
+// a history of changes made to one path through it, with undo and redo. edits made to the path
+// behind the log's back leave the history pointing at the wrong commands
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditLog {
+    done: Vec<Splice>,
+    undone: Vec<Splice>,
+}
+
+impl EditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, path: &mut Path, splice: Splice) {
+        splice.apply(path);
+        self.done.push(splice);
+        self.undone.clear();
+    }
+
+    // the commands in `range` replaced by `commands`, the one edit every other one is made of.
+    // out of bounds ranges are clamped
+    pub fn splice(&mut self, path: &mut Path, range: Range<usize>, commands: Vec<Command>) {
+        let end = range.end.min(path.commands.len());
+        let start = range.start.min(end);
+
+        self.record(
+            path,
+            Splice {
+                index: start,
+                removed: path.commands[start..end].to_vec(),
+                inserted: commands,
+            },
+        );
+    }
+
+    pub fn insert(&mut self, path: &mut Path, index: usize, commands: Vec<Command>) {
+        self.splice(path, index..index, commands);
+    }
+
+    pub fn delete(&mut self, path: &mut Path, range: Range<usize>) {
+        self.splice(path, range, Vec::new());
+    }
+
+    // false when there is no command at `index`, nothing is recorded then
+    pub fn replace(&mut self, path: &mut Path, index: usize, command: Command) -> bool {
+        if index >= path.commands.len() {
+            return false;
+        }
+        self.splice(path, index..index + 1, vec![command]);
+        true
+    }
+
+    pub fn translate(&mut self, path: &mut Path, x: Float, y: Float) {
+        let moved = path
+            .commands
+            .iter()
+            .map(|cmd| cmd.translate(x, y))
+            .collect();
+        self.splice(path, 0..path.commands.len(), moved);
+    }
+
+    // scale about the origin
+    pub fn scale(&mut self, path: &mut Path, sx: Float, sy: Float) {
+        let scaled = path.commands.iter().map(|cmd| cmd.scale(sx, sy)).collect();
+        self.splice(path, 0..path.commands.len(), scaled);
+    }
+
+    // take back the latest edit, false when there is nothing left to undo
+    pub fn undo(&mut self, path: &mut Path) -> bool {
+        let Some(splice) = self.done.pop() else {
+            return false;
+        };
+        splice.inverse().apply(path);
+        self.undone.push(splice);
+        true
+    }
+
+    // make the latest undone edit again, false when there is nothing to redo
+    pub fn redo(&mut self, path: &mut Path) -> bool {
+        let Some(splice) = self.undone.pop() else {
+            return false;
+        };
+        splice.apply(path);
+        self.done.push(splice);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_path_str;
+
+    #[test]
+    fn replace_only_replaces() {
+        let mut path = Path::new(parse_path_str("M0 0 L1 1").unwrap());
+        let mut log = EditLog::new();
+
+        assert!(!log.replace(&mut path, 2, Command::LineTo { x: 5.0, y: 5.0 }));
+        assert!(!log.replace(&mut path, usize::MAX, Command::ClosePath));
+        assert_eq!(path.to_d(), "M0 0 L1 1");
+        assert!(!log.can_undo());
+
+        assert!(log.replace(&mut path, 1, Command::LineTo { x: 5.0, y: 5.0 }));
+        assert_eq!(path.to_d(), "M0 0 L5 5");
+        assert!(log.undo(&mut path));
+        assert_eq!(path.to_d(), "M0 0 L1 1");
+        assert!(log.redo(&mut path));
+        assert_eq!(path.to_d(), "M0 0 L5 5");
+    }
+}
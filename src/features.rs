@@ -0,0 +1,204 @@
+use crate::consts::PI;
+
+use crate::{
+    flatten::{flatten_subpaths, FlattenMethod},
+    path::Path,
+    segment::dist,
+    Float, Point,
+};
+
+// what goes into `Path::feature_vector`, in this order:
+//
+//   `points` points spread evenly along the outline, x then y for each
+//   a `curvature_bins` histogram of how sharply the outline turns between them
+//   the seven central moments of the points up to third order when `moments` is set
+//   (mu20 mu11 mu02 mu30 mu21 mu12 mu03)
+//
+// the histogram and moments are measured on those points, so they need `points` above 0. the
+// points are moved so their centroid is at the origin and scaled so their mean distance from
+// it is 1, which makes every part of the vector independent of where the path is and how large
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureConfig {
+    pub points: usize,
+    pub curvature_bins: usize,
+    pub moments: bool,
+}
+
+impl Default for FeatureConfig {
+    fn default() -> Self {
+        Self {
+            points: 32,
+            curvature_bins: 8,
+            moments: true,
+        }
+    }
+}
+
+impl FeatureConfig {
+    // how many numbers `feature_vector` returns for this config, whatever the path
+    pub fn len(&self) -> usize {
+        self.points * 2 + self.curvature_bins + if self.moments { 7 } else { 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// `n` points at equal steps along the polylines, measured as if they were drawn one after the
+// other without the jumps between them, each with the polyline it is on
+pub(crate) fn spread(polylines: &[(Vec<Point>, bool)], n: usize) -> Vec<(Point, usize)> {
+    let total: Float = polylines
+        .iter()
+        .flat_map(|(points, _)| points.windows(2))
+        .map(|w| dist(w[0], w[1]))
+        .sum();
+    if n == 0 || total.is_nan() || total <= 0.0 {
+        return Vec::new();
+    }
+
+    let step = total / n as Float;
+    let mut out = Vec::with_capacity(n);
+    // halfway into the first step, so closed outlines come out the same wherever they start
+    let mut next = step / 2.0;
+    let mut walked = 0.0;
+    for (i, (points, _)) in polylines.iter().enumerate() {
+        for w in points.windows(2) {
+            let len = dist(w[0], w[1]);
+            while out.len() < n && next <= walked + len {
+                let t = (next - walked) / len;
+                out.push((
+                    (
+                        w[0].0 + (w[1].0 - w[0].0) * t,
+                        w[0].1 + (w[1].1 - w[0].1) * t,
+                    ),
+                    i,
+                ));
+                next += step;
+            }
+            walked += len;
+        }
+    }
+
+    // rounding can leave the last one short of the end
+    while let (true, Some(&last)) = (out.len() < n, out.last()) {
+        out.push(last);
+    }
+    out
+}
+
+// the share of turns that fall in each of `bins` equal ranges from a full turn right to a full
+// turn left. turns are taken between neighbouring points on the same polyline, around the ends of
+// closed ones
+fn turning(samples: &[(Point, usize)], closed: &[bool], bins: usize) -> Vec<Float> {
+    let mut histogram = vec![0.0; bins];
+    if bins == 0 {
+        return histogram;
+    }
+
+    let mut turns = 0;
+    let mut start = 0;
+    while start < samples.len() {
+        let polyline = samples[start].1;
+        let end = start
+            + samples[start..]
+                .iter()
+                .take_while(|(_, i)| *i == polyline)
+                .count();
+        let run: Vec<Point> = samples[start..end].iter().map(|(p, _)| *p).collect();
+        start = end;
+
+        let n = run.len();
+        let corners = if closed[polyline] {
+            n
+        } else {
+            n.saturating_sub(2)
+        };
+        if n < 3 {
+            continue;
+        }
+
+        for i in 0..corners {
+            let (a, b, c) = if closed[polyline] {
+                (run[(i + n - 1) % n], run[i], run[(i + 1) % n])
+            } else {
+                (run[i], run[i + 1], run[i + 2])
+            };
+            let (ux, uy) = (b.0 - a.0, b.1 - a.1);
+            let (vx, vy) = (c.0 - b.0, c.1 - b.1);
+            let angle = (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+
+            let bin = ((angle + PI) / (2.0 * PI) * bins as Float) as usize;
+            histogram[bin.min(bins - 1)] += 1.0;
+            turns += 1;
+        }
+    }
+
+    if turns > 0 {
+        for v in &mut histogram {
+            *v /= turns as Float;
+        }
+    }
+    histogram
+}
+
+fn moments(points: &[Point]) -> [Float; 7] {
+    let n = points.len().max(1) as Float;
+    let moment = |p: i32, q: i32| {
+        points
+            .iter()
+            .map(|&(x, y)| x.powi(p) * y.powi(q))
+            .sum::<Float>()
+            / n
+    };
+
+    [
+        moment(2, 0),
+        moment(1, 1),
+        moment(0, 2),
+        moment(3, 0),
+        moment(2, 1),
+        moment(1, 2),
+        moment(0, 3),
+    ]
+}
+
+impl Path {
+    // a fixed length description of the shape for classifiers and clustering, laid out as
+    // `FeatureConfig` describes. paths that draw nothing give all zeros
+    pub fn feature_vector(&self, config: &FeatureConfig) -> Vec<Float> {
+        let tolerance = (self.bb.0.max(self.bb.1) * 1e-3).max(1e-3);
+        let polylines = flatten_subpaths(&self.commands, tolerance, FlattenMethod::Adaptive);
+
+        let samples = spread(&polylines, config.points);
+        let mut out = Vec::with_capacity(config.len());
+        if samples.is_empty() {
+            out.resize(config.len(), 0.0);
+            return out;
+        }
+
+        let n = samples.len() as Float;
+        let cx = samples.iter().map(|((x, _), _)| x).sum::<Float>() / n;
+        let cy = samples.iter().map(|((_, y), _)| y).sum::<Float>() / n;
+        let scale = samples
+            .iter()
+            .map(|&((x, y), _)| dist((x, y), (cx, cy)))
+            .sum::<Float>()
+            / n;
+        let scale = if scale > 0.0 { 1.0 / scale } else { 0.0 };
+
+        let normalized: Vec<Point> = samples
+            .iter()
+            .map(|&((x, y), _)| ((x - cx) * scale, (y - cy) * scale))
+            .collect();
+
+        out.extend(normalized.iter().flat_map(|&(x, y)| [x, y]));
+        let closed: Vec<bool> = polylines.iter().map(|(_, closed)| *closed).collect();
+        out.extend(turning(&samples, &closed, config.curvature_bins));
+        if config.moments {
+            out.extend(moments(&normalized));
+        }
+
+        out
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    flatten::{flatten_subpaths, FlattenMethod},
+    offset::{offset_rings, rings_to_commands},
+    path::Path,
+    scanline::{extent, spans, y_extent, FillRule},
+    trace::{trace_count, trace_span},
+    Command, Float,
+};
+
+#[inline(always)]
+fn rotate((x, y): (Float, Float), cos: Float, sin: Float) -> (Float, Float) {
+    (x * cos - y * sin, x * sin + y * cos)
+}
+
+// every subpath as a closed ring (open subpaths are implicitly closed when filled)
+pub(crate) fn fill_rings(path: &Path, tolerance: Float) -> Vec<Vec<(Float, Float)>> {
+    flatten_subpaths(&path.commands, tolerance, FlattenMethod::Adaptive)
+        .into_iter()
+        .map(|(points, _)| points)
+        .filter(|points| points.len() > 2)
+        .collect()
+}
+
+impl Path {
+    // parallel lines `spacing` apart at `angle` degrees, clipped to the filled (non-zero) region.
+    // lines alternate direction so a plotter can draw them with minimal travel
+    pub fn hatch(&self, angle: Float, spacing: Float) -> Path {
+        trace_span!("hatch", angle, spacing);
+
+        let mut commands = Vec::new();
+
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Path::new(commands);
+        }
+
+        let rad = angle.to_radians();
+        let (sin, cos) = rad.sin_cos();
+
+        // rotate the shape so the hatch lines are horizontal
+        let rings: Vec<Vec<(Float, Float)>> = fill_rings(self, spacing * 0.1)
+            .into_iter()
+            .map(|ring| ring.into_iter().map(|p| rotate(p, cos, -sin)).collect())
+            .collect();
+
+        let Some((min_y, max_y)) = y_extent(&rings) else {
+            return Path::new(commands);
+        };
+
+        // lines sit on a global grid so neighbouring shapes hatched separately line up
+        let first = (min_y / spacing).ceil() as i64;
+        let last = (max_y / spacing).floor() as i64;
+
+        for (n, k) in (first..=last).enumerate() {
+            let y = k as Float * spacing;
+
+            let mut row = spans(&rings, y, FillRule::NonZero);
+            if n % 2 == 1 {
+                row.reverse();
+            }
+
+            for (x0, x1) in row {
+                let (x0, x1) = if n % 2 == 1 { (x1, x0) } else { (x0, x1) };
+
+                let (x, y0) = rotate((x0, y), cos, sin);
+                commands.push(Command::MoveTo { x, y: y0 });
+
+                let (x, y1) = rotate((x1, y), cos, sin);
+                commands.push(Command::LineTo { x, y: y1 });
+            }
+        }
+
+        trace_count!(lines = commands.len() / 2, "hatched");
+
+        Path::new(commands)
+    }
+
+    // closed contours `spacing` apart, each an inward offset of the outline, until the shape
+    // collapses. outermost contour first
+    pub fn infill_concentric(&self, spacing: Float) -> Path {
+        let mut commands = Vec::new();
+
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Path::new(commands);
+        }
+
+        let rings = fill_rings(self, spacing * 0.1);
+
+        // an inset can never be larger than half the shape's extent
+        let max_steps = (self.bb.0.max(self.bb.1) / spacing).ceil() as usize + 1;
+
+        for step in 1..=max_steps {
+            let inset = offset_rings(&rings, -(step as Float) * spacing);
+            if inset.is_empty() {
+                break;
+            }
+
+            rings_to_commands(&inset, &mut commands);
+        }
+
+        Path::new(commands)
+    }
+
+    // a single hilbert curve through the centers of a `spacing` grid over the shape, broken
+    // wherever it leaves the filled (non-zero) region. one long continuous stroke per part, which
+    // plots faster than hatching and reads as a texture rather than lines
+    pub fn infill_hilbert(&self, spacing: Float) -> Path {
+        let mut commands = Vec::new();
+
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Path::new(commands);
+        }
+
+        let rings = fill_rings(self, spacing * 0.1);
+        let Some((min, max)) = extent(&rings) else {
+            return Path::new(commands);
+        };
+
+        let cells = (((max.0 - min.0).max(max.1 - min.1) / spacing).ceil() as usize).max(1);
+        let side = cells.next_power_of_two();
+        let center = |i: usize| (i as Float + 0.5) * spacing;
+
+        // which cells of each row have their center inside the shape
+        let rows: Vec<Vec<(Float, Float)>> = (0..side)
+            .map(|row| spans(&rings, min.1 + center(row), FillRule::NonZero))
+            .collect();
+        let inside = |(col, row): (usize, usize)| {
+            let x = min.0 + center(col);
+            rows[row].iter().any(|&(x0, x1)| x0 < x && x < x1)
+        };
+
+        // the straight run currently being drawn, only its corners become commands
+        let mut run: Option<((usize, usize), (usize, usize))> = None;
+        let mut prev: Option<(usize, usize)> = None;
+
+        for d in 0..side * side {
+            let cell = hilbert(side, d);
+            let point = |(col, row): (usize, usize)| (min.0 + center(col), min.1 + center(row));
+
+            if !inside(cell) {
+                prev = None;
+                continue;
+            }
+
+            match (prev, run) {
+                (None, _) => {
+                    let (x, y) = point(cell);
+                    commands.push(Command::MoveTo { x, y });
+                    run = None;
+                }
+                (Some(p), None) => run = Some((p, cell)),
+                (Some(p), Some((from, _))) => {
+                    // still heading the same way, stretch the run
+                    if (p.0 == from.0 && cell.0 == p.0) || (p.1 == from.1 && cell.1 == p.1) {
+                        run = Some((from, cell));
+                    } else {
+                        let (x, y) = point(p);
+                        commands.push(Command::LineTo { x, y });
+                        run = Some((p, cell));
+                    }
+                }
+            }
+
+            // close off the run when the curve is about to leave the shape or ends
+            let next = (d + 1 < side * side).then(|| hilbert(side, d + 1));
+            if !next.is_some_and(inside) {
+                if let Some((_, to)) = run.take() {
+                    let (x, y) = point(to);
+                    commands.push(Command::LineTo { x, y });
+                } else if let Some(Command::MoveTo { .. }) = commands.last() {
+                    // a lone cell draws nothing
+                    commands.pop();
+                }
+            }
+
+            prev = Some(cell);
+        }
+
+        Path::new(commands)
+    }
+}
+
+// the cell at distance `d` along the hilbert curve filling a `side` x `side` grid (a power of two)
+fn hilbert(side: usize, mut d: usize) -> (usize, usize) {
+    let (mut x, mut y) = (0, 0);
+    let mut s = 1;
+
+    while s < side {
+        let rx = 1 & (d / 2);
+        let ry = 1 & (d ^ rx);
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        x += s * rx;
+        y += s * ry;
+        d /= 4;
+        s *= 2;
+    }
+
+    (x, y)
+}
@@ -0,0 +1,619 @@
+use crate::{
+    flatten::simplify_polyline,
+    offset::segment_distance,
+    path::Path,
+    rng::{Rng, SplitMix64},
+    roots::solve,
+    segment::{dist, subpaths, Segment},
+    smooth::{turn, CORNER_ANGLE},
+    to_f64, Command, Float, Point,
+};
+
+// random pairs tried by `line_ransac`
+const RANSAC_ITERATIONS: usize = 256;
+
+// newton steps `cubics` spends on a fit before splitting it
+const REPARAMETERIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: Float,
+}
+
+// the infinite line through `point` along the unit vector `direction`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line {
+    pub point: Point,
+    pub direction: Point,
+}
+
+impl Line {
+    pub fn distance(&self, (x, y): Point) -> Float {
+        let (dx, dy) = (x - self.point.0, y - self.point.1);
+        (dx * self.direction.1 - dy * self.direction.0).abs()
+    }
+}
+
+// `rotation` is the angle in radians from the x axis to the `rx` axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipse {
+    pub center: Point,
+    pub rx: Float,
+    pub ry: Float,
+    pub rotation: Float,
+}
+
+type Mat3 = [[f64; 3]; 3];
+
+fn mul(a: &Mat3, b: &Mat3) -> Mat3 {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, v) in row.iter_mut().enumerate() {
+            *v = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+fn transpose(a: &Mat3) -> Mat3 {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in a.iter().enumerate() {
+        for (j, &v) in row.iter().enumerate() {
+            out[j][i] = v;
+        }
+    }
+    out
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn det(a: &Mat3) -> f64 {
+    a[0][0] * (a[1][1] * a[2][2] - a[1][2] * a[2][1])
+        - a[0][1] * (a[1][0] * a[2][2] - a[1][2] * a[2][0])
+        + a[0][2] * (a[1][0] * a[2][1] - a[1][1] * a[2][0])
+}
+
+fn inverse(a: &Mat3) -> Option<Mat3> {
+    let d = det(a);
+    if d.abs() < 1e-300 {
+        return None;
+    }
+
+    // rows of the inverse are cross products of the columns
+    let cols = transpose(a);
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        let c = cross(cols[(i + 1) % 3], cols[(i + 2) % 3]);
+        *row = c.map(|v| v / d);
+    }
+    Some(out)
+}
+
+// the eigenvectors of `m` for its real eigenvalues
+fn eigenvectors(m: &Mat3) -> Vec<[f64; 3]> {
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    let minors = m[0][0] * m[1][1] - m[0][1] * m[1][0] + m[0][0] * m[2][2] - m[0][2] * m[2][0]
+        + m[1][1] * m[2][2]
+        - m[1][2] * m[2][1];
+
+    solve([1.0, -trace, minors, -det(m)])
+        .into_iter()
+        .filter_map(|lambda| {
+            let mut rows = *m;
+            for (i, row) in rows.iter_mut().enumerate() {
+                row[i] -= lambda;
+            }
+
+            // the null space of m - lambda, from the pair of rows that pins it down best
+            [(0, 1), (0, 2), (1, 2)]
+                .map(|(a, b)| cross(rows[a], rows[b]))
+                .into_iter()
+                .max_by(|a, b| norm(a).total_cmp(&norm(b)))
+                .filter(|v| norm(v) > 0.0)
+        })
+        .collect()
+}
+
+fn norm(v: &[f64; 3]) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+// the points moved to their mean and scaled to unit spread, which keeps the sums well conditioned
+struct Normalized {
+    points: Vec<(f64, f64)>,
+    mean: (f64, f64),
+    spread: f64,
+}
+
+impl Normalized {
+    fn new(points: &[Point]) -> Option<Normalized> {
+        let n = points.len() as f64;
+        let mean = points.iter().fold((0.0, 0.0), |acc, &(x, y)| {
+            (acc.0 + to_f64(x) / n, acc.1 + to_f64(y) / n)
+        });
+        let spread = points
+            .iter()
+            .map(|&(x, y)| (to_f64(x) - mean.0).abs().max((to_f64(y) - mean.1).abs()))
+            .fold(0.0, f64::max);
+        if !(spread > 0.0 && spread.is_finite()) {
+            return None;
+        }
+
+        let points = points
+            .iter()
+            .map(|&(x, y)| ((to_f64(x) - mean.0) / spread, (to_f64(y) - mean.1) / spread))
+            .collect();
+        Some(Normalized {
+            points,
+            mean,
+            spread,
+        })
+    }
+
+    fn restore(&self, (x, y): (f64, f64)) -> Point {
+        (
+            (x * self.spread + self.mean.0) as Float,
+            (y * self.spread + self.mean.1) as Float,
+        )
+    }
+}
+
+// least-squares circle through `points` (the algebraic kasa fit), `None` for fewer than 3 points
+// or points on a line
+pub fn circle(points: &[Point]) -> Option<Circle> {
+    if points.len() < 3 {
+        return None;
+    }
+    let frame = Normalized::new(points)?;
+
+    // x^2 + y^2 + d x + e y + f = 0 in the least-squares sense
+    let mut ata = [[0.0; 3]; 3];
+    let mut atb = [0.0; 3];
+    for &(x, y) in &frame.points {
+        let row = [x, y, 1.0];
+        let rhs = -(x * x + y * y);
+        for i in 0..3 {
+            for j in 0..3 {
+                ata[i][j] += row[i] * row[j];
+            }
+            atb[i] += row[i] * rhs;
+        }
+    }
+
+    let inv = inverse(&ata)?;
+    let [d, e, f] = [0, 1, 2].map(|i| (0..3).map(|j| inv[i][j] * atb[j]).sum::<f64>());
+
+    let (cx, cy) = (-d / 2.0, -e / 2.0);
+    let r2 = cx * cx + cy * cy - f;
+    if r2 <= 0.0 {
+        return None;
+    }
+
+    Some(Circle {
+        center: frame.restore((cx, cy)),
+        radius: (r2.sqrt() * frame.spread) as Float,
+    })
+}
+
+// least-squares ellipse through `points` (fitzgibbon's direct fit in the halir-flusser form),
+// `None` for fewer than 5 points or when they do not make an ellipse
+pub fn ellipse(points: &[Point]) -> Option<Ellipse> {
+    if points.len() < 5 {
+        return None;
+    }
+    let frame = Normalized::new(points)?;
+
+    // scatter of the quadratic part [x^2, xy, y^2] and the linear part [x, y, 1]
+    let (mut s1, mut s2, mut s3) = ([[0.0; 3]; 3], [[0.0; 3]; 3], [[0.0; 3]; 3]);
+    for &(x, y) in &frame.points {
+        let quad = [x * x, x * y, y * y];
+        let lin = [x, y, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                s1[i][j] += quad[i] * quad[j];
+                s2[i][j] += quad[i] * lin[j];
+                s3[i][j] += lin[i] * lin[j];
+            }
+        }
+    }
+
+    // the linear coefficients follow from the quadratic ones: lin = t quad
+    let t = mul(&inverse(&s3)?, &transpose(&s2)).map(|row| row.map(|v| -v));
+    let mut m = mul(&s2, &t);
+    for (row, s1) in m.iter_mut().zip(s1) {
+        for (v, s1) in row.iter_mut().zip(s1) {
+            *v += s1;
+        }
+    }
+    // premultiplied by the inverse of the constraint 4ac - b^2 = 1
+    let m = [
+        m[2].map(|v| v / 2.0),
+        m[1].map(|v| -v),
+        m[0].map(|v| v / 2.0),
+    ];
+
+    let quad = eigenvectors(&m)
+        .into_iter()
+        .find(|v| 4.0 * v[0] * v[2] - v[1] * v[1] > 0.0)?;
+    let lin = [0, 1, 2].map(|i| (0..3).map(|j| t[i][j] * quad[j]).sum::<f64>());
+
+    let [a, b, c] = quad;
+    let [d, e, f] = lin;
+    let disc = b * b - 4.0 * a * c;
+
+    let cx = (2.0 * c * d - b * e) / disc;
+    let cy = (2.0 * a * e - b * d) / disc;
+
+    let k = 2.0 * (a * e * e + c * d * d - b * d * e + disc * f);
+    let root = ((a - c) * (a - c) + b * b).sqrt();
+    let major = -(k * (a + c + root)).sqrt() / disc;
+    let minor = -(k * (a + c - root)).sqrt() / disc;
+    if !(major.is_finite() && minor.is_finite()) {
+        return None;
+    }
+
+    let rotation = if b != 0.0 {
+        ((c - a - root) / b).atan()
+    } else if a < c {
+        0.0
+    } else {
+        std::f64::consts::FRAC_PI_2
+    };
+
+    Some(Ellipse {
+        center: frame.restore((cx, cy)),
+        rx: (major * frame.spread) as Float,
+        ry: (minor * frame.spread) as Float,
+        rotation: rotation as Float,
+    })
+}
+
+// the line closest to all points (total least squares, distances measured square to the line),
+// `None` for fewer than 2 distinct points
+pub fn line(points: &[Point]) -> Option<Line> {
+    if points.len() < 2 {
+        return None;
+    }
+    let frame = Normalized::new(points)?;
+
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    for &(x, y) in &frame.points {
+        sxx += x * x;
+        sxy += x * y;
+        syy += y * y;
+    }
+
+    // the direction of greatest spread
+    let angle = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+    Some(Line {
+        point: frame.restore((0.0, 0.0)),
+        direction: (angle.cos() as Float, angle.sin() as Float),
+    })
+}
+
+// `line` that ignores outliers: lines through random pairs are scored by how many points lie
+// within `tolerance`, and the best one is refit to just those. returns the line with the indices
+// of the points it fits. the same seed gives the same answer
+pub fn line_ransac(points: &[Point], tolerance: Float, seed: u64) -> Option<(Line, Vec<usize>)> {
+    line_ransac_with(points, tolerance, SplitMix64::new(seed))
+}
+
+// `line_ransac` drawing from `rng`
+pub fn line_ransac_with(
+    points: &[Point],
+    tolerance: Float,
+    mut rng: impl Rng,
+) -> Option<(Line, Vec<usize>)> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let inliers = |line: &Line| -> Vec<usize> {
+        (0..points.len())
+            .filter(|&i| line.distance(points[i]) <= tolerance)
+            .collect()
+    };
+
+    let mut best: Vec<usize> = Vec::new();
+    for _ in 0..RANSAC_ITERATIONS {
+        let a = rng.below(points.len());
+        let b = rng.below(points.len());
+        let Some(line) = line(&[points[a], points[b]]) else {
+            continue;
+        };
+
+        let found = inliers(&line);
+        if found.len() > best.len() {
+            best = found;
+        }
+    }
+
+    let fitted: Vec<Point> = best.iter().map(|&i| points[i]).collect();
+    let line = line(&fitted)?;
+    Some((line, inliers(&line)))
+}
+
+// the fewest of `points`, in order and keeping both ends, that stay within `tolerance` of the
+// whole run (douglas-peucker)
+pub fn polyline(points: &[Point], tolerance: Float) -> Vec<Point> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    simplify_polyline(points, tolerance)
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn dot(a: Point, b: Point) -> Float {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn unit(v: Point) -> Point {
+    let len = v.0.hypot(v.1);
+    if len > 0.0 {
+        (v.0 / len, v.1 / len)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+fn along(p: Point, dir: Point, d: Float) -> Point {
+    (p.0 + dir.0 * d, p.1 + dir.1 * d)
+}
+
+// how far along the polyline each point is, 0 to 1
+fn chord_lengths(points: &[Point]) -> Vec<Float> {
+    let mut u = vec![0.0];
+    for w in points.windows(2) {
+        u.push(u[u.len() - 1] + dist(w[0], w[1]));
+    }
+    let total = u[u.len() - 1];
+    u.iter()
+        .map(|v| if total > 0.0 { v / total } else { 0.0 })
+        .collect()
+}
+
+// the cubic from the first to the last point leaving and arriving along the unit tangents
+// `left` and `right` (which points back from the end) that is closest to the points at `u`
+fn generate(points: &[Point], u: &[Float], left: Point, right: Point) -> Segment {
+    let (first, last) = (points[0], points[points.len() - 1]);
+
+    let (mut c00, mut c01, mut c11, mut x0, mut x1) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (&p, &t) in points.iter().zip(u) {
+        let s = 1.0 - t;
+        let (b0, b1, b2, b3) = (s * s * s, 3.0 * t * s * s, 3.0 * t * t * s, t * t * t);
+        let (a0, a1) = ((left.0 * b1, left.1 * b1), (right.0 * b2, right.1 * b2));
+        let rest = sub(
+            p,
+            (
+                first.0 * (b0 + b1) + last.0 * (b2 + b3),
+                first.1 * (b0 + b1) + last.1 * (b2 + b3),
+            ),
+        );
+        c00 += dot(a0, a0);
+        c01 += dot(a0, a1);
+        c11 += dot(a1, a1);
+        x0 += dot(a0, rest);
+        x1 += dot(a1, rest);
+    }
+
+    let det = c00 * c11 - c01 * c01;
+    let (alpha_l, alpha_r) = if det != 0.0 {
+        ((x0 * c11 - x1 * c01) / det, (c00 * x1 - c01 * x0) / det)
+    } else {
+        (0.0, 0.0)
+    };
+
+    // handles that vanish or turn backwards fit badly, a third of the chord does well enough
+    let chord = dist(first, last);
+    let floor = chord * 1e-6;
+    let (alpha_l, alpha_r) = if alpha_l < floor || alpha_r < floor {
+        (chord / 3.0, chord / 3.0)
+    } else {
+        (alpha_l, alpha_r)
+    };
+
+    Segment::Cubic(
+        first,
+        along(first, left, alpha_l),
+        along(last, right, alpha_r),
+        last,
+    )
+}
+
+// the squared distance of the furthest point from `curve`, and which one it is
+fn furthest(points: &[Point], u: &[Float], curve: &Segment) -> (Float, usize) {
+    let mut worst = (0.0, points.len() / 2);
+    for (i, (&p, &t)) in points.iter().zip(u).enumerate().skip(1) {
+        let d = sub(curve.eval(t), p);
+        let d = dot(d, d);
+        if d >= worst.0 && i + 1 < points.len() {
+            worst = (d, i);
+        }
+    }
+    worst
+}
+
+// `u` moved to where `curve` comes closest to each point, one newton step each
+fn reparameterize(points: &[Point], u: &mut [Float], curve: &Segment) {
+    let Segment::Cubic(p0, p1, p2, p3) = *curve else {
+        return;
+    };
+    for (&p, t) in points.iter().zip(u.iter_mut()) {
+        let s = 1.0 - *t;
+        let second = (
+            6.0 * (s * (p2.0 - 2.0 * p1.0 + p0.0) + *t * (p3.0 - 2.0 * p2.0 + p1.0)),
+            6.0 * (s * (p2.1 - 2.0 * p1.1 + p0.1) + *t * (p3.1 - 2.0 * p2.1 + p1.1)),
+        );
+        let off = sub(curve.eval(*t), p);
+        let first = curve.derivative(*t);
+        let den = dot(first, first) + dot(off, second);
+        if den != 0.0 && den.is_finite() {
+            *t = (*t - dot(off, first) / den).clamp(0.0, 1.0);
+        }
+    }
+}
+
+fn fit_cubics(
+    points: &[Point],
+    left: Point,
+    right: Point,
+    max_error: Float,
+    out: &mut Vec<Segment>,
+) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if points.len() == 2 {
+        let third = dist(first, last) / 3.0;
+        out.push(Segment::Cubic(
+            first,
+            along(first, left, third),
+            along(last, right, third),
+            last,
+        ));
+        return;
+    }
+
+    let limit = max_error * max_error;
+    let mut u = chord_lengths(points);
+    let mut curve = generate(points, &u, left, right);
+    let (mut error, mut split) = furthest(points, &u, &curve);
+
+    // close misses are worth moving the parameters for before giving up on one curve
+    if error > limit && error < limit * 4.0 {
+        for _ in 0..REPARAMETERIZE {
+            reparameterize(points, &mut u, &curve);
+            curve = generate(points, &u, left, right);
+            (error, split) = furthest(points, &u, &curve);
+            if error <= limit {
+                break;
+            }
+        }
+    }
+    if error <= limit {
+        out.push(curve);
+        return;
+    }
+
+    let center = unit(sub(points[split - 1], points[split + 1]));
+    let center = if center == (0.0, 0.0) {
+        unit(sub(points[split - 1], points[split]))
+    } else {
+        center
+    };
+    fit_cubics(&points[..=split], left, center, max_error, out);
+    fit_cubics(
+        &points[split..],
+        (-center.0, -center.1),
+        right,
+        max_error,
+        out,
+    );
+}
+
+// the fewest cubics, joined smoothly, that pass within `max_error` of every point (schneider's
+// least squares fit, split where it misses most). the curves run from the first point to the last
+// and follow the polyline at both ends. fewer than 2 distinct points give no curves
+pub fn cubics(points: &[Point], max_error: Float) -> Vec<Segment> {
+    let mut points = points.to_vec();
+    points.dedup();
+    let mut out = Vec::new();
+    if points.len() < 2 || max_error.is_nan() {
+        return out;
+    }
+
+    let n = points.len();
+    let left = unit(sub(points[1], points[0]));
+    let right = unit(sub(points[n - 2], points[n - 1]));
+    fit_cubics(&points, left, right, max_error.max(0.0), &mut out);
+    out
+}
+
+// the curves or lines `points` become: runs of 3 lines or more between corners are refit, a run
+// lying within `max_error` of its chord is one line
+fn refit_run(points: &[Point], max_error: Float, commands: &mut Vec<Command>) {
+    let n = points.len();
+    let mut from = 0;
+    let ends = (1..n - 1)
+        .filter(|&i| turn(points[i - 1], points[i], points[i + 1]) > CORNER_ANGLE)
+        .chain([n - 1]);
+
+    for to in ends {
+        let run = &points[from..=to];
+        let straight = run[1..run.len() - 1]
+            .iter()
+            .all(|&p| segment_distance(p, run[0], run[run.len() - 1]) <= max_error);
+        if straight {
+            let (x, y) = run[run.len() - 1];
+            commands.push(Command::LineTo { x, y });
+        } else if run.len() < 4 {
+            commands.extend(run[1..].iter().map(|&(x, y)| Command::LineTo { x, y }));
+        } else {
+            commands.extend(cubics(run, max_error).iter().map(|seg| seg.to_command()));
+        }
+        from = to;
+    }
+}
+
+impl Path {
+    // runs of lines, such as flattened arcs or traced outlines, drawn again with as few smooth
+    // cubics as stay within `max_error` of their points. corners sharper than the smoothing in
+    // `smooth` uses stay corners, and everything that is not a line is kept as it is
+    pub fn fit_curves(&self, max_error: Float) -> Path {
+        let max_error = if max_error.is_nan() {
+            0.0
+        } else {
+            max_error.max(0.0)
+        };
+        let mut commands = Vec::with_capacity(self.commands.len());
+
+        for sp in subpaths(&self.commands) {
+            commands.push(Command::MoveTo {
+                x: sp.start.0,
+                y: sp.start.1,
+            });
+            let mut current = sp.start;
+            let mut run: Vec<Point> = Vec::new();
+            let flush = |run: &mut Vec<Point>, commands: &mut Vec<Command>| {
+                if run.len() > 1 {
+                    refit_run(run, max_error, commands);
+                }
+                run.clear();
+            };
+
+            for cmd in sp.commands {
+                match *cmd {
+                    Command::MoveTo { .. } => {}
+                    Command::LineTo { x, y } => {
+                        if (x, y) != current {
+                            if run.is_empty() {
+                                run.push(current);
+                            }
+                            run.push((x, y));
+                            current = (x, y);
+                        }
+                    }
+                    cmd => {
+                        flush(&mut run, &mut commands);
+                        commands.push(cmd);
+                        current = match cmd {
+                            Command::ClosePath => sp.start,
+                            cmd => cmd.points().last().unwrap_or(current),
+                        };
+                    }
+                }
+            }
+            flush(&mut run, &mut commands);
+        }
+
+        Path::new(commands)
+    }
+}
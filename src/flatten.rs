@@ -0,0 +1,561 @@
+use crate::{
+    offset::segment_distance,
+    path::Path,
+    segment::{dist, push_segments, subpaths, Segment},
+    trace::{trace_count, trace_span},
+    Command, Float, Point,
+};
+
+// recursion guard for degenerate input (NaN/huge coordinates)
+const MAX_DEPTH: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlattenMethod {
+    // evenly spaced steps, as many as the sharpest bend of each curve needs. cheap per point and
+    // branch free, but straight stretches get as many points as the corners
+    Uniform,
+    // recursive subdivision that only splits where the curve is not flat yet
+    #[default]
+    Adaptive,
+}
+
+// a flattened subpath
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    pub closed: bool,
+    // one per point when asked for with `Path::flatten_with_attribute`, empty otherwise
+    pub attributes: Vec<Float>,
+}
+
+// a value `Path::flatten_with_attribute` keeps for every point, for shaders drawing dashes or
+// gradients along the path. lengths are measured along the flattened lines, so they agree with
+// the geometry handed over rather than the exact curves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexAttribute {
+    // the length of the path up to the point, subpaths after one another
+    Distance,
+    // the length of the subpath up to the point
+    SubpathDistance,
+    // `Distance` as a fraction of the whole length, 0 at the start and 1 at the end
+    T,
+}
+
+// distance from p to the line through a and b
+fn line_distance(p: (Float, Float), a: (Float, Float), b: (Float, Float)) -> Float {
+    let len = dist(a, b);
+    if len == 0.0 {
+        return dist(p, a);
+    }
+
+    ((b.0 - a.0) * (a.1 - p.1) - (a.0 - p.0) * (b.1 - a.1)).abs() / len
+}
+
+fn is_flat(seg: &Segment, tolerance: Float) -> bool {
+    match *seg {
+        Segment::Line(..) => true,
+        Segment::Quad(p0, p1, p2) => line_distance(p1, p0, p2) * 0.5 <= tolerance,
+        Segment::Cubic(p0, p1, p2, p3) => {
+            line_distance(p1, p0, p3).max(line_distance(p2, p0, p3)) * 0.75 <= tolerance
+        }
+    }
+}
+
+fn subdivide(seg: &Segment, tolerance: Float, depth: u32, emit: &mut impl FnMut(Point)) {
+    if depth >= MAX_DEPTH || is_flat(seg, tolerance) {
+        emit(seg.to());
+        return;
+    }
+
+    let (a, b) = seg.split(0.5);
+    subdivide(&a, tolerance, depth + 1, emit);
+    subdivide(&b, tolerance, depth + 1, emit);
+}
+
+// steps needed for uniform stepping to stay within `tolerance` (wang's formula)
+fn uniform_steps(seg: &Segment, tolerance: Float) -> usize {
+    // |a - 2b + c|
+    let bend = |a: (Float, Float), b: (Float, Float), c: (Float, Float)| {
+        let (x, y) = (a.0 - 2.0 * b.0 + c.0, a.1 - 2.0 * b.1 + c.1);
+        (x * x + y * y).sqrt()
+    };
+
+    // bound on the second derivative
+    let dd = match *seg {
+        Segment::Line(..) => return 1,
+        Segment::Quad(p0, p1, p2) => 2.0 * bend(p0, p1, p2),
+        Segment::Cubic(p0, p1, p2, p3) => 6.0 * bend(p0, p1, p2).max(bend(p1, p2, p3)),
+    };
+
+    let steps = (dd / (8.0 * tolerance)).sqrt().ceil();
+    if steps.is_nan() {
+        return 1;
+    }
+    (steps as usize).clamp(1, 1 << MAX_DEPTH)
+}
+
+// emit the points of `seg` (excluding its start point) so that no point of the curve is further
+// than `tolerance` from the polyline
+fn emit_segment(
+    seg: &Segment,
+    tolerance: Float,
+    method: FlattenMethod,
+    emit: &mut impl FnMut(Point),
+) {
+    let tolerance = tolerance.max(Float::EPSILON);
+
+    match method {
+        FlattenMethod::Adaptive => subdivide(seg, tolerance, 0, emit),
+        FlattenMethod::Uniform => {
+            let steps = uniform_steps(seg, tolerance);
+            for i in 1..steps {
+                emit(seg.eval(i as Float / steps as Float));
+            }
+            emit(seg.to());
+        }
+    }
+}
+
+pub(crate) fn flatten_segment(
+    seg: &Segment,
+    tolerance: Float,
+    method: FlattenMethod,
+    out: &mut Vec<Point>,
+) {
+    emit_segment(seg, tolerance, method, &mut |p| out.push(p));
+}
+
+// length of the flattened segment
+pub(crate) fn segment_length(seg: &Segment, tolerance: Float) -> Float {
+    let mut length = 0.0;
+    let mut last = seg.from();
+    emit_segment(seg, tolerance, FlattenMethod::Adaptive, &mut |p| {
+        length += dist(last, p);
+        last = p;
+    });
+    length
+}
+
+// the flattened path without allocating: `emit(point, true)` starts a subpath and every
+// `emit(point, false)` continues it. subpaths that draw nothing are skipped
+fn walk(commands: &[Command], tolerance: Float, mut emit: impl FnMut(Point, bool)) {
+    let mut start = (0.0, 0.0);
+    let mut current = start;
+    let mut started = false;
+
+    for cmd in commands {
+        match *cmd {
+            Command::MoveTo { x, y } => {
+                start = (x, y);
+                current = start;
+                started = false;
+            }
+            Command::ClosePath => {
+                if started && current != start {
+                    emit(start, false);
+                }
+                current = start;
+                started = false;
+            }
+            ref cmd => {
+                for seg in Segment::pieces(current, cmd) {
+                    if !started {
+                        emit(current, true);
+                        started = true;
+                    }
+                    emit_segment(&seg, tolerance, FlattenMethod::Adaptive, &mut |p| {
+                        emit(p, false)
+                    });
+                    current = seg.to();
+                }
+            }
+        }
+    }
+}
+
+// append the flattened points of every subpath to `out`, back to back. nothing is allocated once
+// `out` has grown to fit, so a buffer reused across frames stays allocation free. use
+// `for_each_segment` when the breaks between subpaths matter
+pub fn flatten_into(path: &Path, tolerance: Float, out: &mut Vec<Point>) {
+    walk(&path.commands, tolerance, |p, _| out.push(p));
+}
+
+// call `f(from, to)` for every line of the flattened path, without allocating
+pub fn for_each_segment(path: &Path, tolerance: Float, mut f: impl FnMut(Point, Point)) {
+    let mut last = (0.0, 0.0);
+    walk(&path.commands, tolerance, |p, starts| {
+        if !starts {
+            f(last, p);
+        }
+        last = p;
+    });
+}
+
+// one polyline per subpath, paired with whether the subpath was closed
+pub(crate) fn flatten_subpaths(
+    commands: &[Command],
+    tolerance: Float,
+    method: FlattenMethod,
+) -> Vec<(Vec<(Float, Float)>, bool)> {
+    trace_span!("flatten", tolerance);
+
+    let polylines: Vec<_> = subpaths(commands)
+        .iter()
+        .map(|sp| {
+            let mut points = vec![sp.start];
+
+            for seg in sp.segments() {
+                flatten_segment(&seg, tolerance, method, &mut points);
+            }
+
+            (points, sp.closed)
+        })
+        .collect();
+
+    trace_count!(
+        segments = polylines.iter().map(|(p, _)| p.len() - 1).sum::<usize>(),
+        "flattened"
+    );
+
+    polylines
+}
+
+// points `spacing` apart along a polyline, starting with its first point
+fn resample_polyline(points: &[Point], closed: bool, spacing: Float) -> Vec<Point> {
+    let mut out = Vec::new();
+    let Some(&first) = points.first() else {
+        return out;
+    };
+    out.push(first);
+
+    let closing = closed.then_some(first);
+    let mut from = first;
+    // distance walked since the last emitted point
+    let mut walked = 0.0;
+    for &to in points[1..].iter().chain(closing.iter()) {
+        let len = dist(from, to);
+        let mut at = spacing - walked;
+        while at <= len {
+            let t = at / len;
+            out.push((from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t));
+            at += spacing;
+        }
+        walked = len - (at - spacing);
+        from = to;
+    }
+
+    // a closed outline would otherwise end on a point right next to its start
+    if closed && out.len() > 1 && dist(out[out.len() - 1], first) < spacing * 0.5 {
+        out.pop();
+    }
+    out
+}
+
+// douglas-peucker: the fewest of `points` (always keeping both ends) that stay within `tolerance`
+// of the rest
+pub(crate) fn simplify_polyline(points: &[Point], tolerance: Float) -> Vec<Point> {
+    let mut keep = vec![false; points.len()];
+    let mut stack = vec![(0, points.len().saturating_sub(1))];
+
+    while let Some((a, b)) = stack.pop() {
+        keep[a] = true;
+        keep[b] = true;
+
+        let far = (a + 1..b)
+            .map(|i| (i, segment_distance(points[i], points[a], points[b])))
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((i, d)) = far {
+            if d > tolerance {
+                stack.push((a, i));
+                stack.push((i, b));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, keep)| keep.then_some(*p))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SimplifyOptions {
+    // flatten the curves and thin them along with the lines, leaving only straight lines.
+    // subpaths that end up drawing nothing are dropped
+    pub flatten_curves: bool,
+}
+
+impl Path {
+    // fewer commands for the same drawing to within `tolerance`, curves kept as they are
+    pub fn simplify(&self, tolerance: Float) -> Path {
+        self.simplify_with(tolerance, &SimplifyOptions::default())
+    }
+
+    pub fn simplify_with(&self, tolerance: Float, options: &SimplifyOptions) -> Path {
+        if options.flatten_curves {
+            self.simplify_flattened(tolerance)
+        } else {
+            self.simplify_lines(tolerance)
+        }
+    }
+
+    // the path as straight lines, with as few points as stay within `tolerance` of the original
+    fn simplify_flattened(&self, tolerance: Float) -> Path {
+        let mut commands = Vec::new();
+
+        if tolerance.is_nan() || tolerance <= 0.0 {
+            return self.fork();
+        }
+
+        // half the budget for flattening the curves, half for dropping points
+        for (points, closed) in
+            flatten_subpaths(&self.commands, tolerance * 0.5, FlattenMethod::Adaptive)
+        {
+            let points = simplify_polyline(&points, tolerance * 0.5);
+            // closed polylines end on their start again, and a polyline that stays on one point
+            // draws nothing however many points it has
+            if points.len() < if closed { 4 } else { 2 } || points.iter().all(|p| *p == points[0]) {
+                continue;
+            }
+
+            let segments: Vec<Segment> = points
+                .windows(2)
+                .map(|w| Segment::Line(w[0], w[1]))
+                .collect();
+            push_segments(&segments, closed, &mut commands);
+        }
+
+        Path::new(commands)
+    }
+
+    // segments that go nowhere are dropped and every run of lines is thinned with douglas-peucker
+    // to within `tolerance`, which merges the lines lying along each other in the polylines
+    // tracing tools write. a line back to the start of a closed subpath is left to its ClosePath
+    fn simplify_lines(&self, tolerance: Float) -> Path {
+        let tolerance = if tolerance.is_nan() {
+            0.0
+        } else {
+            tolerance.max(0.0)
+        };
+        let mut commands = Vec::with_capacity(self.commands.len());
+
+        for sp in subpaths(&self.commands) {
+            commands.push(Command::MoveTo {
+                x: sp.start.0,
+                y: sp.start.1,
+            });
+            let mut current = sp.start;
+            let mut run: Vec<Point> = Vec::new();
+            let flush = |run: &mut Vec<Point>, commands: &mut Vec<Command>, closing: bool| {
+                if run.is_empty() {
+                    return;
+                }
+                let mut points = simplify_polyline(run, tolerance);
+                if closing && points.len() > 1 && points.last() == Some(&sp.start) {
+                    points.pop();
+                }
+                commands.extend(
+                    points
+                        .iter()
+                        .skip(1)
+                        .map(|&(x, y)| Command::LineTo { x, y }),
+                );
+                run.clear();
+            };
+
+            for cmd in sp.commands {
+                match *cmd {
+                    Command::MoveTo { .. } => {}
+                    Command::LineTo { x, y } => {
+                        if (x, y) != current {
+                            if run.is_empty() {
+                                run.push(current);
+                            }
+                            run.push((x, y));
+                            current = (x, y);
+                        }
+                    }
+                    Command::ClosePath => {
+                        flush(&mut run, &mut commands, true);
+                        commands.push(Command::ClosePath);
+                        current = sp.start;
+                    }
+                    cmd => {
+                        let end = cmd.points().last().unwrap_or(current);
+                        if cmd.points().all(|p| p == current) {
+                            continue;
+                        }
+                        flush(&mut run, &mut commands, false);
+                        commands.push(cmd);
+                        current = end;
+                    }
+                }
+            }
+            flush(&mut run, &mut commands, false);
+        }
+
+        Path::new(commands)
+    }
+
+    // points `spacing` apart along every subpath, measured on a fine flattening
+    pub fn resample(&self, spacing: Float) -> Vec<Polyline> {
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Vec::new();
+        }
+
+        flatten_subpaths(&self.commands, spacing * 0.01, FlattenMethod::Adaptive)
+            .into_iter()
+            .map(|(points, closed)| Polyline {
+                points: resample_polyline(&points, closed, spacing),
+                closed,
+                attributes: Vec::new(),
+            })
+            .collect()
+    }
+
+    // every subpath as its own polyline no further than `tolerance` from the curves, stepped
+    // with `method`
+    pub fn flatten_with(&self, tolerance: Float, method: FlattenMethod) -> Vec<Polyline> {
+        flatten_subpaths(&self.commands, tolerance, method)
+            .into_iter()
+            .map(|(points, closed)| Polyline {
+                points,
+                closed,
+                attributes: Vec::new(),
+            })
+            .collect()
+    }
+
+    // `flatten_with` and `attribute` worked out for every point, so drawing along the path needs
+    // no second pass over the polylines
+    pub fn flatten_with_attribute(
+        &self,
+        tolerance: Float,
+        method: FlattenMethod,
+        attribute: VertexAttribute,
+    ) -> Vec<Polyline> {
+        let mut polylines = self.flatten_with(tolerance, method);
+
+        let mut walked = 0.0;
+        for polyline in &mut polylines {
+            let mut along = 0.0;
+            let mut last = polyline.points.first().copied().unwrap_or((0.0, 0.0));
+            polyline.attributes = polyline
+                .points
+                .iter()
+                .map(|&p| {
+                    along += dist(last, p);
+                    last = p;
+                    match attribute {
+                        VertexAttribute::SubpathDistance => along,
+                        VertexAttribute::Distance | VertexAttribute::T => walked + along,
+                    }
+                })
+                .collect();
+            walked += along;
+        }
+
+        if attribute == VertexAttribute::T && walked > 0.0 {
+            for polyline in &mut polylines {
+                for t in &mut polyline.attributes {
+                    *t /= walked;
+                }
+            }
+        }
+        polylines
+    }
+
+    // every subpath flattened adaptively and back to back into one list, as `flatten_into` does.
+    // handy for triangulation and hit-testing that do not care where subpaths break,
+    // `flatten_with` keeps them apart
+    pub fn flatten(&self, tolerance: Float) -> Vec<Point> {
+        let mut points = Vec::new();
+        flatten_into(self, tolerance, &mut points);
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_path_str;
+
+    fn path(d: &str) -> Path {
+        Path::new(parse_path_str(d).unwrap())
+    }
+
+    fn distance_to_segment(p: Point, a: Point, b: Point) -> Float {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = dx * dx + dy * dy;
+        let t = if len == 0.0 {
+            0.0
+        } else {
+            (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len).clamp(0.0, 1.0)
+        };
+        (p.0 - a.0 - dx * t).hypot(p.1 - a.1 - dy * t)
+    }
+
+    #[test]
+    fn simplify_merges_lines_and_keeps_curves() {
+        let simplified = path("M0 0 L1 0 L2 0 L3 0 C4 1 5 1 6 0 L6 0 L7 .001 L8 0 M0 5 L0 5 L0 6")
+            .simplify(0.01);
+        assert_eq!(simplified.to_d(), "M0 0 L3 0 C4 1 5 1 6 0 L8 0 M0 5 L0 6");
+
+        // the line back to the start is the ClosePath's to draw
+        let simplified = path("M0 0 L5 0 L10 0 L10 10 L0 10 L0 0 Z").simplify(0.01);
+        assert_eq!(simplified.to_d(), "M0 0 L10 0 L10 10 L0 10 Z");
+
+        // within the tolerance only, a real corner stays
+        let simplified = path("M0 0 L5 1 L10 0").simplify(0.5);
+        assert_eq!(simplified.to_d(), "M0 0 L5 1 L10 0");
+        assert_eq!(path("M0 0 L5 1 L10 0").simplify(2.0).to_d(), "M0 0 L10 0");
+    }
+
+    #[test]
+    fn simplify_flattening_curves_stays_within_tolerance() {
+        let original = path("M0 50 C0 -15 100 -15 100 50 S0 115 0 50 Z M200 0 Q250 100 300 0");
+        let options = SimplifyOptions {
+            flatten_curves: true,
+        };
+
+        for tolerance in [0.1, 1.0, 5.0] {
+            let simplified = original.simplify_with(tolerance, &options);
+            assert!(simplified.commands().iter().all(|cmd| matches!(
+                cmd,
+                Command::MoveTo { .. } | Command::LineTo { .. } | Command::ClosePath
+            )));
+
+            // every point of a fine flattening of the curves is near the lines
+            let lines = simplified.flatten_with(tolerance, FlattenMethod::Adaptive);
+            let fine = original.flatten_with(tolerance * 0.01, FlattenMethod::Adaptive);
+            assert_eq!(lines.len(), fine.len());
+            for (lines, fine) in lines.iter().zip(&fine) {
+                assert_eq!(lines.closed, fine.closed);
+                for &p in &fine.points {
+                    let distance = lines
+                        .points
+                        .windows(2)
+                        .map(|w| distance_to_segment(p, w[0], w[1]))
+                        .fold(Float::INFINITY, Float::min);
+                    assert!(distance <= tolerance * 1.01, "{p:?} is {distance} away");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn simplify_flattening_curves_drops_what_draws_nothing() {
+        let options = SimplifyOptions {
+            flatten_curves: true,
+        };
+        let simplified = path("M0 0 L0 0 M10 10 L20 10 M5 5 Z").simplify_with(1.0, &options);
+        assert_eq!(simplified.to_d(), "M10 10 L20 10");
+
+        let original = path("M0 0 Q5 5 10 0");
+        assert_eq!(
+            original.simplify_with(0.0, &options).to_d(),
+            original.to_d()
+        );
+    }
+}
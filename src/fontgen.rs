@@ -0,0 +1,670 @@
+use std::fmt::Write;
+
+use crate::{
+    path::Path,
+    segment::{lerp, Segment},
+    transform::Transform,
+    writer::write_d,
+    Float, Point,
+};
+
+// cubics are split into quadratics until they stay this close, in font units
+const QUAD_TOLERANCE: Float = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontOptions {
+    pub family: String,
+    pub units_per_em: u16,
+    // the share of the em square below the baseline
+    pub descent: Float,
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            family: "Icons".to_string(),
+            units_per_em: 1000,
+            descent: 0.125,
+        }
+    }
+}
+
+impl FontOptions {
+    fn metrics(&self) -> (Float, Float, Float) {
+        let em = Float::from(self.units_per_em.max(16));
+        let descent = (em * self.descent.clamp(0.0, 1.0)).round();
+        (em, em - descent, descent)
+    }
+}
+
+// `path` scaled to fill the em square keeping its proportions, centered in it, and turned into
+// font coordinates: y up with the baseline at 0
+fn place(path: &Path, options: &FontOptions) -> Option<Path> {
+    let (em, ascent, _) = options.metrics();
+    let bounds = path.tight_bounds()?;
+    let extent = bounds.width().max(bounds.height());
+    if extent.is_nan() || extent <= 0.0 {
+        return None;
+    }
+
+    let scale = em / extent;
+    let center = (
+        (bounds.min_x + bounds.max_x) / 2.0,
+        (bounds.min_y + bounds.max_y) / 2.0,
+    );
+    let mut placed = path.fork();
+    placed.transform(
+        &Transform::translate(-center.0, -center.1)
+            .then(&Transform::scale(scale, -scale))
+            .then(&Transform::translate(em / 2.0, ascent - em / 2.0)),
+    );
+    Some(placed)
+}
+
+// the glyphs sorted by codepoint, the first one kept where a codepoint repeats
+fn sorted<'a>(glyphs: &[(char, &'a Path)]) -> Vec<(char, &'a Path)> {
+    let mut glyphs = glyphs.to_vec();
+    glyphs.sort_by_key(|&(c, _)| c);
+    glyphs.dedup_by_key(|&mut (c, _)| c);
+    glyphs
+}
+
+// the quadratics standing in for a cubic: its thirds formula on enough equal pieces to stay within
+// `QUAD_TOLERANCE`
+fn quads(cubic: Segment, out: &mut Vec<(Point, bool)>) {
+    let Segment::Cubic(p0, p1, p2, p3) = cubic else {
+        return;
+    };
+    // the error of one quadratic is at most sqrt(3) / 36 of this, and falls with the cube of the
+    // pieces
+    let third = (
+        p3.0 - 3.0 * p2.0 + 3.0 * p1.0 - p0.0,
+        p3.1 - 3.0 * p2.1 + 3.0 * p1.1 - p0.1,
+    );
+    let error = (third.0 * third.0 + third.1 * third.1).sqrt() * Float::sqrt(3.0) / 36.0;
+    let pieces = (error / QUAD_TOLERANCE).cbrt().ceil().clamp(1.0, 16.0) as usize;
+
+    let mut rest = cubic;
+    for i in 0..pieces {
+        let (piece, after) = rest.split(1.0 / (pieces - i) as Float);
+        rest = after;
+        if let Segment::Cubic(a, b, c, d) = piece {
+            let control = lerp(lerp(a, b, 1.5), lerp(d, c, 1.5), 0.5);
+            out.push((control, false));
+            out.push((d, true));
+        }
+    }
+}
+
+// the contours of a placed path as truetype points, on or off the curve, rounded to font units
+fn contours(path: &Path) -> Vec<Vec<((i16, i16), bool)>> {
+    let round = |(x, y): Point| {
+        (
+            x.round().clamp(-32768.0, 32767.0) as i16,
+            y.round().clamp(-32768.0, 32767.0) as i16,
+        )
+    };
+
+    let mut out = Vec::new();
+    for sp in path.subpaths() {
+        let mut points = vec![(sp.start, true)];
+        for seg in sp.segments() {
+            match seg {
+                Segment::Line(_, to) => points.push((to, true)),
+                Segment::Quad(_, control, to) => {
+                    points.push((control, false));
+                    points.push((to, true));
+                }
+                cubic => quads(cubic, &mut points),
+            }
+        }
+
+        let mut contour: Vec<_> = points.into_iter().map(|(p, on)| (round(p), on)).collect();
+        contour.dedup();
+        // contours close on their own
+        if contour.len() > 1 && contour.last() == contour.first() {
+            contour.pop();
+        }
+        if contour.len() > 2 {
+            out.push(contour);
+        }
+    }
+    out
+}
+
+struct Glyf {
+    data: Vec<u8>,
+    bounds: [i16; 4],
+    points: usize,
+    contours: usize,
+}
+
+fn glyf(contours: &[Vec<((i16, i16), bool)>]) -> Option<Glyf> {
+    if contours.is_empty() {
+        return None;
+    }
+
+    let all = contours.iter().flatten();
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (i16::MAX, i16::MAX, i16::MIN, i16::MIN);
+    for &((x, y), _) in all.clone() {
+        (min_x, min_y) = (min_x.min(x), min_y.min(y));
+        (max_x, max_y) = (max_x.max(x), max_y.max(y));
+    }
+
+    let mut data = Vec::new();
+    data.extend((contours.len() as i16).to_be_bytes());
+    for v in [min_x, min_y, max_x, max_y] {
+        data.extend(v.to_be_bytes());
+    }
+    let mut end = 0;
+    for contour in contours {
+        end += contour.len();
+        data.extend((end as u16 - 1).to_be_bytes());
+    }
+    // no instructions
+    data.extend(0u16.to_be_bytes());
+
+    // every coordinate as a full 16 bit delta, only the on curve bit set
+    data.extend(all.clone().map(|&(_, on)| u8::from(on)));
+    for axis in 0..2 {
+        let mut last = 0i16;
+        for &((x, y), _) in all.clone() {
+            let v = if axis == 0 { x } else { y };
+            data.extend(v.wrapping_sub(last).to_be_bytes());
+            last = v;
+        }
+    }
+    data.resize(data.len().div_ceil(4) * 4, 0);
+
+    Some(Glyf {
+        data,
+        bounds: [min_x, min_y, max_x, max_y],
+        points: end,
+        contours: contours.len(),
+    })
+}
+
+fn checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, word| {
+        let mut bytes = [0; 4];
+        bytes[..word.len()].copy_from_slice(word);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+// the search fields of binary searchable tables: the largest power of 2 not above `n` times
+// `size`, its log2, and what is left over
+fn search(n: usize, size: usize) -> [u16; 3] {
+    let power = if n == 0 { 0 } else { n.ilog2() };
+    let range = (1usize << power) * size;
+    [range as u16, power as u16, (n * size - range) as u16]
+}
+
+fn be16(out: &mut Vec<u8>, values: &[u16]) {
+    for v in values {
+        out.extend(v.to_be_bytes());
+    }
+}
+
+fn be32(out: &mut Vec<u8>, values: &[u32]) {
+    for v in values {
+        out.extend(v.to_be_bytes());
+    }
+}
+
+// format 4 for codepoints in the basic multilingual plane, format 12 for all of them
+fn cmap(codes: &[(u32, u16)]) -> Vec<u8> {
+    // as many as its 16 bit length has room for, format 12 has the rest
+    let bmp: Vec<_> = codes
+        .iter()
+        .filter(|(c, _)| *c < 0xffff)
+        .take(8000)
+        .collect();
+    let mut format4 = Vec::new();
+    let segments = bmp.len() + 1;
+    let [range, selector, shift] = search(segments, 2);
+    be16(
+        &mut format4,
+        &[4, 0, 0, segments as u16 * 2, range, selector, shift],
+    );
+    // one segment per codepoint, then the closing one
+    let ends = bmp.iter().map(|(c, _)| *c as u16).chain([0xffff]);
+    ends.clone().for_each(|c| format4.extend(c.to_be_bytes()));
+    format4.extend(0u16.to_be_bytes());
+    ends.for_each(|c| format4.extend(c.to_be_bytes()));
+    let deltas = bmp
+        .iter()
+        .map(|(c, g)| g.wrapping_sub(*c as u16))
+        .chain([1]);
+    deltas.for_each(|d| format4.extend(d.to_be_bytes()));
+    (0..segments).for_each(|_| format4.extend(0u16.to_be_bytes()));
+    let length = format4.len() as u16;
+    format4[2..4].copy_from_slice(&length.to_be_bytes());
+
+    let mut format12 = Vec::new();
+    be16(&mut format12, &[12, 0]);
+    be32(
+        &mut format12,
+        &[16 + codes.len() as u32 * 12, 0, codes.len() as u32],
+    );
+    for &(c, g) in codes {
+        be32(&mut format12, &[c, c, u32::from(g)]);
+    }
+
+    let mut out = Vec::new();
+    be16(&mut out, &[0, 2]);
+    be16(&mut out, &[3, 1]);
+    be32(&mut out, &[4 + 8 * 2]);
+    be16(&mut out, &[3, 10]);
+    be32(&mut out, &[4 + 8 * 2 + format4.len() as u32]);
+    out.extend(format4);
+    out.extend(format12);
+    out
+}
+
+fn name(family: &str) -> Vec<u8> {
+    let postscript: String = family
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    let postscript = if postscript.is_empty() {
+        "Icons".to_string()
+    } else {
+        postscript
+    };
+    let strings = [
+        (1, family),
+        (2, "Regular"),
+        (3, family),
+        (4, family),
+        (6, postscript.as_str()),
+    ];
+
+    let mut records = Vec::new();
+    let mut storage = Vec::new();
+    for (id, s) in strings {
+        let utf16: Vec<u8> = s.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        be16(
+            &mut records,
+            &[3, 1, 0x409, id, utf16.len() as u16, storage.len() as u16],
+        );
+        storage.extend(utf16);
+    }
+
+    let mut out = Vec::new();
+    be16(
+        &mut out,
+        &[0, strings.len() as u16, 6 + records.len() as u16],
+    );
+    out.extend(records);
+    out.extend(storage);
+    out
+}
+
+// every glyph fitted to the em square and centered in it, as a truetype font mapping each char
+// to its path. paths that draw nothing give empty glyphs, a char given twice keeps its first path
+pub fn to_ttf(glyphs: &[(char, &Path)], options: &FontOptions) -> Vec<u8> {
+    let (em, ascent, descent) = options.metrics();
+    let advance = em as u16;
+    let mut glyphs = sorted(glyphs);
+    // glyph ids are 16 bit and the first one is taken
+    glyphs.truncate(usize::from(u16::MAX) - 1);
+
+    // glyph 0 is the empty .notdef
+    let outlines: Vec<Option<Glyf>> = std::iter::once(None)
+        .chain(
+            glyphs
+                .iter()
+                .map(|(_, path)| place(path, options).and_then(|placed| glyf(&contours(&placed)))),
+        )
+        .collect();
+    let count = outlines.len() as u16;
+
+    let mut glyf_table = Vec::new();
+    let mut loca = Vec::new();
+    let mut hmtx = Vec::new();
+    let mut bounds = [i16::MAX, i16::MAX, i16::MIN, i16::MIN];
+    let (mut max_points, mut max_contours) = (0, 0);
+    for outline in &outlines {
+        be32(&mut loca, &[glyf_table.len() as u32]);
+        let lsb = outline.as_ref().map_or(0, |g| g.bounds[0]);
+        be16(&mut hmtx, &[advance, lsb as u16]);
+
+        if let Some(g) = outline {
+            glyf_table.extend(&g.data);
+            bounds = [
+                bounds[0].min(g.bounds[0]),
+                bounds[1].min(g.bounds[1]),
+                bounds[2].max(g.bounds[2]),
+                bounds[3].max(g.bounds[3]),
+            ];
+            max_points = max_points.max(g.points);
+            max_contours = max_contours.max(g.contours);
+        }
+    }
+    be32(&mut loca, &[glyf_table.len() as u32]);
+    if bounds[0] > bounds[2] {
+        bounds = [0; 4];
+    }
+
+    let mut head = Vec::new();
+    be32(&mut head, &[0x0001_0000, 0x0001_0000, 0, 0x5F0F_3CF5]);
+    be16(&mut head, &[0b1011, advance]);
+    // created and modified, left at the epoch of the format
+    be32(&mut head, &[0, 0, 0, 0]);
+    be16(&mut head, &bounds.map(|v| v as u16));
+    // mac style, smallest readable size, direction hint, long loca offsets, glyph format
+    be16(&mut head, &[0, 8, 2, 1, 0]);
+
+    let mut hhea = Vec::new();
+    be32(&mut hhea, &[0x0001_0000]);
+    be16(
+        &mut hhea,
+        &[
+            ascent as u16,
+            (-descent) as i16 as u16,
+            0,
+            advance,
+            bounds[0] as u16,
+            (i32::from(advance) - i32::from(bounds[2])) as i16 as u16,
+            bounds[2] as u16,
+            1,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            count,
+        ],
+    );
+
+    let mut maxp = Vec::new();
+    be32(&mut maxp, &[0x0001_0000]);
+    be16(
+        &mut maxp,
+        &[
+            count,
+            max_points as u16,
+            max_contours as u16,
+            0,
+            0,
+            2,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ],
+    );
+
+    let codes: Vec<(u32, u16)> = glyphs
+        .iter()
+        .enumerate()
+        .map(|(i, &(c, _))| (c as u32, i as u16 + 1))
+        .collect();
+    let first = codes.first().map_or(0, |&(c, _)| c.min(0xffff) as u16);
+    let last = codes.last().map_or(0, |&(c, _)| c.min(0xffff) as u16);
+
+    let mut os2 = Vec::new();
+    be16(&mut os2, &[4, advance, 400, 5, 0]);
+    // sub and superscript sizes and offsets, the strikeout, then the family class
+    let part = |share: Float| (em * share) as u16;
+    be16(
+        &mut os2,
+        &[
+            part(0.65),
+            part(0.7),
+            0,
+            part(0.14),
+            part(0.65),
+            part(0.7),
+            0,
+            part(0.48),
+            part(0.05),
+            part(0.26),
+            0,
+        ],
+    );
+    os2.extend([0; 10]);
+    be32(&mut os2, &[0, 0, 0, 0]);
+    os2.extend(*b"NONE");
+    be16(
+        &mut os2,
+        &[
+            0x40,
+            first,
+            last,
+            ascent as u16,
+            (-descent) as i16 as u16,
+            0,
+            ascent as u16,
+            descent as u16,
+        ],
+    );
+    be32(&mut os2, &[1, 0]);
+    be16(&mut os2, &[0, 0, 0, 32, 0]);
+
+    let mut post = Vec::new();
+    be32(&mut post, &[0x0003_0000, 0]);
+    be16(
+        &mut post,
+        &[(-(em / 10.0)) as i16 as u16, (em / 20.0) as u16],
+    );
+    be32(&mut post, &[1, 0, 0, 0, 0]);
+
+    let tables: [(&[u8; 4], Vec<u8>); 10] = [
+        (b"OS/2", os2),
+        (b"cmap", cmap(&codes)),
+        (b"glyf", glyf_table),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+        (b"name", name(&options.family)),
+        (b"post", post),
+    ];
+
+    let mut out = Vec::new();
+    let [range, selector, shift] = search(tables.len(), 16);
+    be32(&mut out, &[0x0001_0000]);
+    be16(&mut out, &[tables.len() as u16, range, selector, shift]);
+
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_at = 0;
+    for (tag, table) in &tables {
+        if *tag == b"head" {
+            head_at = offset;
+        }
+        out.extend(*tag);
+        be32(
+            &mut out,
+            &[checksum(table), offset as u32, table.len() as u32],
+        );
+        offset += table.len().div_ceil(4) * 4;
+    }
+    for (_, table) in &tables {
+        out.extend(table);
+        out.resize(out.len().div_ceil(4) * 4, 0);
+    }
+
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+    out[head_at + 8..head_at + 12].copy_from_slice(&adjustment.to_be_bytes());
+    out
+}
+
+// the same font as `to_ttf` written as an svg font, which some icon tools still take
+pub fn to_svg_font(glyphs: &[(char, &Path)], options: &FontOptions) -> String {
+    let (em, ascent, descent) = options.metrics();
+    let family: String = options
+        .family
+        .chars()
+        .filter(|c| !matches!(c, '"' | '<' | '&'))
+        .collect();
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><defs><font id=\"{family}\" horiz-adv-x=\"{em}\">\
+         <font-face font-family=\"{family}\" units-per-em=\"{em}\" ascent=\"{ascent}\" descent=\"-{descent}\"/>\
+         <missing-glyph horiz-adv-x=\"{em}\"/>"
+    );
+    for (c, path) in sorted(glyphs) {
+        let d = place(path, options).map_or(String::new(), |p| write_d(p.commands()));
+        let _ = write!(out, "<glyph unicode=\"&#x{:x};\" d=\"{d}\"/>", u32::from(c));
+    }
+    out.push_str("</font></defs></svg>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_path_str;
+
+    fn u16_at(data: &[u8], at: usize) -> u16 {
+        u16::from_be_bytes([data[at], data[at + 1]])
+    }
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+    }
+
+    // the tables of a font by tag, checking each against its checksum on the way
+    fn tables(font: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        assert_eq!(u32_at(font, 0), 0x0001_0000);
+        let count = usize::from(u16_at(font, 4));
+        assert_eq!(
+            [u16_at(font, 6), u16_at(font, 8), u16_at(font, 10)],
+            search(count, 16)
+        );
+
+        (0..count)
+            .map(|i| {
+                let record = 12 + i * 16;
+                let tag: [u8; 4] = font[record..record + 4].try_into().unwrap();
+                let offset = u32_at(font, record + 8) as usize;
+                let length = u32_at(font, record + 12) as usize;
+                assert_eq!(offset % 4, 0);
+                let table = &font[offset..offset + length];
+
+                // the head table is summed with its checksum adjustment left at 0
+                let mut summed = table.to_vec();
+                if &tag == b"head" {
+                    summed[8..12].fill(0);
+                }
+                assert_eq!(checksum(&summed), u32_at(font, record + 4), "{tag:?}");
+                (tag, table)
+            })
+            .collect()
+    }
+
+    fn table<'a>(tables: &[([u8; 4], &'a [u8])], tag: &[u8; 4]) -> &'a [u8] {
+        tables.iter().find(|(t, _)| t == tag).unwrap().1
+    }
+
+    // the glyph for `c` the way a format 4 cmap is searched
+    fn format4(subtable: &[u8], c: u16) -> u16 {
+        assert_eq!(u16_at(subtable, 0), 4);
+        let segments = usize::from(u16_at(subtable, 6) / 2);
+        let ends = 14;
+        let starts = ends + segments * 2 + 2;
+        let deltas = starts + segments * 2;
+        let ranges = deltas + segments * 2;
+
+        let Some(i) = (0..segments).find(|&i| u16_at(subtable, ends + i * 2) >= c) else {
+            return 0;
+        };
+        if u16_at(subtable, starts + i * 2) > c {
+            return 0;
+        }
+        assert_eq!(u16_at(subtable, ranges + i * 2), 0);
+        c.wrapping_add(u16_at(subtable, deltas + i * 2))
+    }
+
+    fn format12(subtable: &[u8], c: u32) -> u16 {
+        assert_eq!(u16_at(subtable, 0), 12);
+        (0..u32_at(subtable, 12) as usize)
+            .map(|i| 16 + i * 12)
+            .find(|&at| (u32_at(subtable, at)..=u32_at(subtable, at + 4)).contains(&c))
+            .map_or(0, |at| {
+                (u32_at(subtable, at + 8) + c - u32_at(subtable, at)) as u16
+            })
+    }
+
+    #[test]
+    fn ttf_tables_agree() {
+        let paths = [
+            "M0 0 L10 0 L10 10 L0 10 Z",
+            "M0 0 L10 0 L10 10 L0 10 Z M3 3 L3 7 L7 7 L7 3 Z",
+            "M0 0 C 0 10 10 10 10 0 Z",
+            "",
+        ]
+        .map(|d| Path::new(parse_path_str(d).unwrap()));
+        let glyphs = [
+            ('b', &paths[0]),
+            ('a', &paths[1]),
+            ('\u{1f600}', &paths[2]),
+            ('c', &paths[3]),
+            ('a', &paths[0]),
+        ];
+        let font = to_ttf(&glyphs, &FontOptions::default());
+        let tables = tables(&font);
+
+        // the whole font sums to the magic the adjustment is there for
+        let head = table(&tables, b"head");
+        assert_eq!(u32_at(head, 12), 0x5F0F_3CF5);
+        assert_eq!(checksum(&font), 0xB1B0_AFBA);
+
+        // .notdef then the glyphs in codepoint order, `a` keeping its first path
+        let count = usize::from(u16_at(table(&tables, b"maxp"), 4));
+        assert_eq!(count, 5);
+
+        // long offsets, one per glyph and one for the end, into the glyf table
+        assert_eq!(u16_at(head, 50), 1);
+        let (loca, glyf) = (table(&tables, b"loca"), table(&tables, b"glyf"));
+        assert_eq!(loca.len(), (count + 1) * 4);
+        let offsets: Vec<_> = (0..=count).map(|i| u32_at(loca, i * 4) as usize).collect();
+        assert!(offsets.windows(2).all(|w| w[0] <= w[1] && w[0] % 4 == 0));
+        assert_eq!(offsets[count], glyf.len());
+        let contours = |glyph: usize| {
+            let (start, end) = (offsets[glyph], offsets[glyph + 1]);
+            (start < end).then(|| u16_at(glyf, start))
+        };
+        assert_eq!(
+            (0..count).map(contours).collect::<Vec<_>>(),
+            [None, Some(2), Some(1), None, Some(1)]
+        );
+
+        // each glyph's box inside the font's
+        let font_box = [36, 38, 40, 42].map(|at| u16_at(head, at) as i16);
+        for glyph in (0..count).filter(|&g| contours(g).is_some()) {
+            let b = [2, 4, 6, 8].map(|at| u16_at(glyf, offsets[glyph] + at) as i16);
+            assert!(b[0] >= font_box[0] && b[1] >= font_box[1], "{glyph}");
+            assert!(b[2] <= font_box[2] && b[3] <= font_box[3], "{glyph}");
+        }
+
+        let cmap = table(&tables, b"cmap");
+        assert_eq!(u16_at(cmap, 2), 2);
+        let subtable = |platform: u16, encoding: u16| {
+            let record = (0..2)
+                .map(|i| 4 + i * 8)
+                .find(|&at| [u16_at(cmap, at), u16_at(cmap, at + 2)] == [platform, encoding])
+                .unwrap();
+            &cmap[u32_at(cmap, record + 4) as usize..]
+        };
+        let bmp = subtable(3, 1);
+        for (c, glyph) in [('a', 1), ('b', 2), ('c', 3), ('d', 0), ('\0', 0)] {
+            assert_eq!(format4(bmp, c as u16), glyph, "{c:?}");
+        }
+        let full = subtable(3, 10);
+        for (c, glyph) in [('a', 1), ('c', 3), ('\u{1f600}', 4), ('\u{1f601}', 0)] {
+            assert_eq!(format12(full, c as u32), glyph, "{c:?}");
+        }
+    }
+}
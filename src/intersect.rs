@@ -0,0 +1,109 @@
+use crate::{
+    path::Path,
+    roots::{coefficients, unit_roots},
+    segment::Segment,
+    to_f64, Command, Float, Point,
+};
+
+// how far past the ends of the line an intersection still counts, in units of its length
+const SLACK: Float = 1e-6;
+
+// curve parameters in [0, 1] where `seg` meets the infinite line through `p0` and `p1`
+pub(crate) fn line_roots(seg: &Segment, p0: Point, p1: Point) -> Vec<Float> {
+    // signed distance from the line (scaled), as a polynomial in the segment's t
+    let (nx, ny) = (to_f64(p0.1 - p1.1), to_f64(p1.0 - p0.0));
+    let xs = coefficients(seg, |p| p.0);
+    let ys = coefficients(seg, |p| p.1);
+    let mut poly = [0.0; 4];
+    for i in 0..4 {
+        poly[i] = nx * xs[i] + ny * ys[i];
+    }
+    poly[3] -= nx * to_f64(p0.0) + ny * to_f64(p0.1);
+
+    unit_roots(poly)
+}
+
+fn intersect_segment(
+    seg: &Segment,
+    p0: Point,
+    p1: Point,
+    include_end: bool,
+    out: &mut Vec<(Point, Float, Float)>,
+) {
+    let d = (p1.0 - p0.0, p1.1 - p0.1);
+    let len_sq = d.0 * d.0 + d.1 * d.1;
+    if len_sq == 0.0 {
+        return;
+    }
+
+    for t in line_roots(seg, p0, p1) {
+        // the next segment reports its start
+        if t >= 1.0 - SLACK && !include_end {
+            continue;
+        }
+
+        let point = seg.eval(t);
+        let t_line = ((point.0 - p0.0) * d.0 + (point.1 - p0.1) * d.1) / len_sq;
+        if (-SLACK..=1.0 + SLACK).contains(&t_line) {
+            out.push((point, t_line.clamp(0.0, 1.0), t));
+        }
+    }
+}
+
+impl Path {
+    // where the segment from `p0` to `p1` crosses the outline, ordered along the line. each hit is
+    // `(point, t_line, t_path)` with `t_line` in [0, 1] from `p0` to `p1` and `t_path` the index
+    // into `commands()` of the command drawing the hit segment plus the curve parameter on it, so
+    // 3.25 is a quarter along the segment drawn by command 3 (a ClosePath for closing lines). the
+    // very end of an open subpath drawn by command 3 is 4.0. stretches where the line runs along a
+    // straight segment are not reported
+    pub fn intersect_line(&self, p0: Point, p1: Point) -> Vec<(Point, Float, Float)> {
+        let mut hits = Vec::new();
+        let mut found = Vec::new();
+
+        let mut start = (0.0, 0.0);
+        let mut current = start;
+        for (i, cmd) in self.commands.iter().enumerate() {
+            let pieces: Vec<Segment> = match *cmd {
+                Command::MoveTo { x, y } => {
+                    start = (x, y);
+                    current = start;
+                    continue;
+                }
+                Command::ClosePath => {
+                    let seg = Segment::Line(current, start);
+                    current = start;
+                    if seg.from() == seg.to() {
+                        continue;
+                    }
+                    vec![seg]
+                }
+                ref cmd => Segment::pieces(current, cmd).collect(),
+            };
+            let Some(last) = pieces.last() else {
+                continue;
+            };
+            current = last.to();
+
+            // the end of an open subpath is not the start of anything else
+            let include_end = !matches!(cmd, Command::ClosePath)
+                && matches!(
+                    self.commands.get(i + 1),
+                    None | Some(Command::MoveTo { .. })
+                );
+
+            // an arc's cubics share its command, each taking an equal share of the parameter
+            let n = pieces.len();
+            for (k, seg) in pieces.iter().enumerate() {
+                found.clear();
+                intersect_segment(seg, p0, p1, include_end && k + 1 == n, &mut found);
+                hits.extend(found.iter().map(|&(point, t_line, t)| {
+                    (point, t_line, i as Float + (k as Float + t) / n as Float)
+                }));
+            }
+        }
+
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits
+    }
+}
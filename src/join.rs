@@ -0,0 +1,164 @@
+use crate::{
+    path::Path,
+    segment::{dist, Segment, Subpath},
+    viewbox::calculate_bb,
+    Command, Float,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinMode {
+    // start the other path as a new subpath
+    #[default]
+    Gap,
+    // continue the current subpath with a straight line to the other path
+    Line,
+    // continue with a curve leaving and arriving along the tangents of both ends
+    Smooth,
+}
+
+fn normalize((x, y): (Float, Float)) -> Option<(Float, Float)> {
+    let len = (x * x + y * y).sqrt();
+    (len > Float::EPSILON && len.is_finite()).then(|| (x / len, y / len))
+}
+
+// unit tangent at t (0 or 1), falling back to the chord where a control point sits on the end
+pub(crate) fn tangent(seg: &Segment, t: Float) -> Option<(Float, Float)> {
+    let (from, to) = (seg.from(), seg.to());
+    normalize(seg.derivative(t)).or_else(|| normalize((to.0 - from.0, to.1 - from.1)))
+}
+
+// a cubic from `from` to `to`, leaving along `out` and arriving along `into`. either direction
+// may be missing, that end then aims at the other end
+pub(crate) fn bridge(
+    from: (Float, Float),
+    out: Option<(Float, Float)>,
+    to: (Float, Float),
+    into: Option<(Float, Float)>,
+) -> Command {
+    let reach = dist(from, to) / 3.0;
+    let chord = normalize((to.0 - from.0, to.1 - from.1)).unwrap_or((0.0, 0.0));
+    let out = out.unwrap_or(chord);
+    let into = into.unwrap_or(chord);
+
+    Command::CurveTo {
+        x1: from.0 + out.0 * reach,
+        y1: from.1 + out.1 * reach,
+        x2: to.0 - into.0 * reach,
+        y2: to.1 - into.1 * reach,
+        x: to.0,
+        y: to.1,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CloseMode {
+    // a straight segment back to the start
+    #[default]
+    Straight,
+    // a curve back to the start that continues the last segment and runs into the first
+    Smooth,
+}
+
+impl Path {
+    // close every open subpath that draws something
+    pub fn close_all(&mut self, mode: CloseMode) {
+        let mut commands = Vec::with_capacity(self.commands.len());
+
+        for sp in self.subpaths() {
+            commands.extend_from_slice(sp.commands);
+            if sp.closed {
+                continue;
+            }
+
+            let (Some(first), Some(last)) = (sp.segments().next(), sp.segments().last()) else {
+                continue;
+            };
+
+            let end = last.to();
+            if mode == CloseMode::Smooth && end != sp.start {
+                commands.push(bridge(
+                    end,
+                    tangent(&last, 1.0),
+                    sp.start,
+                    tangent(&first, 0.0),
+                ));
+            }
+            commands.push(Command::ClosePath);
+        }
+
+        self.set_commands(commands);
+    }
+
+    // append `other` after this path, keeping the direction of both
+    pub fn join(&mut self, other: &Path, mode: JoinMode) {
+        let theirs = other.subpaths();
+        let Some(first) = theirs.first() else {
+            return;
+        };
+
+        let ours = self.subpaths();
+        let Some(last) = ours.last() else {
+            *self = other.fork();
+            return;
+        };
+
+        let end = last.end();
+        let end_tangent = last.segments().last().and_then(|seg| tangent(&seg, 1.0));
+        let start = first.start;
+        let start_tangent = first.segments().next().and_then(|seg| tangent(&seg, 0.0));
+
+        let mut rest = other.commands.as_slice();
+        match mode {
+            JoinMode::Gap => {
+                // a path that does not open with a MoveTo starts at the origin
+                if !matches!(rest.first(), Some(Command::MoveTo { .. })) {
+                    self.commands_mut().push(Command::MoveTo { x: 0.0, y: 0.0 });
+                }
+            }
+            JoinMode::Line | JoinMode::Smooth => {
+                if let Some(Command::MoveTo { .. }) = rest.first() {
+                    rest = &rest[1..];
+                }
+
+                if end != start {
+                    self.commands_mut().push(match mode {
+                        JoinMode::Smooth => bridge(end, end_tangent, start, start_tangent),
+                        _ => Command::LineTo {
+                            x: start.0,
+                            y: start.1,
+                        },
+                    });
+                }
+
+                // the joined subpath now starts at our start, so the other path closing its first
+                // subpath has to be spelled out to keep its shape
+                if first.closed {
+                    let open = Subpath {
+                        commands: &first.commands[..first.commands.len() - 1],
+                        closed: false,
+                        ..*first
+                    };
+                    let close = open.commands.len() - (other.commands.len() - rest.len());
+
+                    self.commands_mut().extend_from_slice(&rest[..close]);
+                    if open.end() != start {
+                        self.commands_mut().push(Command::LineTo {
+                            x: start.0,
+                            y: start.1,
+                        });
+                    }
+                    rest = &rest[close + 1..];
+                    if !matches!(rest.first(), Some(Command::MoveTo { .. }) | None) {
+                        self.commands_mut().push(Command::MoveTo {
+                            x: start.0,
+                            y: start.1,
+                        });
+                    }
+                }
+            }
+        }
+
+        self.commands_mut().extend_from_slice(rest);
+        self.bb = calculate_bb(self.commands.iter());
+    }
+}
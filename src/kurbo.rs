@@ -0,0 +1,100 @@
+use ::kurbo::{BezPath, PathEl};
+
+use crate::{path::Path, segment::Segment, to_f64, Command, Float, Point};
+
+fn kurbo_point((x, y): Point) -> ::kurbo::Point {
+    ::kurbo::Point::new(to_f64(x), to_f64(y))
+}
+
+fn el(seg: Segment) -> PathEl {
+    match seg {
+        Segment::Line(_, to) => PathEl::LineTo(kurbo_point(to)),
+        Segment::Quad(_, ctrl, to) => PathEl::QuadTo(kurbo_point(ctrl), kurbo_point(to)),
+        Segment::Cubic(_, ctrl1, ctrl2, to) => {
+            PathEl::CurveTo(kurbo_point(ctrl1), kurbo_point(ctrl2), kurbo_point(to))
+        }
+    }
+}
+
+// the elements drawing `cmd` when the pen is at `from`. smooth commands come out as the curves
+// they draw and arcs as up to four cubics, since kurbo has no element for either
+pub fn path_els(from: Point, cmd: &Command) -> Vec<PathEl> {
+    match *cmd {
+        Command::MoveTo { x, y } => vec![PathEl::MoveTo(kurbo_point((x, y)))],
+        Command::ClosePath => vec![PathEl::ClosePath],
+        ref cmd => Segment::pieces(from, cmd).map(el).collect(),
+    }
+}
+
+impl From<&Path> for BezPath {
+    fn from(path: &Path) -> BezPath {
+        let mut out = BezPath::new();
+
+        let mut start = (0.0, 0.0);
+        let mut pen = start;
+        // kurbo wants every subpath to begin with a move, svg lets one follow a close without
+        let mut open = false;
+        for cmd in path.commands() {
+            match *cmd {
+                Command::MoveTo { x, y } => (start, open) = ((x, y), true),
+                Command::ClosePath => {}
+                _ if !open => {
+                    out.push(PathEl::MoveTo(kurbo_point(start)));
+                    open = true;
+                }
+                _ => {}
+            }
+
+            out.extend(path_els(pen, cmd));
+
+            pen = match *cmd {
+                Command::ClosePath => {
+                    open = false;
+                    start
+                }
+                ref cmd => cmd.points().last().unwrap_or(pen),
+            };
+        }
+
+        out
+    }
+}
+
+impl From<&BezPath> for Path {
+    fn from(path: &BezPath) -> Path {
+        let p = |p: ::kurbo::Point| (p.x as Float, p.y as Float);
+
+        let commands = path
+            .elements()
+            .iter()
+            .map(|el| match *el {
+                PathEl::MoveTo(to) => {
+                    let (x, y) = p(to);
+                    Command::MoveTo { x, y }
+                }
+                PathEl::LineTo(to) => {
+                    let (x, y) = p(to);
+                    Command::LineTo { x, y }
+                }
+                PathEl::QuadTo(ctrl, to) => {
+                    let ((x1, y1), (x, y)) = (p(ctrl), p(to));
+                    Command::QuadraticBezierCurveTo { x1, y1, x, y }
+                }
+                PathEl::CurveTo(ctrl1, ctrl2, to) => {
+                    let ((x1, y1), (x2, y2), (x, y)) = (p(ctrl1), p(ctrl2), p(to));
+                    Command::CurveTo {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    }
+                }
+                PathEl::ClosePath => Command::ClosePath,
+            })
+            .collect();
+
+        Path::new(commands)
+    }
+}
@@ -0,0 +1,219 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{
+    fill::fill_rings,
+    offset::{oriented, ring_distance},
+    path::Path,
+    scanline::{contains, extent, spans, FillRule},
+    Float, Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LabelPos {
+    // the center of mass of the filled region, which can fall outside concave shapes
+    Centroid,
+    // the point inside the filled region furthest from its outline, where the widest label fits
+    PoleOfInaccessibility,
+    // this far along the outline, measured like `Path::length`
+    Along(Float),
+}
+
+// a square of the polylabel search, `d` is the signed distance from its center to the outline
+struct Cell {
+    center: Point,
+    half: Float,
+    d: Float,
+}
+
+impl Cell {
+    fn new(center: Point, half: Float, rings: &[Vec<Point>]) -> Cell {
+        let d = rings
+            .iter()
+            .map(|ring| ring_distance(ring, center))
+            .fold(Float::INFINITY, Float::min);
+        let d = if contains(rings, center, FillRule::EvenOdd) {
+            d
+        } else {
+            -d
+        };
+        Cell { center, half, d }
+    }
+
+    // the furthest any point of the cell can be from the outline
+    fn potential(&self) -> Float {
+        self.d + self.half * crate::consts::SQRT_2
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.potential().total_cmp(&other.potential())
+    }
+}
+
+// area centroid, holes are told apart by nesting so they subtract whichever way they wind
+fn centroid(rings: &[Vec<Point>]) -> Option<Point> {
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for ring in oriented(rings, false) {
+        for i in 0..ring.len() {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            let cross = a.0 * b.1 - b.0 * a.1;
+            area += cross;
+            cx += (a.0 + b.0) * cross;
+            cy += (a.1 + b.1) * cross;
+        }
+    }
+
+    (area != 0.0).then(|| (cx / (3.0 * area), cy / (3.0 * area)))
+}
+
+// polylabel: split the bounding box into cells and keep refining the ones that could still beat
+// the best center found, until none can by more than `precision`
+fn pole(rings: &[Vec<Point>], precision: Float) -> Option<Point> {
+    let (min, max) = extent(rings)?;
+    let (w, h) = (max.0 - min.0, max.1 - min.1);
+    let size = w.min(h);
+    if size <= 0.0 {
+        return Some(min);
+    }
+
+    let half = size / 2.0;
+    let mut queue = BinaryHeap::new();
+    let mut y = min.1;
+    while y < max.1 {
+        let mut x = min.0;
+        while x < max.0 {
+            queue.push(Cell::new((x + half, y + half), half, rings));
+            x += size;
+        }
+        y += size;
+    }
+
+    let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+    let mut best = Cell::new(center, 0.0, rings);
+    if let Some(c) = centroid(rings) {
+        let cell = Cell::new(c, 0.0, rings);
+        if cell.d > best.d {
+            best = cell;
+        }
+    }
+
+    while let Some(cell) = queue.pop() {
+        if cell.d > best.d {
+            best = Cell { half: 0.0, ..cell };
+        }
+        if cell.potential() - best.d <= precision {
+            continue;
+        }
+
+        let half = cell.half / 2.0;
+        for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            let center = (cell.center.0 + dx * half, cell.center.1 + dy * half);
+            queue.push(Cell::new(center, half, rings));
+        }
+    }
+
+    Some(best.center)
+}
+
+// the parts of both interval lists that overlap
+fn overlap(a: &[(Float, Float)], b: &[(Float, Float)]) -> Vec<(Float, Float)> {
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let (lo, hi) = (a[i].0.max(b[j].0), a[i].1.min(b[j].1));
+        if lo < hi {
+            out.push((lo, hi));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
+
+// what stays inside for the whole band from `top` to `top + height`. the rings are polygons, so
+// the span ends only turn at vertices and checking there and just inside both edges is exact
+fn band_spans(
+    rings: &[Vec<Point>],
+    ys: &[Float],
+    top: Float,
+    height: Float,
+) -> Vec<(Float, Float)> {
+    // an edge lying exactly on a flat top or bottom has no crossing
+    let inset = height * 1e-4;
+    let (top, bottom) = (top + inset, top + height - inset);
+    let mut out = spans(rings, top, FillRule::NonZero);
+    for &y in ys.iter().filter(|&&y| y > top && y < bottom) {
+        out = overlap(&out, &spans(rings, y, FillRule::NonZero));
+    }
+    overlap(&out, &spans(rings, bottom, FillRule::NonZero))
+}
+
+impl Path {
+    // the widest horizontal strip `height` tall that fits inside the filled (non-zero) region, as
+    // its top left corner and width. strips lined up with the outline's corners are tried along
+    // with a fine sweep between them, `None` when nothing that tall fits
+    pub fn widest_horizontal_band(&self, height: Float) -> Option<(Point, Float)> {
+        if height.is_nan() || height <= 0.0 {
+            return None;
+        }
+
+        let tolerance = (self.bb.0.max(self.bb.1) * 1e-3).max(1e-3);
+        let rings = fill_rings(self, tolerance);
+        let (min, max) = extent(&rings)?;
+
+        let mut ys: Vec<Float> = rings.iter().flatten().map(|p| p.1).collect();
+        ys.sort_by(Float::total_cmp);
+        ys.dedup();
+
+        let step = height / 8.0;
+        let sweep = (0..)
+            .map(|i| min.1 + i as Float * step)
+            .take_while(|&y| y + height <= max.1);
+        let aligned = ys.iter().flat_map(|&y| [y, y - height]);
+
+        let mut best: Option<(Point, Float)> = None;
+        for top in sweep.chain(aligned) {
+            if top < min.1 || top + height > max.1 {
+                continue;
+            }
+
+            for (x0, x1) in band_spans(&rings, &ys, top, height) {
+                if !matches!(best, Some((_, w)) if w >= x1 - x0) {
+                    best = Some(((x0, top), x1 - x0));
+                }
+            }
+        }
+
+        best
+    }
+
+    // where to put a label for the shape, `None` when the path has nothing to anchor to. holes are
+    // told apart by nesting, so they stay empty whichever way they wind
+    pub fn label_anchor(&self, pos: LabelPos) -> Option<Point> {
+        let tolerance = (self.bb.0.max(self.bb.1) * 1e-3).max(1e-3);
+
+        match pos {
+            LabelPos::Centroid => centroid(&fill_rings(self, tolerance)),
+            LabelPos::PoleOfInaccessibility => pole(&fill_rings(self, tolerance), tolerance),
+            LabelPos::Along(length) => self.point_at_length(length).map(|at| at.point),
+        }
+    }
+}
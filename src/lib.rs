@@ -1,569 +1,164 @@
+#[cfg(feature = "geometry")]
+mod align;
+#[cfg(feature = "geometry")]
+mod along;
+mod ast;
+#[cfg(feature = "geometry")]
+pub mod biarc;
+#[cfg(feature = "geometry")]
+mod blend;
+mod bounds;
+#[cfg(feature = "geometry")]
+mod clip;
+#[cfg(feature = "geometry")]
+mod collider;
+mod compat;
+#[cfg(feature = "storage")]
+mod compress;
+#[cfg(feature = "geometry")]
+mod corners;
+#[cfg(feature = "svg")]
+pub mod css;
+#[cfg(feature = "geometry")]
+mod dash;
+#[cfg(feature = "storage")]
+pub mod dataset;
+#[cfg(feature = "delaunay")]
+pub mod delaunay;
+pub mod diff;
+pub mod edit;
+#[cfg(feature = "geometry")]
+mod features;
+#[cfg(feature = "geometry")]
+mod fill;
+#[cfg(feature = "geometry")]
+pub mod fit;
+#[cfg(feature = "geometry")]
+mod flatten;
+#[cfg(feature = "fontgen")]
+pub mod fontgen;
+#[cfg(feature = "geometry")]
+mod intersect;
+#[cfg(feature = "geometry")]
+mod join;
+#[cfg(feature = "kurbo")]
+pub mod kurbo;
+#[cfg(feature = "geometry")]
+mod label;
+#[cfg(feature = "lyon")]
+mod lyon;
+#[cfg(feature = "geometry")]
+mod offset;
 pub mod path;
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
+#[cfg(feature = "geometry")]
+pub mod plan;
+mod precision;
+#[cfg(feature = "delaunay")]
+mod predicates;
+#[cfg(feature = "storage")]
+mod progressive;
+#[cfg(feature = "geometry")]
+pub mod project;
+#[cfg(feature = "raster")]
+mod raster;
+#[cfg(feature = "geometry")]
+pub mod rig;
+mod rng;
+mod roots;
+#[cfg(feature = "geometry")]
+mod scanline;
+#[cfg(feature = "geometry")]
+mod scatter;
+pub mod segment;
+mod shapes;
 mod simplification;
+#[cfg(feature = "tiny-skia")]
+mod skia;
+#[cfg(feature = "geometry")]
+mod slice;
+#[cfg(feature = "geometry")]
+mod smooth;
+pub mod sprites;
+#[cfg(feature = "geometry")]
+mod stroke;
+#[cfg(feature = "svg")]
+pub mod svg;
+#[cfg(feature = "tiles")]
+pub mod tiles;
+#[cfg(feature = "geometry")]
+mod trace;
+pub mod transform;
+pub mod units;
 pub mod viewbox;
-
-use logos::{Lexer, Logos};
-use simplification::{calculate_ellipse_parameters, push_eliptical_cmds};
-use std::iter::Peekable;
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Cmd {
-    M,
-    L,
-    H,
-    V,
-    C,
-    S,
-    Q,
-    T,
-    A,
-    Z,
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "geometry")]
+mod winding;
+pub mod writer;
+
+#[cfg(not(feature = "f64"))]
+pub(crate) use std::f32::consts;
+#[cfg(feature = "f64")]
+pub(crate) use std::f64::consts;
+
+// `v` as the f32 the stored formats keep, which is no conversion at all without `f64`
+#[cfg(any(feature = "storage", feature = "lyon", feature = "tiny-skia"))]
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn to_f32(v: Float) -> f32 {
+    v as f32
 }
 
-impl Cmd {
-    #[inline]
-    fn map(c: char) -> Option<(Self, bool)> {
-        return Some(match c {
-            'M' => (Cmd::M, false),
-            'm' => (Cmd::M, true),
-            'L' => (Cmd::L, false),
-            'l' => (Cmd::L, true),
-            'H' => (Cmd::H, false),
-            'h' => (Cmd::H, true),
-            'V' => (Cmd::V, false),
-            'v' => (Cmd::V, true),
-            'C' => (Cmd::C, false),
-            'c' => (Cmd::C, true),
-            'S' => (Cmd::S, false),
-            's' => (Cmd::S, true),
-            'Q' => (Cmd::Q, false),
-            'q' => (Cmd::Q, true),
-            'T' => (Cmd::T, false),
-            't' => (Cmd::T, true),
-            'A' => (Cmd::A, false),
-            'a' => (Cmd::A, true),
-            'Z' => (Cmd::Z, false),
-            'z' => (Cmd::Z, true),
-            _ => return None,
-        });
-    }
+// `v` widened for the sums that need more than f32, no conversion at all with `f64`
+#[allow(clippy::useless_conversion)]
+pub(crate) fn to_f64(v: Float) -> f64 {
+    f64::from(v)
 }
 
-#[derive(Logos, Debug, PartialEq)]
-#[logos(skip r"[ ,\t\r\n]+")]
-enum Token {
-    // any single character
-    #[regex(r"[a-zA-Z]", |lex| Cmd::map(lex.slice().chars().next().expect("char")))]
-    Command((Cmd, bool)),
-
-    // any floating point number
-    #[regex(r"-?(?:0|[1-9]\d*)?(?:\.\d+)?", |lex| lex.slice().parse::<f32>().unwrap_or(0.0))]
-    Number(f32),
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Command {
-    // M x y
-    MoveTo {
-        x: f32,
-        y: f32,
-    },
-    // L x y
-    LineTo {
-        x: f32,
-        y: f32,
-    },
-
-    // C x1 y1 x2 y2 x y
-    CurveTo {
-        x1: f32,
-        y1: f32,
-        x2: f32,
-        y2: f32,
-        x: f32,
-        y: f32,
-    },
-    // Z
-    ClosePath,
-    // S x2 y2 x y
-    SmoothCurveTo {
-        cx: f32,
-        cy: f32,
-
-        x2: f32,
-        y2: f32,
-        x: f32,
-        y: f32,
-    },
-    // Q x1 y1 x y
-    QuadraticBezierCurveTo {
-        x1: f32,
-        y1: f32,
-        x: f32,
-        y: f32,
-    },
-    // T x y
-    SmoothQuadraticBezierCurveTo {
-        cx: f32,
-        cy: f32,
-
-        x: f32,
-        y: f32,
-    },
-}
-
-impl Command {
-    pub(crate) fn translate(self, tx: f32, ty: f32) -> Command {
-        match self {
-            Command::MoveTo { x, y } => Command::MoveTo {
-                x: x + tx,
-                y: y + ty,
-            },
-            Command::LineTo { x, y } => Command::LineTo {
-                x: x + tx,
-                y: y + ty,
-            },
-            Command::CurveTo {
-                x1,
-                y1,
-                x2,
-                y2,
-                x,
-                y,
-            } => Command::CurveTo {
-                x1: x1 + tx,
-                y1: y1 + ty,
-                x2: x2 + tx,
-                y2: y2 + ty,
-                x: x + tx,
-                y: y + ty,
-            },
-            Command::ClosePath => Command::ClosePath,
-            Command::SmoothCurveTo {
-                cx,
-                cy,
-                x2,
-                y2,
-                x,
-                y,
-            } => Command::SmoothCurveTo {
-                cx: cx + tx,
-                cy: cy + ty,
-                x2: x2 + tx,
-                y2: y2 + ty,
-                x: x + tx,
-                y: y + ty,
-            },
-            Command::QuadraticBezierCurveTo { x1, y1, x, y } => Command::QuadraticBezierCurveTo {
-                x1: x1 + tx,
-                y1: y1 + ty,
-                x: x + tx,
-                y: y + ty,
-            },
-            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
-                Command::SmoothQuadraticBezierCurveTo {
-                    cx: cx + tx,
-                    cy: cy + ty,
-                    x: x + tx,
-                    y: y + ty,
-                }
-            }
-        }
-    }
-}
-
-pub struct Parser<'src> {
-    lexer: Peekable<Lexer<'src, Token>>,
-
-    px: f32,
-    py: f32,
-
-    cx: f32,
-    cy: f32,
-
-    sx: f32,
-    sy: f32,
-
-    bezier_steps: i32,
-
-    last_command: Option<Cmd>,
-
-    commands: Vec<Command>,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Expected {
-    Command,
-    Number,
-}
-
-impl<'src> Parser<'src> {
-    pub fn new(path: &'src str) -> Parser<'src> {
-        let lexer = Token::lexer(path);
-
-        Parser {
-            lexer: lexer.peekable(),
-
-            px: 0.0,
-            py: 0.0,
-
-            cx: 0.0,
-            cy: 0.0,
-
-            sx: 0.0,
-            sy: 0.0,
-
-            bezier_steps: 16,
-
-            last_command: None,
-
-            commands: Vec::new(),
-        }
-    }
-
-    pub fn bezier_steps(mut self, bezier_steps: i32) -> Self {
-        self.bezier_steps = bezier_steps;
-        self
-    }
-
-    pub fn parse(mut self) -> Result<Vec<Command>, Expected> {
-        while let Some(Ok(token)) = self.lexer.next() {
-            match token {
-                Token::Command((command, relative)) => {
-                    match command {
-                        Cmd::M => self.m(relative)?,
-                        Cmd::L => self.l(relative)?,
-                        Cmd::H => self.h(relative)?,
-                        Cmd::V => self.v(relative)?,
-                        Cmd::C => self.c(relative)?,
-                        Cmd::S => self.s(relative)?,
-                        Cmd::Q => self.q(relative)?,
-                        Cmd::T => self.t(relative)?,
-                        Cmd::A => self.a(relative)?,
-                        Cmd::Z => {
-                            self.px = self.sx;
-                            self.py = self.sy;
-
-                            self.commands.push(Command::ClosePath);
-                        }
-                    }
-
-                    self.last_command = Some(command);
-                }
-                Token::Number(_) => {
-                    return Err(Expected::Command);
-                }
-            }
-        }
-
-        Ok(self.commands)
-    }
-
-    #[inline]
-    fn peek<'a>(&'a mut self) -> Option<&'a Result<Token, ()>> {
-        self.lexer.peek()
-    }
-
-    #[inline]
-    fn number(&mut self) -> Result<f32, Expected> {
-        match self.lexer.next() {
-            Some(Ok(Token::Number(n))) => Ok(n),
-            _ => Err(Expected::Number),
-        }
-    }
-
-    #[inline]
-    fn delta(&self, relative: bool) -> (f32, f32) {
-        if relative {
-            (self.px, self.py)
-        } else {
-            (0.0, 0.0)
-        }
-    }
-
-    #[inline]
-    fn try_number(&mut self) -> Result<f32, Expected> {
-        match self.peek() {
-            Some(Ok(Token::Number(n))) => {
-                let n = *n;
-                self.lexer.next();
-                Ok(n)
-            }
-            _ => Err(Expected::Number),
-        }
-    }
-
-    #[inline]
-    fn m(&mut self, relative: bool) -> Result<(), Expected> {
-        let x = self.number()?;
-        let y = self.number()?;
-
-        self.px = x + if relative { self.px } else { 0.0 };
-        self.py = y + if relative { self.py } else { 0.0 };
-
-        self.sx = self.px;
-        self.sy = self.py;
-
-        self.commands.push(Command::MoveTo {
-            x: self.px,
-            y: self.py,
-        });
-
-        self.l(relative)?;
-
-        Ok(())
-    }
-
-    #[inline]
-    fn l(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let Ok(x) = self.try_number() else {
-                break;
-            };
-
-            let y = self.number()?;
-
-            self.px = x + if relative { self.px } else { 0.0 };
-            self.py = y + if relative { self.py } else { 0.0 };
-
-            self.commands.push(Command::LineTo {
-                x: self.px,
-                y: self.py,
-            });
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn h(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let Ok(x) = self.try_number() else {
-                break;
-            };
-
-            self.px = x + if relative { self.px } else { 0.0 };
-
-            self.commands.push(Command::LineTo {
-                x: self.px,
-                y: self.py,
-            });
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn v(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let Ok(y) = self.try_number() else {
-                break;
-            };
-
-            self.py = y + if relative { self.py } else { 0.0 };
-
-            self.commands.push(Command::LineTo {
-                x: self.px,
-                y: self.py,
-            });
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn c(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let (dx, dy) = self.delta(relative);
-            let Ok(x1) = self.try_number() else {
-                break;
-            };
-
-            let y1 = self.number()?;
-            let x2 = self.number()?;
-            let y2 = self.number()?;
-            let x = self.number()?;
-            let y = self.number()?;
-
-            self.px = x + dx;
-            self.py = y + dy;
-
-            self.cx = x2 + dx;
-            self.cy = y2 + dy;
-
-            self.commands.push(Command::CurveTo {
-                x1: x1 + dx,
-                y1: y1 + dy,
-                x2: x2 + dx,
-                y2: y2 + dy,
-                x: self.px,
-                y: self.py,
-            });
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn s(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let (dx, dy) = self.delta(relative);
-            let Ok(x2) = self.try_number() else {
-                break;
-            };
-
-            let y2 = self.number()?;
-            let x = self.number()?;
-            let y = self.number()?;
-
-            if let Some(Cmd::C | Cmd::S) = self.last_command {
-                self.cx = self.px + (self.px - self.cx);
-                self.cy = self.py + (self.py - self.cy);
-            } else {
-                self.cx = self.px;
-                self.cy = self.py;
-            }
-
-            self.px = x + dx;
-            self.py = y + dy;
-
-            self.commands.push(Command::SmoothCurveTo {
-                cx: self.cx,
-                cy: self.cy,
-
-                x2: x2 + dx,
-                y2: y2 + dy,
-                x: self.px,
-                y: self.py,
-            });
-
-            self.cx = x2 + dx;
-            self.cy = y2 + dy;
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn q(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let (dx, dy) = self.delta(relative);
-
-            let Ok(x1) = self.try_number() else {
-                break;
-            };
-
-            let y1 = self.number()?;
-            let x = self.number()?;
-            let y = self.number()?;
-
-            self.px = x + dx;
-            self.py = y + dy;
-
-            self.cx = x1 + dx;
-            self.cy = y1 + dy;
-
-            self.commands.push(Command::QuadraticBezierCurveTo {
-                x1: x1 + dx,
-                y1: y1 + dy,
-                x: self.px,
-                y: self.py,
-            });
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn t(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let (dx, dy) = self.delta(relative);
-            let Ok(x) = self.try_number() else {
-                break;
-            };
-
-            let y = self.number()?;
-
-            if let Some(Cmd::Q | Cmd::T) = self.last_command {
-                self.cx = self.px + (self.px - self.cx);
-                self.cy = self.py + (self.py - self.cy);
-            } else {
-                self.cx = self.px;
-                self.cy = self.py;
-            }
-
-            self.px = x + dx;
-            self.py = y + dy;
-
-            self.commands.push(Command::SmoothQuadraticBezierCurveTo {
-                cx: self.cx,
-                cy: self.cy,
-
-                x: self.px,
-                y: self.py,
-            });
-
-            self.cx = self.px;
-            self.cy = self.py;
-        }
-
-        Ok(())
-    }
-
-    #[inline]
-    fn a(&mut self, relative: bool) -> Result<(), Expected> {
-        loop {
-            let (dx, dy) = self.delta(relative);
-
-            let Ok(rx) = self.try_number() else {
-                break;
-            };
-
-            let ry = self.number()?;
-            let x_axis_rotation = self.number()?;
-            let large_arc_flag = self.number()? != 0.0;
-            let sweep_flag = self.number()? != 0.0;
-            let x = self.number()?;
-            let y = self.number()?;
-
-            let x2 = self.px;
-            let y2 = self.py;
-
-            self.px = dx + x;
-            self.py = dy + y;
-
-            if let Some((cx, cy, start_angle, delta_angle)) = calculate_ellipse_parameters(
-                x2,
-                y2,
-                self.px,
-                self.py,
-                rx,
-                ry,
-                x_axis_rotation,
-                large_arc_flag,
-                sweep_flag,
-            ) {
-                push_eliptical_cmds(
-                    &mut self.commands,
-                    cx,
-                    cy,
-                    rx,
-                    ry,
-                    start_angle,
-                    start_angle + delta_angle,
-                    x_axis_rotation,
-                    self.bezier_steps,
-                );
-            }
-
-            self.cx = self.px;
-            self.cy = self.py;
-        }
-
-        Ok(())
-    }
-}
-
-pub fn parse_path_str(path: &str) -> Result<Vec<Command>, Expected> {
-    Parser::new(path).parse()
-}
+#[cfg(feature = "geometry")]
+pub use align::{Align, AlignOptions};
+#[cfg(feature = "geometry")]
+pub use along::PointAt;
+pub use ast::AST_VERSION;
+pub use bounds::Rect;
+#[cfg(feature = "geometry")]
+pub use clip::Region;
+#[cfg(feature = "geometry")]
+pub use collider::ConvexPolygon;
+pub use compat::{Issue, Target};
+#[cfg(feature = "storage")]
+pub use compress::CompressError;
+#[cfg(feature = "geometry")]
+pub use corners::Corner;
+#[cfg(feature = "geometry")]
+pub use features::FeatureConfig;
+#[cfg(feature = "geometry")]
+pub use flatten::{
+    flatten_into, for_each_segment, FlattenMethod, Polyline, SimplifyOptions, VertexAttribute,
+};
+#[cfg(feature = "geometry")]
+pub use join::{CloseMode, JoinMode};
+#[cfg(feature = "geometry")]
+pub use label::LabelPos;
+pub use path::ParsePath;
+pub use precision::PrecisionReport;
+#[cfg(feature = "storage")]
+pub use progressive::{Progress, ProgressiveError};
+#[cfg(feature = "raster")]
+pub use raster::Mask;
+pub use rng::{Rng, SplitMix64};
+#[cfg(feature = "geometry")]
+pub use scanline::FillRule;
+#[cfg(feature = "geometry")]
+pub use stroke::{LineCap, LineJoin, StrokeStyle};
+
+// the parser and commands live in scavenger-core, for those who need nothing else
+#[cfg(feature = "audit")]
+pub use scavenger_core::audit;
+#[cfg(feature = "corpus")]
+pub use scavenger_core::corpus;
+pub use scavenger_core::{
+    parse_path_str, Command, CommandIterator, EllipticalArc, Expected, Float, ParseError,
+    ParseOptions, Parser, Point, Warning,
+};
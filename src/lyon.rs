@@ -0,0 +1,90 @@
+use lyon_path::{math::point, PathEvent};
+
+use crate::{
+    path::Path,
+    segment::{Segment, Subpath},
+    to_f32, Command, Float, Point,
+};
+
+fn lyon_point((x, y): Point) -> lyon_path::math::Point {
+    point(to_f32(x), to_f32(y))
+}
+
+// every subpath as lines, quadratics and cubics (arcs become cubics), lyon keeps f32 coordinates
+impl From<&Path> for lyon_path::Path {
+    fn from(path: &Path) -> lyon_path::Path {
+        let mut builder = lyon_path::Path::builder();
+
+        for sp in path.subpaths() {
+            // lyon closes the subpath itself, so a ClosePath is not drawn as a line
+            let commands = match sp.commands.split_last() {
+                Some((Command::ClosePath, rest)) => rest,
+                _ => sp.commands,
+            };
+            let open = Subpath {
+                start: sp.start,
+                commands,
+                closed: false,
+            };
+
+            builder.begin(lyon_point(sp.start));
+            for seg in open.segments() {
+                match seg {
+                    Segment::Line(_, to) => builder.line_to(lyon_point(to)),
+                    Segment::Quad(_, ctrl, to) => {
+                        builder.quadratic_bezier_to(lyon_point(ctrl), lyon_point(to))
+                    }
+                    Segment::Cubic(_, ctrl1, ctrl2, to) => builder.cubic_bezier_to(
+                        lyon_point(ctrl1),
+                        lyon_point(ctrl2),
+                        lyon_point(to),
+                    ),
+                };
+            }
+            builder.end(sp.closed);
+        }
+
+        builder.build()
+    }
+}
+
+impl From<&lyon_path::Path> for Path {
+    fn from(path: &lyon_path::Path) -> Path {
+        let p = |p: lyon_path::math::Point| (p.x as Float, p.y as Float);
+
+        let mut commands = Vec::new();
+        for event in path.iter() {
+            commands.push(match event {
+                PathEvent::Begin { at } => {
+                    let (x, y) = p(at);
+                    Command::MoveTo { x, y }
+                }
+                PathEvent::Line { to, .. } => {
+                    let (x, y) = p(to);
+                    Command::LineTo { x, y }
+                }
+                PathEvent::Quadratic { ctrl, to, .. } => {
+                    let ((x1, y1), (x, y)) = (p(ctrl), p(to));
+                    Command::QuadraticBezierCurveTo { x1, y1, x, y }
+                }
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    let ((x1, y1), (x2, y2), (x, y)) = (p(ctrl1), p(ctrl2), p(to));
+                    Command::CurveTo {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                    }
+                }
+                PathEvent::End { close: true, .. } => Command::ClosePath,
+                PathEvent::End { close: false, .. } => continue,
+            });
+        }
+
+        Path::new(commands)
+    }
+}
@@ -1,11 +1,7 @@
-use scavenger::parse_path_str;
+mod cli;
 
 fn main() {
-    let path = "L-134.2-123-23.4 18.2-26-20.3 77.2-60.1z";
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-    let commands = parse_path_str(path).unwrap();
-
-    for cmd in commands {
-        println!("{:?}", cmd);
-    }
+    std::process::exit(cli::run(&args));
 }
@@ -0,0 +1,250 @@
+use crate::{
+    fill::fill_rings,
+    path::Path,
+    trace::{trace_count, trace_span},
+    Command, Float,
+};
+
+// joins further than this many offset distances from their corner are clamped
+const MITER_LIMIT: Float = 4.0;
+
+// twice the signed area of a closed ring, positive when the interior is on the left
+pub(crate) fn signed_area(ring: &[(Float, Float)]) -> Float {
+    let mut area = 0.0;
+    for i in 0..ring.len() {
+        let a = ring[i];
+        let b = ring[(i + 1) % ring.len()];
+        area += a.0 * b.1 - b.0 * a.1;
+    }
+    area
+}
+
+// even-odd point in polygon test against a single ring
+pub(crate) fn ring_contains(ring: &[(Float, Float)], (x, y): (Float, Float)) -> bool {
+    let mut inside = false;
+    for i in 0..ring.len() {
+        let a = ring[i];
+        let b = ring[(i + 1) % ring.len()];
+        if (a.1 <= y) != (b.1 <= y) && x < a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// the point of the segment from a to b closest to p
+pub(crate) fn closest_on_segment(
+    p: (Float, Float),
+    a: (Float, Float),
+    b: (Float, Float),
+) -> (Float, Float) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (a.0 + dx * t, a.1 + dy * t)
+}
+
+pub(crate) fn segment_distance(p: (Float, Float), a: (Float, Float), b: (Float, Float)) -> Float {
+    let (x, y) = closest_on_segment(p, a, b);
+    let (x, y) = (x - p.0, y - p.1);
+    (x * x + y * y).sqrt()
+}
+
+// distance from p to the closest edge of a closed ring
+pub(crate) fn ring_distance(ring: &[(Float, Float)], p: (Float, Float)) -> Float {
+    (0..ring.len())
+        .map(|i| segment_distance(p, ring[i], ring[(i + 1) % ring.len()]))
+        .fold(Float::INFINITY, Float::min)
+}
+
+struct Edge {
+    origin: (Float, Float),
+    dir: (Float, Float),
+    // start of the edge once shifted
+    shifted: (Float, Float),
+}
+
+fn intersect(a: &Edge, b: &Edge, d: Float) -> (Float, Float) {
+    let cross = a.dir.0 * b.dir.1 - a.dir.1 * b.dir.0;
+    if cross.abs() < 1e-6 {
+        return b.shifted;
+    }
+
+    let qx = b.shifted.0 - a.shifted.0;
+    let qy = b.shifted.1 - a.shifted.1;
+    let s = (qx * b.dir.1 - qy * b.dir.0) / cross;
+    let v = (a.shifted.0 + s * a.dir.0, a.shifted.1 + s * a.dir.1);
+
+    // clamp long miters at sharp corners
+    let (mx, my) = (v.0 - b.origin.0, v.1 - b.origin.1);
+    let len = (mx * mx + my * my).sqrt();
+    let limit = MITER_LIMIT * d.abs();
+    if len > limit {
+        (b.origin.0 + mx * limit / len, b.origin.1 + my * limit / len)
+    } else {
+        v
+    }
+}
+
+// shift every edge of a closed ring `d` units to its left, dropping edges that invert.
+// returns `None` once the ring collapses
+pub(crate) fn offset_ring(ring: &[(Float, Float)], d: Float) -> Option<Vec<(Float, Float)>> {
+    let mut edges = Vec::with_capacity(ring.len());
+    for i in 0..ring.len() {
+        let a = ring[i];
+        let b = ring[(i + 1) % ring.len()];
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        // slivers left over from flattening have unreliable directions
+        if len <= d.abs() * 1e-3 || len <= Float::EPSILON {
+            continue;
+        }
+
+        let dir = (dx / len, dy / len);
+        edges.push(Edge {
+            origin: a,
+            dir,
+            shifted: (a.0 - dir.1 * d, a.1 + dir.0 * d),
+        });
+    }
+
+    loop {
+        if edges.len() < 3 {
+            return None;
+        }
+
+        let n = edges.len();
+        let points: Vec<(Float, Float)> = (0..n)
+            .map(|i| intersect(&edges[(i + n - 1) % n], &edges[i], d))
+            .collect();
+
+        let before = edges.len();
+        let mut i = 0;
+        edges.retain(|edge| {
+            let a = points[i];
+            let b = points[(i + 1) % n];
+            i += 1;
+            (b.0 - a.0) * edge.dir.0 + (b.1 - a.1) * edge.dir.1 >= 0.0
+        });
+
+        if edges.len() == before {
+            // anything that ended up closer to the outline than `d` crossed over another part of it
+            let points: Vec<(Float, Float)> = points
+                .into_iter()
+                .filter(|p| ring_distance(ring, *p) >= d.abs() * 0.99)
+                .collect();
+
+            let area = signed_area(&points);
+            let original = signed_area(ring);
+            if points.len() < 3
+                || area.abs() <= original.abs() * 1e-6
+                || (area > 0.0) != (original > 0.0)
+            {
+                return None;
+            }
+            return Some(points);
+        }
+    }
+}
+
+// whether ring `i` sits inside an odd number of the other rings
+fn is_hole(rings: &[Vec<(Float, Float)>], i: usize) -> bool {
+    let depth = rings
+        .iter()
+        .enumerate()
+        .filter(|(j, other)| *j != i && ring_contains(other, rings[i][0]))
+        .count();
+    depth % 2 == 1
+}
+
+// the rings turned so outlines run with positive area and holes against it, or all the other way
+// round when `inverted`
+pub(crate) fn oriented(rings: &[Vec<(Float, Float)>], inverted: bool) -> Vec<Vec<(Float, Float)>> {
+    (0..rings.len())
+        .map(|i| {
+            let mut ring = rings[i].clone();
+            if (signed_area(&ring) > 0.0) == (is_hole(rings, i) != inverted) {
+                ring.reverse();
+            }
+            ring
+        })
+        .collect()
+}
+
+// the closed rings of `path` offset by `distance` (positive grows the filled region).
+// holes are detected by nesting so either winding convention for them works
+pub(crate) fn offset_rings(
+    rings: &[Vec<(Float, Float)>],
+    distance: Float,
+) -> Vec<Vec<(Float, Float)>> {
+    trace_span!("offset", distance, rings = rings.len());
+
+    let out: Vec<_> = rings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, ring)| {
+            // the filled side of the ring is on the left when the signs agree
+            let filled_left = (signed_area(ring) > 0.0) != is_hole(rings, i);
+            let d = if filled_left { -distance } else { distance };
+
+            offset_ring(ring, d)
+        })
+        .collect();
+
+    trace_count!(rings = out.len(), "offset");
+
+    out
+}
+
+pub(crate) fn rings_to_commands(rings: &[Vec<(Float, Float)>], commands: &mut Vec<Command>) {
+    for ring in rings {
+        let Some(&(x, y)) = ring.first() else {
+            continue;
+        };
+
+        commands.push(Command::MoveTo { x, y });
+        for &(x, y) in &ring[1..] {
+            commands.push(Command::LineTo { x, y });
+        }
+        commands.push(Command::ClosePath);
+    }
+}
+
+impl Path {
+    // flattened outline moved `distance` outward (negative shrinks), with mitered corners
+    pub fn offset(&self, distance: Float) -> Path {
+        let tolerance = (self.bb.0.max(self.bb.1) * 1e-3).max(1e-3);
+        let rings = fill_rings(self, tolerance);
+
+        let mut commands = Vec::new();
+        rings_to_commands(&offset_rings(&rings, distance), &mut commands);
+
+        Path::new(commands)
+    }
+
+    // the band `width` wide around the outside of the shape: the outward offset with the shape
+    // itself cut out, as geometry for selection outlines and glows. fill with the non-zero rule
+    pub fn halo(&self, width: Float) -> Path {
+        let mut commands = Vec::new();
+
+        if width.is_nan() || width <= 0.0 {
+            return Path::new(commands);
+        }
+
+        let tolerance = (self.bb.0.max(self.bb.1) * 1e-3).max(1e-3);
+        let rings = fill_rings(self, tolerance);
+
+        // the shape runs against the grown outline, so the winding cancels inside it
+        rings_to_commands(
+            &oriented(&offset_rings(&rings, width), false),
+            &mut commands,
+        );
+        rings_to_commands(&oriented(&rings, true), &mut commands);
+
+        Path::new(commands)
+    }
+}
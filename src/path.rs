@@ -1,17 +1,76 @@
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use scavenger_core::{
+    arc::{calculate_ellipse_parameters, push_eliptical_cmds},
+    truncate_commands,
+};
+
 use crate::{
+    segment::{dist, push_segments, subpaths, Segment, Subpath},
     viewbox::{calculate_bb, ViewBox},
-    Command,
+    Command, EllipticalArc, Float, ParseError, Parser,
 };
 
+// the commands sit behind an Arc so `fork` is cheap, they are copied on the first change made to
+// a shared buffer
 pub struct Path {
-    pub(crate) commands: Vec<Command>,
-    pub(crate) bb: (f32, f32),
+    pub(crate) commands: Arc<Vec<Command>>,
+    pub(crate) bb: (Float, Float),
+    // set by `Parser::parse_path`, dropped by changes that would make the arcs wrong
+    pub(crate) arcs: Vec<(Range<usize>, EllipticalArc)>,
+    // who the geometry belongs to (svg id, class, ...), kept through changes to the path
+    pub(crate) tags: HashMap<String, String>,
+}
+
+// bring into scope for `Parser::parse_path`. the parser is in scavenger-core, `Path` is not
+pub trait ParsePath {
+    // the commands as a `Path` that also remembers which of them came from arcs
+    fn parse_path(self) -> Result<Path, ParseError>;
+}
+
+impl ParsePath for Parser<'_> {
+    fn parse_path(self) -> Result<Path, ParseError> {
+        let (commands, arcs) = self.parse_with_arcs()?;
+
+        let mut path = Path::new(commands);
+        path.arcs = arcs;
+        Ok(path)
+    }
 }
 
 impl Path {
     pub fn new(commands: Vec<Command>) -> Self {
         let bb = calculate_bb(commands.iter());
-        Self { commands, bb }
+        Self {
+            commands: Arc::new(commands),
+            bb,
+            arcs: Vec::new(),
+            tags: HashMap::new(),
+        }
+    }
+
+    // a copy sharing this path's commands until either of them changes, for keeping undo states
+    // or previews of large paths around
+    pub fn fork(&self) -> Path {
+        Path {
+            commands: Arc::clone(&self.commands),
+            bb: self.bb,
+            arcs: self.arcs.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+
+    // the commands for changing, copied first if another fork still shares them
+    pub(crate) fn commands_mut(&mut self) -> &mut Vec<Command> {
+        self.arcs.clear();
+        Arc::make_mut(&mut self.commands)
+    }
+
+    // swap in new geometry, keeping the tags
+    pub(crate) fn set_commands(&mut self, commands: Vec<Command>) {
+        let tags = std::mem::take(&mut self.tags);
+        *self = Path::new(commands);
+        self.tags = tags;
     }
 
     pub fn commands(&self) -> &[Command] {
@@ -19,39 +78,218 @@ impl Path {
     }
 
     pub fn take_commands(self) -> Vec<Command> {
-        self.commands
+        Arc::try_unwrap(self.commands).unwrap_or_else(|shared| (*shared).clone())
     }
 
-    pub fn bb(&self) -> (f32, f32) {
+    pub fn bb(&self) -> (Float, Float) {
         self.bb
     }
 
-    pub fn translate(&mut self, x: f32, y: f32) {
-        for cmd in self.commands.iter_mut() {
+    // the elliptical arcs the parser or `flatten_arcs` flattened, each with the range of commands
+    // it became, so writers that support arcs can put them back. empty unless the path came from
+    // `Parser::parse_path` and has not been reshaped since
+    pub fn arc_spans(&self) -> Vec<(Range<usize>, EllipticalArc)> {
+        self.arcs.clone()
+    }
+
+    // turn every `Command::EllipticalArc` into `bezier_steps` quadratics, as the parser does when
+    // it is not asked to preserve arcs. the arcs are recorded in `arc_spans`
+    pub fn flatten_arcs(&mut self, bezier_steps: i32) {
+        if !self
+            .commands
+            .iter()
+            .any(|cmd| matches!(cmd, Command::EllipticalArc { .. }))
+        {
+            return;
+        }
+
+        let mut commands = Vec::with_capacity(self.commands.len());
+        // where each command ends up, to carry the spans of arcs flattened earlier along
+        let mut moved = Vec::with_capacity(self.commands.len() + 1);
+        let mut arcs = Vec::new();
+        let mut start = (0.0, 0.0);
+        let mut pen = start;
+
+        for cmd in self.commands.iter() {
+            moved.push(commands.len());
+            match *cmd {
+                Command::EllipticalArc {
+                    rx,
+                    ry,
+                    x_axis_rotation,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => {
+                    let first = commands.len();
+                    match calculate_ellipse_parameters(
+                        pen.0,
+                        pen.1,
+                        x,
+                        y,
+                        rx,
+                        ry,
+                        x_axis_rotation,
+                        large_arc,
+                        sweep,
+                    ) {
+                        Some((cx, cy, start_angle, delta_angle)) => {
+                            push_eliptical_cmds(
+                                &mut commands,
+                                cx,
+                                cy,
+                                rx,
+                                ry,
+                                start_angle,
+                                start_angle + delta_angle,
+                                x_axis_rotation,
+                                bezier_steps,
+                            );
+                            arcs.push((
+                                first..commands.len(),
+                                EllipticalArc {
+                                    from: pen,
+                                    rx,
+                                    ry,
+                                    rotation: x_axis_rotation,
+                                    large_arc,
+                                    sweep,
+                                    to: (x, y),
+                                },
+                            ));
+                        }
+                        // a zero radius draws a straight line
+                        None if pen != (x, y) => commands.push(Command::LineTo { x, y }),
+                        None => {}
+                    }
+                    pen = (x, y);
+                }
+                Command::MoveTo { x, y } => {
+                    start = (x, y);
+                    pen = start;
+                    commands.push(*cmd);
+                }
+                Command::ClosePath => {
+                    pen = start;
+                    commands.push(*cmd);
+                }
+                cmd => {
+                    pen = cmd.points().last().unwrap_or(pen);
+                    commands.push(cmd);
+                }
+            }
+        }
+        moved.push(commands.len());
+
+        let earlier = std::mem::take(&mut self.arcs)
+            .into_iter()
+            .map(|(range, arc)| (moved[range.start]..moved[range.end], arc));
+        arcs.extend(earlier);
+        arcs.sort_by_key(|(range, _)| range.start);
+
+        self.set_commands(commands);
+        self.arcs = arcs;
+    }
+
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.get(key).map(String::as_str)
+    }
+
+    pub fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
+    }
+
+    pub fn set_tag(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.tags.insert(key.into(), value.into());
+    }
+
+    pub fn subpaths(&self) -> Vec<Subpath<'_>> {
+        subpaths(&self.commands)
+    }
+
+    // total length of every drawn segment
+    pub fn length(&self) -> Float {
+        self.segment_lengths().iter().sum()
+    }
+
+    // the length each command draws, one per command: 0 for moves, the closing line for
+    // ClosePath, every piece of an arc
+    pub fn segment_lengths(&self) -> Vec<Float> {
+        let mut start = (0.0, 0.0);
+        let mut pen = start;
+
+        self.commands
+            .iter()
+            .map(|cmd| match *cmd {
+                Command::MoveTo { x, y } => {
+                    (start, pen) = ((x, y), (x, y));
+                    0.0
+                }
+                Command::ClosePath => {
+                    let length = dist(pen, start);
+                    pen = start;
+                    length
+                }
+                ref cmd => {
+                    let mut length = 0.0;
+                    for seg in Segment::pieces(pen, cmd) {
+                        length += seg.length();
+                    }
+                    pen = cmd.points().last().unwrap_or(pen);
+                    length
+                }
+            })
+            .collect()
+    }
+
+    // drop whole trailing subpaths until at most `n` commands remain
+    pub fn truncate_to(&mut self, n: usize) {
+        let mut arcs = std::mem::take(&mut self.arcs);
+        truncate_commands(self.commands_mut(), n);
+        arcs.retain(|(range, _)| range.end <= self.commands.len());
+        self.arcs = arcs;
+        self.bb = calculate_bb(self.commands.iter());
+    }
+
+    pub fn translate(&mut self, x: Float, y: Float) {
+        for cmd in Arc::make_mut(&mut self.commands).iter_mut() {
             *cmd = cmd.translate(x, y);
         }
+        for (_, arc) in self.arcs.iter_mut() {
+            arc.from = (arc.from.0 + x, arc.from.1 + y);
+            arc.to = (arc.to.0 + x, arc.to.1 + y);
+        }
+
+        self.bb = calculate_bb(self.commands.iter());
     }
 
-    pub fn resize(&mut self, width: f32, height: f32) {
+    pub fn resize(&mut self, width: Float, height: Float) {
         let scalex = width / self.bb.0;
         let scaley = height / self.bb.1;
 
         let vb = ViewBox::new(0.0, 0.0, self.bb.0 / scalex, self.bb.1 / scaley);
 
-        for cmd in self.commands.iter_mut() {
-            *cmd = vb.scale_cmd(cmd, self.bb.0, self.bb.1);
+        let bb = self.bb;
+        for cmd in self.commands_mut().iter_mut() {
+            *cmd = vb.scale_cmd(cmd, bb.0, bb.1);
         }
+
+        self.bb = calculate_bb(self.commands.iter());
     }
 
-    pub fn scale(&mut self, scale: f32) {
+    pub fn scale(&mut self, scale: Float) {
         let vb = ViewBox::new(0.0, 0.0, self.bb.0 / scale, self.bb.1 / scale);
 
-        for cmd in self.commands.iter_mut() {
-            *cmd = vb.scale_cmd(cmd, self.bb.0, self.bb.1);
+        let bb = self.bb;
+        for cmd in self.commands_mut().iter_mut() {
+            *cmd = vb.scale_cmd(cmd, bb.0, bb.1);
         }
+
+        self.bb = calculate_bb(self.commands.iter());
     }
 
-    pub fn fit(&mut self, width: f32, height: f32) {
+    pub fn fit(&mut self, width: Float, height: Float) {
         let scalex = width / self.bb.0;
         let scaley = height / self.bb.1;
 
@@ -59,12 +297,15 @@ impl Path {
 
         let vb = ViewBox::new(0.0, 0.0, self.bb.0 / scale, self.bb.1 / scale);
 
-        for cmd in self.commands.iter_mut() {
-            *cmd = vb.scale_cmd(cmd, self.bb.0, self.bb.1);
+        let bb = self.bb;
+        for cmd in self.commands_mut().iter_mut() {
+            *cmd = vb.scale_cmd(cmd, bb.0, bb.1);
         }
+
+        self.bb = calculate_bb(self.commands.iter());
     }
 
-    pub fn cover(&mut self, width: f32, height: f32) {
+    pub fn cover(&mut self, width: Float, height: Float) {
         let scalex = width / self.bb.0;
         let scaley = height / self.bb.1;
 
@@ -72,9 +313,96 @@ impl Path {
 
         let vb = ViewBox::new(0.0, 0.0, self.bb.0 / scale, self.bb.1 / scale);
 
-        for cmd in self.commands.iter_mut() {
-            *cmd = vb.scale_cmd(cmd, self.bb.0, self.bb.1);
+        let bb = self.bb;
+        for cmd in self.commands_mut().iter_mut() {
+            *cmd = vb.scale_cmd(cmd, bb.0, bb.1);
+        }
+
+        self.bb = calculate_bb(self.commands.iter());
+    }
+
+    // `n` copies behind the path, copy i shifted by i * (dx, dy) and scaled by 1 + i * scale_step
+    // about the center of the control points. the farthest copy comes first so the original
+    // ends up drawn on top, as for drop shadows and echo trails
+    pub fn echo(&self, n: usize, dx: Float, dy: Float, scale_step: Float) -> Path {
+        let (min, max) = self.commands.iter().flat_map(|cmd| cmd.points()).fold(
+            ((Float::MAX, Float::MAX), (Float::MIN, Float::MIN)),
+            |(lo, hi), (x, y)| ((lo.0.min(x), lo.1.min(y)), (hi.0.max(x), hi.1.max(y))),
+        );
+        let center = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+
+        // a path that does not open with a MoveTo starts at the origin, each copy needs its own
+        let start = match self.commands.first() {
+            Some(Command::MoveTo { .. }) | None => None,
+            Some(_) => Some(Command::MoveTo { x: 0.0, y: 0.0 }),
+        };
+
+        let mut commands = Vec::with_capacity((self.commands.len() + 1) * (n + 1));
+        for i in (1..=n).rev() {
+            let scale = 1.0 + i as Float * scale_step;
+            let (tx, ty) = (i as Float * dx, i as Float * dy);
+
+            commands.extend(start.iter().chain(self.commands.iter()).map(|cmd| {
+                cmd.translate(-center.0, -center.1)
+                    .scale(scale, scale)
+                    .translate(center.0 + tx, center.1 + ty)
+            }));
         }
+        commands.extend(start.iter().chain(self.commands.iter()).copied());
+
+        Path::new(commands)
+    }
+
+    // mirror within `height` so y grows upwards, as opengl and pdf expect. every subpath is also
+    // reversed: a plain mirror would flip the sign of each contour's area, turning counter
+    // clockwise outlines clockwise and breaking anything that tells outlines from holes that way
+    pub fn to_y_up(&mut self, height: Float) {
+        self.flip_y(height);
+    }
+
+    // back from y-up to the y-down space svg uses, the same mirror and reversal as `to_y_up`
+    pub fn to_y_down(&mut self, height: Float) {
+        self.flip_y(height);
+    }
+
+    fn flip_y(&mut self, height: Float) {
+        let flip = |(x, y): (Float, Float)| (x, height - y);
+        let mut commands = Vec::with_capacity(self.commands.len());
+
+        for sp in self.subpaths() {
+            let mut segments: Vec<Segment> = sp.segments().collect();
+            segments.reverse();
+            let mut segments: Vec<Segment> = segments
+                .iter()
+                .map(|seg| match seg.reverse() {
+                    Segment::Line(a, b) => Segment::Line(flip(a), flip(b)),
+                    Segment::Quad(a, b, c) => Segment::Quad(flip(a), flip(b), flip(c)),
+                    Segment::Cubic(a, b, c, d) => {
+                        Segment::Cubic(flip(a), flip(b), flip(c), flip(d))
+                    }
+                })
+                .collect();
+
+            // a trailing line back to the start is what Z draws anyway
+            if let (true, Some(&Segment::Line(_, to))) = (sp.closed, segments.last()) {
+                if segments.len() > 1 && to == segments[0].from() {
+                    segments.pop();
+                }
+            }
+
+            if segments.is_empty() {
+                let (x, y) = flip(sp.end());
+                commands.push(Command::MoveTo { x, y });
+                if sp.closed {
+                    commands.push(Command::ClosePath);
+                }
+                continue;
+            }
+
+            push_segments(&segments, sp.closed, &mut commands);
+        }
+
+        self.set_commands(commands);
     }
 }
 
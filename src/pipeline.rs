@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use crate::{path::Path, Float};
+
+// a step of a `Pipeline`. passes of this crate and the caller's own are run the same way
+pub trait Pass {
+    // what the pass is called in reports, the name of its type unless it says otherwise
+    fn name(&self) -> &str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    // changes `path` in place. the report only needs the notes, the pipeline fills in the rest
+    fn run(&self, path: &mut Path) -> PassReport;
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PassReport {
+    pub name: String,
+    pub commands_before: usize,
+    pub commands_after: usize,
+    pub elapsed: Duration,
+    // anything else the pass wants to say about what it did
+    pub notes: Vec<String>,
+}
+
+// moves the top left of the bounds to the origin
+pub struct NormalizePass;
+
+impl Pass for NormalizePass {
+    fn run(&self, path: &mut Path) -> PassReport {
+        let Some(bounds) = path.tight_bounds() else {
+            return PassReport::default();
+        };
+        path.translate(-bounds.min_x, -bounds.min_y);
+
+        PassReport {
+            notes: vec![format!("moved by {} {}", -bounds.min_x, -bounds.min_y)],
+            ..PassReport::default()
+        }
+    }
+}
+
+// `Path::simplify` to within the tolerance
+pub struct SimplifyPass(pub Float);
+
+impl Pass for SimplifyPass {
+    fn run(&self, path: &mut Path) -> PassReport {
+        let simple = path.simplify(self.0);
+        path.set_commands(simple.take_commands());
+        PassReport::default()
+    }
+}
+
+// passes run one after the other on the same path
+#[derive(Default)]
+pub struct Pipeline {
+    passes: Vec<Box<dyn Pass>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, pass: impl Pass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    // one report per pass, in the order they ran
+    pub fn run(&self, path: &mut Path) -> Vec<PassReport> {
+        self.passes
+            .iter()
+            .map(|pass| {
+                let commands_before = path.commands().len();
+                let start = Instant::now();
+                let report = pass.run(path);
+                let elapsed = start.elapsed();
+
+                PassReport {
+                    name: pass.name().to_string(),
+                    commands_before,
+                    commands_after: path.commands().len(),
+                    elapsed,
+                    ..report
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::{
+    path::Path,
+    segment::{dist, push_segments, Segment, Subpath},
+    Command, Float,
+};
+
+// maximum number of full 2-opt sweeps, each one is O(n^2)
+const MAX_PASSES: usize = 8;
+
+// where the pen lands first and where it lifts for the last time
+pub(crate) fn endpoints(path: &Path) -> ((Float, Float), (Float, Float)) {
+    let subpaths = path.subpaths();
+
+    let start = subpaths.first().map_or((0.0, 0.0), |sp| sp.start);
+    let end = subpaths.last().map_or(start, |sp| sp.end());
+
+    (start, end)
+}
+
+// pen-up travel of visiting `order` starting from the origin
+pub fn travel(paths: &[Path], order: &[usize]) -> Float {
+    let ends: Vec<_> = paths.iter().map(endpoints).collect();
+
+    let mut pen = (0.0, 0.0);
+    let mut total = 0.0;
+    for &i in order {
+        total += dist(pen, ends[i].0);
+        pen = ends[i].1;
+    }
+    total
+}
+
+// an order to draw `paths` in that keeps pen-up travel short. starts from the origin with a nearest
+// neighbour tour which is then improved with 2-opt moves
+pub fn order_paths(paths: &[Path]) -> Vec<usize> {
+    let ends: Vec<_> = paths.iter().map(endpoints).collect();
+    let cost =
+        |from: Option<usize>, to: usize| dist(from.map_or((0.0, 0.0), |i| ends[i].1), ends[to].0);
+
+    // nearest neighbour
+    let mut order = Vec::with_capacity(paths.len());
+    let mut visited = vec![false; paths.len()];
+    let mut last = None;
+    for _ in 0..paths.len() {
+        let next = (0..paths.len())
+            .filter(|&i| !visited[i])
+            .min_by(|&a, &b| cost(last, a).total_cmp(&cost(last, b)))
+            .expect("unvisited path");
+
+        visited[next] = true;
+        order.push(next);
+        last = Some(next);
+    }
+
+    // 2-opt, travel is directed (a path is drawn from its start to its end) so the cost of the
+    // reversed run is accumulated alongside the forward one
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for i in 0..order.len() {
+            let prev = i.checked_sub(1).map(|k| order[k]);
+
+            let mut forward = 0.0;
+            let mut reversed = 0.0;
+
+            for j in i + 1..order.len() {
+                forward += cost(Some(order[j - 1]), order[j]);
+                reversed += cost(Some(order[j]), order[j - 1]);
+
+                let next = order.get(j + 1).copied();
+                let tail = |k: usize| next.map_or(0.0, |n| cost(Some(k), n));
+
+                let before = cost(prev, order[i]) + forward + tail(order[j]);
+                let after = cost(prev, order[j]) + reversed + tail(order[i]);
+
+                if after + 1e-4 < before {
+                    order[i..=j].reverse();
+                    improved = true;
+
+                    // the running sums describe the old order, restart from this `i`
+                    forward = 0.0;
+                    reversed = 0.0;
+                    for k in i + 1..=j {
+                        forward += cost(Some(order[k - 1]), order[k]);
+                        reversed += cost(Some(order[k]), order[k - 1]);
+                    }
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    order
+}
+
+impl Path {
+    // reorders nothing, but picks where each subpath is drawn from: closed contours are rotated to
+    // start at the vertex nearest the pen and open ones are reversed if their end is closer
+    pub fn optimize_start_points(&mut self) {
+        let mut commands = Vec::with_capacity(self.commands.len());
+        let mut pen = (0.0, 0.0);
+
+        // subpaths that follow a ClosePath start at the previous start, which may have moved
+        let keep = |commands: &mut Vec<Command>, sp: &Subpath| {
+            if !matches!(sp.commands.first(), Some(Command::MoveTo { .. })) {
+                let (x, y) = sp.start;
+                commands.push(Command::MoveTo { x, y });
+            }
+            commands.extend_from_slice(sp.commands);
+        };
+
+        for sp in self.subpaths() {
+            let mut segments: Vec<Segment> = sp.segments().collect();
+
+            if segments.is_empty() {
+                keep(&mut commands, &sp);
+                pen = sp.end();
+                continue;
+            }
+
+            if sp.closed {
+                // a contour that stops short of its start is closed by the implicit line
+                let end = segments[segments.len() - 1].to();
+                if end != sp.start {
+                    segments.push(Segment::Line(end, sp.start));
+                }
+
+                let nearest = (0..segments.len())
+                    .min_by(|&a, &b| {
+                        dist(pen, segments[a].from()).total_cmp(&dist(pen, segments[b].from()))
+                    })
+                    .unwrap_or(0);
+
+                if nearest == 0 {
+                    keep(&mut commands, &sp);
+                } else {
+                    segments.rotate_left(nearest);
+                    push_segments(&segments, true, &mut commands);
+                }
+
+                pen = segments[0].from();
+            } else {
+                let end = sp.end();
+
+                if dist(pen, end) < dist(pen, sp.start) {
+                    let reversed: Vec<Segment> =
+                        segments.iter().rev().map(|s| s.reverse()).collect();
+                    push_segments(&reversed, false, &mut commands);
+                    pen = sp.start;
+                } else {
+                    keep(&mut commands, &sp);
+                    pen = end;
+                }
+            }
+        }
+
+        self.commands = Arc::new(commands);
+    }
+}
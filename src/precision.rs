@@ -0,0 +1,87 @@
+use crate::{path::Path, to_f64, Float};
+
+// finer than this many user units is past what svg optimizers usually keep, f32 steps coarser
+// than it start to show
+const RISKY_RESOLUTION: Float = 1e-3;
+
+// rounding errors are reported up to this many decimals
+const MAX_DECIMALS: usize = 9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecisionReport {
+    // largest coordinate magnitude, 0 for a path without finite coordinates
+    pub max_magnitude: Float,
+    // decimals needed to write every coordinate back unchanged
+    pub decimals: usize,
+    // significant digits of the longest coordinate
+    pub significant_digits: usize,
+    // gap between neighbouring f32 values at `max_magnitude`, nothing finer survives parsing
+    pub resolution: Float,
+    // `quantization_error[n]` is the largest change to any coordinate rounded to n decimals
+    pub quantization_error: Vec<Float>,
+}
+
+impl PrecisionReport {
+    // coordinates are large enough that f32 drops detail an svg would reasonably carry
+    pub fn at_risk(&self) -> bool {
+        self.resolution > RISKY_RESOLUTION
+    }
+
+    // the fewest decimals that keep every coordinate within `tolerance`
+    pub fn decimals_for(&self, tolerance: Float) -> usize {
+        self.quantization_error
+            .iter()
+            .position(|&e| e <= tolerance)
+            .unwrap_or(self.decimals)
+    }
+}
+
+// (decimals, significant digits) of the shortest text that reads back as `v`
+fn digits(v: Float) -> (usize, usize) {
+    let text = v.abs().to_string();
+    let decimals = text.find('.').map_or(0, |dot| text.len() - dot - 1);
+    let significant = text
+        .chars()
+        .filter(char::is_ascii_digit)
+        .skip_while(|&c| c == '0')
+        .count();
+    (decimals, significant)
+}
+
+impl Path {
+    // how much precision the coordinates carry and what rounding them would cost
+    pub fn precision_report(&self) -> PrecisionReport {
+        let coords: Vec<Float> = self
+            .commands
+            .iter()
+            .flat_map(|cmd| cmd.points())
+            .flat_map(|(x, y)| [x, y])
+            .filter(|v| v.is_finite())
+            .collect();
+
+        let max_magnitude = coords.iter().fold(0.0, |m: Float, v| m.max(v.abs()));
+        let (decimals, significant_digits) = coords
+            .iter()
+            .map(|&v| digits(v))
+            .fold((0, 0), |(d, s), (vd, vs)| (d.max(vd), s.max(vs)));
+        let resolution = Float::from_bits(max_magnitude.to_bits() + 1) - max_magnitude;
+
+        let quantization_error = (0..=decimals.min(MAX_DECIMALS))
+            .map(|n| {
+                let scale = 10f64.powi(n as i32);
+                coords.iter().fold(0.0, |m: Float, &v| {
+                    let rounded = ((to_f64(v) * scale).round() / scale) as Float;
+                    m.max((rounded - v).abs())
+                })
+            })
+            .collect();
+
+        PrecisionReport {
+            max_magnitude,
+            decimals,
+            significant_digits,
+            resolution,
+            quantization_error,
+        }
+    }
+}
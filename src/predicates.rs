@@ -0,0 +1,28 @@
+use crate::{to_f64, Point};
+
+// geometric predicates evaluated in f64. differences of f32 coordinates are exact in f64 and so
+// are their products, which makes `orient2d` exact up to the final sum, while `incircle` keeps
+// about twice the precision an f32 evaluation would have. with the `f64` feature the coordinates
+// are f64 already and neither is exact
+
+// positive when a, b, c turn counter clockwise (with y up), negative when clockwise, zero when
+// collinear
+pub(crate) fn orient2d(a: Point, b: Point, c: Point) -> f64 {
+    let (ax, ay) = (to_f64(a.0), to_f64(a.1));
+    let (bx, by) = (to_f64(b.0) - ax, to_f64(b.1) - ay);
+    let (cx, cy) = (to_f64(c.0) - ax, to_f64(c.1) - ay);
+    bx * cy - by * cx
+}
+
+// positive when d lies inside the circle through a, b, c (given counter clockwise), negative
+// outside and zero on it
+pub(crate) fn incircle(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    let rel = |p: Point| (to_f64(p.0) - to_f64(d.0), to_f64(p.1) - to_f64(d.1));
+    let ((ax, ay), (bx, by), (cx, cy)) = (rel(a), rel(b), rel(c));
+
+    let a2 = ax * ax + ay * ay;
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+
+    ax * (by * c2 - b2 * cy) - ay * (bx * c2 - b2 * cx) + a2 * (bx * cy - by * cx)
+}
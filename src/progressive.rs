@@ -0,0 +1,442 @@
+use crate::{path::Path, segment::Segment, to_f32, Command, Float, Point};
+
+const MAGIC: [u8; 4] = *b"SCP\x01";
+// bits of every coordinate in the first level, enough for a recognisable outline
+const COARSE_BITS: u8 = 6;
+// bits each following level adds
+const LEVEL_BITS: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressiveError {
+    // not a progressive encoding
+    Format,
+    // the data ends before the header does, nothing can be drawn yet
+    Truncated,
+}
+
+// what the bytes received so far decode to
+pub struct Progress {
+    pub path: Path,
+    // every coordinate is within this of where it was encoded, until the last level arrives
+    pub error: Float,
+    pub complete: bool,
+}
+
+pub(crate) fn opcode(cmd: &Command) -> u8 {
+    match cmd {
+        Command::MoveTo { .. } => 0,
+        Command::LineTo { .. } => 1,
+        Command::CurveTo { .. } => 2,
+        Command::SmoothCurveTo { .. } => 3,
+        Command::QuadraticBezierCurveTo { .. } => 4,
+        Command::SmoothQuadraticBezierCurveTo { .. } => 5,
+        Command::ClosePath | Command::EllipticalArc { .. } => 6,
+    }
+}
+
+pub(crate) fn arity(opcode: u8) -> Option<usize> {
+    Some(match opcode {
+        0 | 1 => 1,
+        2 | 3 => 3,
+        4 | 5 => 2,
+        6 => 0,
+        _ => return None,
+    })
+}
+
+pub(crate) fn command(opcode: u8, p: &[Point]) -> Command {
+    match opcode {
+        0 => Command::MoveTo {
+            x: p[0].0,
+            y: p[0].1,
+        },
+        1 => Command::LineTo {
+            x: p[0].0,
+            y: p[0].1,
+        },
+        2 => Command::CurveTo {
+            x1: p[0].0,
+            y1: p[0].1,
+            x2: p[1].0,
+            y2: p[1].1,
+            x: p[2].0,
+            y: p[2].1,
+        },
+        3 => Command::SmoothCurveTo {
+            cx: p[0].0,
+            cy: p[0].1,
+            x2: p[1].0,
+            y2: p[1].1,
+            x: p[2].0,
+            y: p[2].1,
+        },
+        4 => Command::QuadraticBezierCurveTo {
+            x1: p[0].0,
+            y1: p[0].1,
+            x: p[1].0,
+            y: p[1].1,
+        },
+        5 => Command::SmoothQuadraticBezierCurveTo {
+            cx: p[0].0,
+            cy: p[0].1,
+            x: p[1].0,
+            y: p[1].1,
+        },
+        _ => Command::ClosePath,
+    }
+}
+
+pub(crate) fn varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+pub(crate) fn read_varint(bytes: &[u8], at: &mut usize) -> Option<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*at)?;
+        *at += 1;
+        n |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return Some(n);
+        }
+    }
+    None
+}
+
+pub(crate) fn read_f32(bytes: &[u8], at: &mut usize) -> Option<Float> {
+    let raw = bytes.get(*at..*at + 4)?;
+    *at += 4;
+    Some(Float::from(f32::from_le_bytes(raw.try_into().ok()?)))
+}
+
+// most significant bit first
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: u32,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u32, bits: u8) {
+        for i in (0..bits).rev() {
+            if self.used.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = (value >> i) as u8 & 1;
+            *self.bytes.last_mut().expect("byte") |= bit << (7 - self.used % 8);
+            self.used += 1;
+        }
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: u8) -> Option<u32> {
+        if self.at + usize::from(bits) > self.bytes.len() * 8 {
+            return None;
+        }
+
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = self.bytes[self.at / 8] >> (7 - self.at % 8) & 1;
+            value = value << 1 | u32::from(bit);
+            self.at += 1;
+        }
+        Some(value)
+    }
+}
+
+// bits sent in each level, coarse to fine
+fn levels(total: u8) -> impl Iterator<Item = u8> {
+    let mut sent = 0;
+    std::iter::from_fn(move || {
+        let bits = if sent == 0 { COARSE_BITS } else { LEVEL_BITS }.min(total - sent);
+        sent += bits;
+        (bits > 0).then_some(bits)
+    })
+}
+
+impl Path {
+    // the path as bytes that can be drawn from any prefix past the header: the first level has the
+    // top bits of every coordinate, each level after adds the next ones, and the last puts every
+    // coordinate within `precision` of where it is. arcs are sent as the cubics they are made of,
+    // coordinates that are not finite as the corner of the bounds
+    pub fn to_progressive(&self, precision: Float) -> Vec<u8> {
+        let mut opcodes = Vec::with_capacity(self.commands.len());
+        let mut points = Vec::new();
+        let mut pen = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+
+        for cmd in self.commands.iter() {
+            match *cmd {
+                Command::EllipticalArc { .. } => {
+                    for seg in Segment::pieces(pen, cmd) {
+                        let cubic = seg.to_cubic().to_command();
+                        opcodes.push(opcode(&cubic));
+                        points.extend(cubic.points());
+                    }
+                }
+                ref cmd => {
+                    opcodes.push(opcode(cmd));
+                    points.extend(cmd.points());
+                }
+            }
+            pen = match *cmd {
+                Command::MoveTo { x, y } => {
+                    start = (x, y);
+                    start
+                }
+                Command::ClosePath => start,
+                ref cmd => cmd.points().last().unwrap_or(pen),
+            };
+        }
+
+        let finite = points
+            .iter()
+            .flat_map(|&(x, y)| [x, y])
+            .filter(|v| v.is_finite());
+        let (min, max) = finite.fold((Float::MAX, Float::MIN), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+        let (min, span) = if min <= max {
+            (min, max - min)
+        } else {
+            (0.0, 0.0)
+        };
+
+        // one step of the finest level covers `2 * precision`, widened until 32 bits reach across
+        let mut step = (precision * 2.0).max(Float::from(f32::MIN_POSITIVE));
+        if span / step > u32::MAX as Float {
+            step = span / u32::MAX as Float;
+        }
+        // the header holds both as f32, quantized against what a reader will get back
+        let (min, step) = (Float::from(to_f32(min)), Float::from(to_f32(step)));
+        let steps = ((span / step).ceil() as u64).min(u64::from(u32::MAX));
+        let total = (u64::BITS - steps.leading_zeros()).max(1) as u8;
+
+        let mut out = MAGIC.to_vec();
+        varint(&mut out, opcodes.len() as u64);
+        // two opcodes to a byte
+        for pair in opcodes.chunks(2) {
+            out.push(pair[0] << 4 | pair.get(1).copied().unwrap_or(0));
+        }
+        out.extend(to_f32(min).to_le_bytes());
+        out.extend(to_f32(step).to_le_bytes());
+        out.push(total);
+
+        let quantized: Vec<u32> = points
+            .iter()
+            .flat_map(|&(x, y)| [x, y])
+            .map(|v| ((v - min) / step).round().clamp(0.0, steps as Float) as u32)
+            .collect();
+
+        let mut bits = BitWriter {
+            used: out.len() as u32 * 8,
+            bytes: out,
+        };
+        let mut sent = 0;
+        for level in levels(total) {
+            sent += level;
+            for &q in &quantized {
+                bits.push(q >> (total - sent), level);
+            }
+        }
+
+        bits.bytes
+    }
+
+    // a path from the start of `to_progressive` output, as refined as the bytes so far allow.
+    // coordinates whose next bits have not arrived sit in the middle of where they may end up
+    pub fn from_progressive(bytes: &[u8]) -> Result<Progress, ProgressiveError> {
+        let magic = &bytes[..bytes.len().min(MAGIC.len())];
+        if magic != &MAGIC[..magic.len()] {
+            return Err(ProgressiveError::Format);
+        }
+
+        let mut at = MAGIC.len();
+        let count = read_varint(bytes, &mut at).ok_or(ProgressiveError::Truncated)? as usize;
+        let packed = bytes
+            .get(at..at + count.div_ceil(2))
+            .ok_or(ProgressiveError::Truncated)?;
+        at += packed.len();
+        let opcodes: Vec<u8> = packed
+            .iter()
+            .flat_map(|byte| [byte >> 4, byte & 0xf])
+            .take(count)
+            .collect();
+
+        let min = read_f32(bytes, &mut at).ok_or(ProgressiveError::Truncated)?;
+        let step = read_f32(bytes, &mut at).ok_or(ProgressiveError::Truncated)?;
+        let total = *bytes.get(at).ok_or(ProgressiveError::Truncated)?;
+        at += 1;
+        if total > 32 {
+            return Err(ProgressiveError::Format);
+        }
+
+        let arities = opcodes
+            .iter()
+            .map(|&op| arity(op))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ProgressiveError::Format)?;
+        let coordinates = arities.iter().sum::<usize>() * 2;
+
+        // the bits known of every coordinate so far
+        let mut known = vec![(0u32, 0u8); coordinates];
+        let mut reader = BitReader { bytes, at: at * 8 };
+        'levels: for level in levels(total) {
+            for (value, bits) in &mut known {
+                let Some(more) = reader.read(level) else {
+                    break 'levels;
+                };
+                *value = *value << level | more;
+                *bits += level;
+            }
+        }
+
+        let least = known.iter().map(|&(_, bits)| bits).min().unwrap_or(total);
+        // steps from the middle to the ends of what the missing bits leave open, the rounding
+        // every coordinate had when it was quantized comes on top
+        let open = match total - least {
+            0 => 0.0,
+            missing => (1u64 << (missing - 1)) as Float,
+        };
+        let value = |(value, bits): (u32, u8)| {
+            let missing = total - bits;
+            let mut steps = u64::from(value) << missing;
+            // the middle of what the missing bits may hold
+            if missing > 0 {
+                steps += 1 << (missing - 1);
+            }
+            min + steps as Float * step
+        };
+
+        let mut coords = known.into_iter().map(value);
+        let mut points = Vec::with_capacity(3);
+        let commands = opcodes
+            .iter()
+            .zip(arities)
+            .map(|(&op, arity)| {
+                points.clear();
+                for _ in 0..arity {
+                    let x = coords.next().unwrap_or(min);
+                    let y = coords.next().unwrap_or(min);
+                    points.push((x, y));
+                }
+                command(op, &points)
+            })
+            .collect();
+
+        Ok(Progress {
+            path: Path::new(commands),
+            error: step * (open + 0.5),
+            complete: least == total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_path_str, Parser};
+
+    fn path(d: &str) -> Path {
+        Path::new(parse_path_str(d).unwrap())
+    }
+
+    fn max_error(a: &Path, b: &Path) -> Float {
+        assert_eq!(a.commands().len(), b.commands().len());
+        a.commands()
+            .iter()
+            .zip(b.commands())
+            .flat_map(|(a, b)| {
+                assert_eq!(opcode(a), opcode(b));
+                a.points().zip(b.points()).collect::<Vec<_>>()
+            })
+            .map(|(p, q)| (p.0 - q.0).abs().max((p.1 - q.1).abs()))
+            .fold(0.0, Float::max)
+    }
+
+    #[test]
+    fn refines_to_within_precision() {
+        let original =
+            path("M10 10 C 20 -5 37.25 40 50 12.5 S 80 3 91 77 Q 3 4 5 6 T 0 .5 Z L 3 3");
+        let bytes = original.to_progressive(0.01);
+
+        let full = Path::from_progressive(&bytes).unwrap();
+        assert!(full.complete);
+        assert!(full.error <= 0.01);
+        assert!(max_error(&original, &full.path) <= 0.01 + 1e-4);
+
+        // every prefix past the header draws the whole path, within the error it reports, and
+        // longer prefixes only get closer
+        let header = bytes.len() - bytes.len().min(160);
+        let mut last = Float::INFINITY;
+        for end in header..bytes.len() {
+            let Ok(progress) = Path::from_progressive(&bytes[..end]) else {
+                continue;
+            };
+            assert!(!progress.complete);
+            assert!(progress.error <= last);
+            assert!(max_error(&original, &progress.path) <= progress.error + 1e-4);
+            last = progress.error;
+        }
+        assert!(last.is_finite());
+    }
+
+    #[test]
+    fn arcs_arrive_as_cubics() {
+        let original = Path::new(
+            Parser::new("M0 0 A 10 10 0 0 1 20 0")
+                .preserve_arcs(true)
+                .parse()
+                .unwrap(),
+        );
+        let progress = Path::from_progressive(&original.to_progressive(0.001)).unwrap();
+        let commands = progress.path.commands();
+        assert!(commands[1..]
+            .iter()
+            .all(|cmd| matches!(cmd, Command::CurveTo { .. })));
+        let (x, y) = commands.last().unwrap().points().last().unwrap();
+        assert!((x - 20.0).abs() <= 0.001 && y.abs() <= 0.001);
+    }
+
+    #[test]
+    fn rejects_what_it_did_not_write() {
+        let bytes = path("M0 0 L1 1").to_progressive(0.1);
+        assert_eq!(
+            Path::from_progressive(&[]).err(),
+            Some(ProgressiveError::Truncated)
+        );
+        assert_eq!(
+            Path::from_progressive(&bytes[..6]).err(),
+            Some(ProgressiveError::Truncated)
+        );
+        assert_eq!(
+            Path::from_progressive(b"SC").err(),
+            Some(ProgressiveError::Truncated)
+        );
+        assert_eq!(
+            Path::from_progressive(b"no").err(),
+            Some(ProgressiveError::Format)
+        );
+        assert_eq!(
+            Path::from_progressive(b"SCZ\x01 compressed").err(),
+            Some(ProgressiveError::Format)
+        );
+
+        // an opcode nothing is written with
+        let mut bad = bytes.clone();
+        bad[MAGIC.len() + 1] = 0xf0;
+        assert_eq!(
+            Path::from_progressive(&bad).err(),
+            Some(ProgressiveError::Format)
+        );
+    }
+}
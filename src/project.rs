@@ -0,0 +1,117 @@
+use crate::consts::PI;
+
+use crate::{
+    offset::segment_distance,
+    path::Path,
+    segment::{push_segments, Segment},
+    Float, Point,
+};
+
+// recursion guard, a projection that never settles (or nan input) stops here
+const MAX_DEPTH: u32 = 12;
+
+// web mercator stops short of the poles, which it would put at infinity
+const MAX_LATITUDE: Float = 85.051_13;
+
+pub trait Projection {
+    fn project(&self, p: Point) -> Point;
+}
+
+impl<F: Fn(Point) -> Point> Projection for F {
+    fn project(&self, p: Point) -> Point {
+        self(p)
+    }
+}
+
+// (longitude, latitude) in degrees to the unit square, y down from the north edge as in svg
+pub struct Equirectangular;
+
+impl Equirectangular {
+    pub fn inverse((x, y): Point) -> Point {
+        (x * 360.0 - 180.0, 90.0 - y * 180.0)
+    }
+}
+
+impl Projection for Equirectangular {
+    fn project(&self, (lon, lat): Point) -> Point {
+        ((lon + 180.0) / 360.0, (90.0 - lat) / 180.0)
+    }
+}
+
+// (longitude, latitude) in degrees to the web mercator unit square, y down from the north edge.
+// latitudes past about 85 degrees are clamped
+pub struct Mercator;
+
+impl Mercator {
+    pub fn inverse((x, y): Point) -> Point {
+        let lat = (PI * (1.0 - 2.0 * y)).sinh().atan();
+        (x * 360.0 - 180.0, lat.to_degrees())
+    }
+}
+
+impl Projection for Mercator {
+    fn project(&self, (lon, lat): Point) -> Point {
+        let phi = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+        let y = (PI / 4.0 + phi / 2.0).tan().ln();
+        ((lon + 180.0) / 360.0, 0.5 - y / (2.0 * PI))
+    }
+}
+
+// the projected segment as lines, split until a few samples of the projected curve are all within
+// `tolerance` of each line
+fn subdivide(
+    seg: &Segment,
+    (t0, p0): (Float, Point),
+    (t1, p1): (Float, Point),
+    projection: &impl Projection,
+    tolerance: Float,
+    depth: u32,
+    out: &mut Vec<Segment>,
+) {
+    let samples = [0.25, 0.5, 0.75].map(|f| {
+        let t = t0 + (t1 - t0) * f;
+        (t, projection.project(seg.eval(t)))
+    });
+
+    let flat = samples
+        .iter()
+        .all(|&(_, p)| segment_distance(p, p0, p1) <= tolerance);
+    if flat || depth >= MAX_DEPTH {
+        out.push(Segment::Line(p0, p1));
+        return;
+    }
+
+    let mid = samples[1];
+    subdivide(seg, (t0, p0), mid, projection, tolerance, depth + 1, out);
+    subdivide(seg, mid, (t1, p1), projection, tolerance, depth + 1, out);
+}
+
+impl Path {
+    // every point sent through `projection`. curves and lines bend under most projections, so
+    // each segment is followed with lines no further than `tolerance` (in projected units) from
+    // where the projected segment really runs. subpaths that draw nothing are dropped
+    pub fn project(&self, projection: &impl Projection, tolerance: Float) -> Path {
+        let mut commands = Vec::new();
+        let mut lines = Vec::new();
+
+        for sp in self.subpaths() {
+            lines.clear();
+            for seg in sp.segments() {
+                let (p0, p1) = (projection.project(seg.from()), projection.project(seg.to()));
+                subdivide(
+                    &seg,
+                    (0.0, p0),
+                    (1.0, p1),
+                    projection,
+                    tolerance,
+                    0,
+                    &mut lines,
+                );
+            }
+
+            push_segments(&lines, sp.closed, &mut commands);
+        }
+
+        Path::new(commands)
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    fill::fill_rings,
+    path::Path,
+    scanline::{spans, FillRule},
+    trace::trace_span,
+    Float,
+};
+
+// sub-scanlines per pixel row
+const SAMPLES: usize = 5;
+
+// 8-bit coverage, row major, 0 is empty and 255 fully covered
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mask {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+// add `amount` of horizontal coverage of [x0, x1) to the row
+fn accumulate(row: &mut [Float], x0: Float, x1: Float, amount: Float) {
+    let width = row.len() as Float;
+    let x0 = x0.clamp(0.0, width);
+    let x1 = x1.clamp(0.0, width);
+    if x1 <= x0 {
+        return;
+    }
+
+    let first = x0 as usize;
+    let last = (x1.ceil() as usize).min(row.len()) - 1;
+
+    if first == last {
+        row[first] += (x1 - x0) * amount;
+        return;
+    }
+
+    row[first] += (first as Float + 1.0 - x0) * amount;
+    for px in &mut row[first + 1..last] {
+        *px += amount;
+    }
+    row[last] += (x1 - last as Float) * amount;
+}
+
+impl Path {
+    // anti-aliased coverage of the filled path, one unit is one pixel
+    pub fn rasterize(&self, width: usize, height: usize, fill_rule: FillRule) -> Mask {
+        trace_span!("rasterize", width, height);
+
+        let mut data = vec![0u8; width * height];
+        let rings = fill_rings(self, 0.1);
+
+        let mut row: Vec<Float> = vec![0.0; width];
+        for y in 0..height {
+            row.iter_mut().for_each(|px| *px = 0.0);
+
+            for s in 0..SAMPLES {
+                let sy = y as Float + (s as Float + 0.5) / SAMPLES as Float;
+                for (x0, x1) in spans(&rings, sy, fill_rule) {
+                    accumulate(&mut row, x0, x1, 1.0 / SAMPLES as Float);
+                }
+            }
+
+            for (px, coverage) in data[y * width..(y + 1) * width].iter_mut().zip(&row) {
+                *px = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+
+        Mask {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+impl Mask {
+    // binary portable graymap
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut out = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    // 8-bit grayscale png, uncompressed (stored deflate blocks) to stay dependency free
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width + 1) * self.height);
+        for row in self.data.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut zlib = vec![0x78, 0x01];
+        let blocks = raw.chunks(0xffff);
+        let count = blocks.len();
+        for (i, block) in blocks.enumerate() {
+            zlib.push(u8::from(i + 1 == count));
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        if count == 0 {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth 8, grayscale, deflate, adaptive filtering, no interlace
+        header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+        let mut out = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        chunk(&mut out, b"IHDR", &header);
+        chunk(&mut out, b"IDAT", &zlib);
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+}
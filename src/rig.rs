@@ -0,0 +1,104 @@
+use crate::{offset::segment_distance, path::Path, trace::audit, Float, Point};
+
+// a bone in its rest position, drawn from `start` to `end`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bone {
+    pub start: Point,
+    pub end: Point,
+}
+
+// how a bone moved away from its rest position: turned by `angle` radians about its rest start,
+// then shifted by (dx, dy). bones do not inherit from each other, chains compose their own
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BoneTransform {
+    pub angle: Float,
+    pub dx: Float,
+    pub dy: Float,
+}
+
+impl BoneTransform {
+    fn apply(&self, bone: &Bone, (x, y): Point) -> Point {
+        let (sin, cos) = self.angle.sin_cos();
+        let (rx, ry) = (x - bone.start.0, y - bone.start.1);
+        (
+            bone.start.0 + rx * cos - ry * sin + self.dx,
+            bone.start.1 + rx * sin + ry * cos + self.dy,
+        )
+    }
+}
+
+// bones and, for every point of the bound path in the order `Command::points` yields them, the
+// bones it follows as (bone index, weight) with weights summing to one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rig {
+    pub bones: Vec<Bone>,
+    pub weights: Vec<Vec<(usize, Float)>>,
+}
+
+// bones weighing less than this after normalizing are dropped
+const MIN_WEIGHT: Float = 0.01;
+
+impl Rig {
+    // bind every point of `path` to the bones, weighted by inverse square distance so points
+    // follow the closest bone and blend smoothly between neighbouring ones
+    pub fn bind(path: &Path, bones: Vec<Bone>) -> Rig {
+        let weights = path
+            .commands
+            .iter()
+            .flat_map(|cmd| cmd.points())
+            .map(|p| {
+                let raw: Vec<Float> = bones
+                    .iter()
+                    .map(|bone| {
+                        let d = segment_distance(p, bone.start, bone.end);
+                        1.0 / (d * d).max(Float::EPSILON)
+                    })
+                    .collect();
+                let total: Float = raw.iter().sum();
+
+                let mut kept: Vec<(usize, Float)> = raw
+                    .iter()
+                    .map(|w| w / total)
+                    .enumerate()
+                    .filter(|&(_, w)| w >= MIN_WEIGHT)
+                    .collect();
+                let kept_total: Float = kept.iter().map(|(_, w)| w).sum();
+                kept.iter_mut().for_each(|(_, w)| *w /= kept_total);
+                kept
+            })
+            .collect();
+
+        Rig { bones, weights }
+    }
+}
+
+impl Path {
+    // the path deformed by moving the rig's bones, `pose[i]` moves bone i and bones without a
+    // transform stay at rest. points the rig has no weights for are left where they are
+    pub fn pose(&self, rig: &Rig, pose: &[BoneTransform]) -> Path {
+        let mut weights = rig.weights.iter();
+
+        let commands = self
+            .commands
+            .iter()
+            .map(|cmd| {
+                let posed = cmd.map_points(|p| match weights.next() {
+                    Some(bound) if !bound.is_empty() => {
+                        bound.iter().fold((0.0, 0.0), |acc, &(i, w)| {
+                            let moved = match (rig.bones.get(i), pose.get(i)) {
+                                (Some(bone), Some(t)) => t.apply(bone, p),
+                                _ => p,
+                            };
+                            (acc.0 + moved.0 * w, acc.1 + moved.1 * w)
+                        })
+                    }
+                    _ => p,
+                });
+                audit!("pose", *cmd, posed);
+                posed
+            })
+            .collect();
+
+        Path::new(commands)
+    }
+}
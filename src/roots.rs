@@ -0,0 +1,95 @@
+use crate::{segment::Segment, to_f64, Float};
+
+// below this a leading coefficient is treated as zero and the degree dropped
+const EPSILON: f64 = 1e-12;
+
+// power basis coefficients of one coordinate of `seg`, highest degree first:
+// value(t) = c[0] t^3 + c[1] t^2 + c[2] t + c[3]
+pub(crate) fn coefficients(seg: &Segment, axis: fn((Float, Float)) -> Float) -> [f64; 4] {
+    let v = |p| to_f64(axis(p));
+    match *seg {
+        Segment::Line(p0, p1) => [0.0, 0.0, v(p1) - v(p0), v(p0)],
+        Segment::Quad(p0, p1, p2) => [
+            0.0,
+            v(p0) - 2.0 * v(p1) + v(p2),
+            2.0 * (v(p1) - v(p0)),
+            v(p0),
+        ],
+        Segment::Cubic(p0, p1, p2, p3) => [
+            -v(p0) + 3.0 * v(p1) - 3.0 * v(p2) + v(p3),
+            3.0 * v(p0) - 6.0 * v(p1) + 3.0 * v(p2),
+            3.0 * (v(p1) - v(p0)),
+            v(p0),
+        ],
+    }
+}
+
+// real roots of a t^3 + b t^2 + c t + d, unsorted
+pub(crate) fn solve([a, b, c, d]: [f64; 4]) -> Vec<f64> {
+    let scale = a.abs().max(b.abs()).max(c.abs()).max(d.abs());
+    if scale == 0.0 {
+        return Vec::new();
+    }
+
+    if a.abs() > EPSILON * scale {
+        return cubic(b / a, c / a, d / a);
+    }
+    if b.abs() > EPSILON * scale {
+        return quadratic(b, c, d);
+    }
+    if c.abs() > EPSILON * scale {
+        return vec![-d / c];
+    }
+    Vec::new()
+}
+
+fn quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let disc = b * b - 4.0 * a * c;
+    if disc < 0.0 {
+        return Vec::new();
+    }
+    if disc == 0.0 {
+        return vec![-b / (2.0 * a)];
+    }
+
+    // avoids cancellation between -b and the square root
+    let q = -0.5 * (b + b.signum() * disc.sqrt());
+    if q == 0.0 {
+        return vec![0.0];
+    }
+    vec![q / a, c / q]
+}
+
+// roots of t^3 + a t^2 + b t + c
+fn cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let q = (a * a - 3.0 * b) / 9.0;
+    let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+    let shift = a / 3.0;
+
+    if r * r < q * q * q {
+        // three real roots
+        let theta = (r / (q * q * q).sqrt()).clamp(-1.0, 1.0).acos();
+        let m = -2.0 * q.sqrt();
+        let tau = std::f64::consts::TAU;
+        return vec![
+            m * (theta / 3.0).cos() - shift,
+            m * ((theta + tau) / 3.0).cos() - shift,
+            m * ((theta - tau) / 3.0).cos() - shift,
+        ];
+    }
+
+    let e = -r.signum() * (r.abs() + (r * r - q * q * q).sqrt()).cbrt();
+    let f = if e == 0.0 { 0.0 } else { q / e };
+    vec![e + f - shift]
+}
+
+// roots inside [0, 1], nudging values just outside it back in
+pub(crate) fn unit_roots(coefficients: [f64; 4]) -> Vec<Float> {
+    const SLACK: f64 = 1e-7;
+
+    solve(coefficients)
+        .into_iter()
+        .filter(|t| (-SLACK..=1.0 + SLACK).contains(t))
+        .map(|t| t.clamp(0.0, 1.0) as Float)
+        .collect()
+}
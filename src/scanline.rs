@@ -0,0 +1,108 @@
+use crate::{Float, Point};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    #[inline]
+    pub fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+// every crossing of the horizontal line at `y` with the (implicitly closed) rings, as
+// (x, direction) sorted by x. edges are half-open in y so shared vertices are counted once
+pub(crate) fn crossings(rings: &[Vec<(Float, Float)>], y: Float) -> Vec<(Float, i32)> {
+    let mut out = Vec::new();
+
+    for ring in rings {
+        if ring.len() < 2 {
+            continue;
+        }
+
+        for i in 0..ring.len() {
+            let a = ring[i];
+            let b = ring[(i + 1) % ring.len()];
+
+            if (a.1 <= y) == (b.1 <= y) {
+                continue;
+            }
+
+            let x = a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1);
+            out.push((x, if b.1 > a.1 { 1 } else { -1 }));
+        }
+    }
+
+    out.sort_by(|a, b| a.0.total_cmp(&b.0));
+    out
+}
+
+// whether `p` is inside the rings under `fill_rule`
+pub(crate) fn contains(rings: &[Vec<(Float, Float)>], (x, y): Point, fill_rule: FillRule) -> bool {
+    let winding = crossings(rings, y)
+        .into_iter()
+        .filter(|&(cx, _)| cx > x)
+        .map(|(_, dir)| dir)
+        .sum();
+    fill_rule.is_inside(winding)
+}
+
+// the x-intervals of the line at `y` that lie inside the rings under `fill_rule`
+pub(crate) fn spans(
+    rings: &[Vec<(Float, Float)>],
+    y: Float,
+    fill_rule: FillRule,
+) -> Vec<(Float, Float)> {
+    let mut out: Vec<(Float, Float)> = Vec::new();
+
+    let mut winding = 0;
+    let mut span_start = 0.0;
+
+    for (x, dir) in crossings(rings, y) {
+        let was_inside = fill_rule.is_inside(winding);
+        winding += dir;
+        let inside = fill_rule.is_inside(winding);
+
+        if !was_inside && inside {
+            span_start = x;
+        } else if was_inside && !inside && x > span_start {
+            match out.last_mut() {
+                // merge spans that touch (coincident edges of adjacent rings)
+                Some(last) if last.1 >= span_start => last.1 = x,
+                _ => out.push((span_start, x)),
+            }
+        }
+    }
+
+    out
+}
+
+// the y range covered by the rings
+pub(crate) fn y_extent(rings: &[Vec<(Float, Float)>]) -> Option<(Float, Float)> {
+    rings
+        .iter()
+        .flatten()
+        .fold(None, |acc: Option<(Float, Float)>, p| match acc {
+            None => Some((p.1, p.1)),
+            Some((lo, hi)) => Some((lo.min(p.1), hi.max(p.1))),
+        })
+}
+
+// the bounding box of the rings as (min, max)
+pub(crate) fn extent(rings: &[Vec<Point>]) -> Option<(Point, Point)> {
+    let mut points = rings.iter().flatten();
+    let first = *points.next()?;
+    Some(points.fold((first, first), |(lo, hi), p| {
+        (
+            (lo.0.min(p.0), lo.1.min(p.1)),
+            (hi.0.max(p.0), hi.1.max(p.1)),
+        )
+    }))
+}
@@ -0,0 +1,147 @@
+use crate::{
+    fill::fill_rings,
+    path::Path,
+    rng::{Rng, SplitMix64},
+    scanline::{contains, extent, spans, FillRule},
+    trace::{trace_count, trace_span},
+    Float, Point,
+};
+
+// candidates tried around an active point before it is retired
+const ATTEMPTS: usize = 30;
+
+// one slot per cell of side radius / sqrt(2), so a cell never holds more than one point
+struct Grid {
+    min: Point,
+    cell: Float,
+    cols: usize,
+    rows: usize,
+    slots: Vec<Option<usize>>,
+}
+
+impl Grid {
+    fn cell_of(&self, (x, y): Point) -> (usize, usize) {
+        let col = ((x - self.min.0) / self.cell) as usize;
+        let row = ((y - self.min.1) / self.cell) as usize;
+        (col.min(self.cols - 1), row.min(self.rows - 1))
+    }
+
+    fn is_free(&self, points: &[Point], p: Point, radius: Float) -> bool {
+        let (col, row) = self.cell_of(p);
+        for r in row.saturating_sub(2)..(row + 3).min(self.rows) {
+            for c in col.saturating_sub(2)..(col + 3).min(self.cols) {
+                if let Some(i) = self.slots[r * self.cols + c] {
+                    let q = points[i];
+                    if (q.0 - p.0).powi(2) + (q.1 - p.1).powi(2) < radius * radius {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Path {
+    // blue noise points inside the filled (non-zero) region, no two closer than `radius`. the same
+    // seed gives the same points. every disconnected part of the shape gets seeded, parts too thin
+    // to hold a point may stay empty
+    pub fn scatter_points(&self, radius: Float, seed: u64) -> Vec<Point> {
+        self.scatter_points_with(radius, SplitMix64::new(seed))
+    }
+
+    // `scatter_points` drawing from `rng`
+    pub fn scatter_points_with(&self, radius: Float, mut rng: impl Rng) -> Vec<Point> {
+        trace_span!("scatter_points", radius);
+
+        let mut points = Vec::new();
+
+        if radius.is_nan() || radius <= 0.0 {
+            return points;
+        }
+
+        let rings = fill_rings(self, radius * 0.05);
+        let Some((min, max)) = extent(&rings) else {
+            return points;
+        };
+
+        let cell = radius / crate::consts::SQRT_2;
+        let cols = ((max.0 - min.0) / cell) as usize + 1;
+        let rows = ((max.1 - min.1) / cell) as usize + 1;
+        let mut grid = Grid {
+            min,
+            cell,
+            cols,
+            rows,
+            slots: vec![None; cols * rows],
+        };
+
+        let mut active = Vec::new();
+        // rows above this one have no room left for a seed
+        let mut seed_row = 0;
+
+        loop {
+            while !active.is_empty() {
+                let k = rng.below(active.len());
+                let from: Point = points[active[k]];
+
+                let mut placed = false;
+                for _ in 0..ATTEMPTS {
+                    let angle = rng.next_f32() as Float * crate::consts::TAU;
+                    let dist = radius * (1.0 + rng.next_f32() as Float);
+                    let p = (from.0 + dist * angle.cos(), from.1 + dist * angle.sin());
+
+                    if p.0 < min.0 || p.0 > max.0 || p.1 < min.1 || p.1 > max.1 {
+                        continue;
+                    }
+                    if grid.is_free(&points, p, radius) && contains(&rings, p, FillRule::NonZero) {
+                        let (c, r) = grid.cell_of(p);
+                        grid.slots[r * cols + c] = Some(points.len());
+                        active.push(points.len());
+                        points.push(p);
+                        placed = true;
+                        break;
+                    }
+                }
+
+                if !placed {
+                    active.swap_remove(k);
+                }
+            }
+
+            // the growth stalled, look for a region it could not reach
+            let mut seed = None;
+            while seed_row < rows && seed.is_none() {
+                let y = min.1 + (seed_row as Float + rng.next_f32() as Float) * cell;
+                for (x0, x1) in spans(&rings, y, FillRule::NonZero) {
+                    let mut x = x0 + rng.next_f32() as Float * cell.min(x1 - x0);
+                    while x < x1 {
+                        if grid.is_free(&points, (x, y), radius) {
+                            seed = Some((x, y));
+                            break;
+                        }
+                        x += cell;
+                    }
+                    if seed.is_some() {
+                        break;
+                    }
+                }
+                if seed.is_none() {
+                    seed_row += 1;
+                }
+            }
+
+            let Some(p) = seed else {
+                break;
+            };
+            let (c, r) = grid.cell_of(p);
+            grid.slots[r * cols + c] = Some(points.len());
+            active.push(points.len());
+            points.push(p);
+        }
+
+        trace_count!(points = points.len(), "scattered");
+
+        points
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{simplification::arc_cubics, Command, Float};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    Line((Float, Float), (Float, Float)),
+    Quad((Float, Float), (Float, Float), (Float, Float)),
+    Cubic(
+        (Float, Float),
+        (Float, Float),
+        (Float, Float),
+        (Float, Float),
+    ),
+}
+
+#[inline(always)]
+pub(crate) fn lerp(a: (Float, Float), b: (Float, Float), t: Float) -> (Float, Float) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+// 5 point gauss-legendre nodes on [-1, 1] and their weights
+const GAUSS: [(Float, Float); 5] = [
+    (0.0, 0.568_888_9),
+    (-0.538_469_3, 0.478_628_67),
+    (0.538_469_3, 0.478_628_67),
+    (-0.906_179_8, 0.236_926_88),
+    (0.906_179_8, 0.236_926_88),
+];
+
+#[inline(always)]
+pub(crate) fn dist(a: (Float, Float), b: (Float, Float)) -> Float {
+    ((b.0 - a.0) * (b.0 - a.0) + (b.1 - a.1) * (b.1 - a.1)).sqrt()
+}
+
+impl Segment {
+    pub fn from(&self) -> (Float, Float) {
+        match *self {
+            Segment::Line(p0, _) | Segment::Quad(p0, _, _) | Segment::Cubic(p0, _, _, _) => p0,
+        }
+    }
+
+    pub fn to(&self) -> (Float, Float) {
+        match *self {
+            Segment::Line(_, p1) | Segment::Quad(_, _, p1) | Segment::Cubic(_, _, _, p1) => p1,
+        }
+    }
+
+    pub fn eval(&self, t: Float) -> (Float, Float) {
+        match *self {
+            Segment::Line(p0, p1) => lerp(p0, p1, t),
+            Segment::Quad(p0, p1, p2) => lerp(lerp(p0, p1, t), lerp(p1, p2, t), t),
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let a = lerp(p0, p1, t);
+                let b = lerp(p1, p2, t);
+                let c = lerp(p2, p3, t);
+                lerp(lerp(a, b, t), lerp(b, c, t), t)
+            }
+        }
+    }
+
+    // first derivative, not normalized
+    pub fn derivative(&self, t: Float) -> (Float, Float) {
+        match *self {
+            Segment::Line(p0, p1) => (p1.0 - p0.0, p1.1 - p0.1),
+            Segment::Quad(p0, p1, p2) => {
+                let a = lerp(p0, p1, t);
+                let b = lerp(p1, p2, t);
+                (2.0 * (b.0 - a.0), 2.0 * (b.1 - a.1))
+            }
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let a = lerp(p0, p1, t);
+                let b = lerp(p1, p2, t);
+                let c = lerp(p2, p3, t);
+                let d = lerp(a, b, t);
+                let e = lerp(b, c, t);
+                (3.0 * (e.0 - d.0), 3.0 * (e.1 - d.1))
+            }
+        }
+    }
+
+    // arc length, exact for lines. curves integrate the speed with gauss-legendre, halving the
+    // parameter range wherever the halves disagree with the whole
+    pub fn length(&self) -> Float {
+        let Segment::Line(p0, p1) = *self else {
+            let tolerance = self.hull_length() * 1e-6;
+            return self.integrate(0.0, 1.0, self.gauss(0.0, 1.0), tolerance, 12);
+        };
+        dist(p0, p1)
+    }
+
+    // the parameter `length` along the segment, clamped to its ends
+    pub fn t_at_length(&self, length: Float) -> Float {
+        let total = self.length();
+        if length.is_nan() || length <= 0.0 || total.is_nan() || total <= 0.0 {
+            return 0.0;
+        }
+        if length >= total {
+            return 1.0;
+        }
+        if let Segment::Line(..) = self {
+            return length / total;
+        }
+
+        // newton steps on the arc length, kept inside a bracket that bisection shrinks when a
+        // step would leave it
+        let tolerance = total * 1e-6;
+        let (mut lo, mut hi) = (0.0, 1.0);
+        let mut t = length / total;
+        for _ in 0..32 {
+            let error = self.integrate(0.0, t, self.gauss(0.0, t), tolerance, 8) - length;
+            if error.abs() <= tolerance {
+                break;
+            }
+            if error > 0.0 {
+                hi = t;
+            } else {
+                lo = t;
+            }
+
+            let (dx, dy) = self.derivative(t);
+            let next = t - error / (dx * dx + dy * dy).sqrt();
+            t = if next > lo && next < hi {
+                next
+            } else {
+                (lo + hi) / 2.0
+            };
+        }
+        t
+    }
+
+    fn hull_length(&self) -> Float {
+        match *self {
+            Segment::Line(p0, p1) => dist(p0, p1),
+            Segment::Quad(p0, p1, p2) => dist(p0, p1) + dist(p1, p2),
+            Segment::Cubic(p0, p1, p2, p3) => dist(p0, p1) + dist(p1, p2) + dist(p2, p3),
+        }
+    }
+
+    fn gauss(&self, a: Float, b: Float) -> Float {
+        let (mid, half) = ((a + b) / 2.0, (b - a) / 2.0);
+        let speed = |t| {
+            let (dx, dy) = self.derivative(t);
+            (dx * dx + dy * dy).sqrt()
+        };
+        half * GAUSS
+            .iter()
+            .map(|&(x, w)| w * speed(mid + half * x))
+            .sum::<Float>()
+    }
+
+    fn integrate(&self, a: Float, b: Float, whole: Float, tolerance: Float, depth: u32) -> Float {
+        let m = (a + b) / 2.0;
+        let (left, right) = (self.gauss(a, m), self.gauss(m, b));
+        if depth == 0 || (left + right - whole).abs() <= tolerance {
+            return left + right;
+        }
+        self.integrate(a, m, left, tolerance / 2.0, depth - 1)
+            + self.integrate(m, b, right, tolerance / 2.0, depth - 1)
+    }
+
+    pub fn split(&self, t: Float) -> (Segment, Segment) {
+        match *self {
+            Segment::Line(p0, p1) => {
+                let m = lerp(p0, p1, t);
+                (Segment::Line(p0, m), Segment::Line(m, p1))
+            }
+            Segment::Quad(p0, p1, p2) => {
+                let a = lerp(p0, p1, t);
+                let b = lerp(p1, p2, t);
+                let m = lerp(a, b, t);
+                (Segment::Quad(p0, a, m), Segment::Quad(m, b, p2))
+            }
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let a = lerp(p0, p1, t);
+                let b = lerp(p1, p2, t);
+                let c = lerp(p2, p3, t);
+                let d = lerp(a, b, t);
+                let e = lerp(b, c, t);
+                let m = lerp(d, e, t);
+                (Segment::Cubic(p0, a, d, m), Segment::Cubic(m, e, c, p3))
+            }
+        }
+    }
+
+    pub fn reverse(&self) -> Segment {
+        match *self {
+            Segment::Line(p0, p1) => Segment::Line(p1, p0),
+            Segment::Quad(p0, p1, p2) => Segment::Quad(p2, p1, p0),
+            Segment::Cubic(p0, p1, p2, p3) => Segment::Cubic(p3, p2, p1, p0),
+        }
+    }
+
+    // the same curve as a cubic, lines get their control points at the thirds
+    pub fn to_cubic(&self) -> Segment {
+        match *self {
+            Segment::Line(p0, p1) => {
+                Segment::Cubic(p0, lerp(p0, p1, 1.0 / 3.0), lerp(p0, p1, 2.0 / 3.0), p1)
+            }
+            Segment::Quad(p0, p1, p2) => {
+                Segment::Cubic(p0, lerp(p0, p1, 2.0 / 3.0), lerp(p2, p1, 2.0 / 3.0), p2)
+            }
+            cubic => cubic,
+        }
+    }
+
+    pub fn to_command(&self) -> Command {
+        match *self {
+            Segment::Line(_, (x, y)) => Command::LineTo { x, y },
+            Segment::Quad(_, (x1, y1), (x, y)) => Command::QuadraticBezierCurveTo { x1, y1, x, y },
+            Segment::Cubic(_, (x1, y1), (x2, y2), (x, y)) => Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            },
+        }
+    }
+
+    // the segment drawn by `cmd` when the pen is at `from`, `None` for MoveTo/ClosePath and for
+    // arcs, which take more than one (see `pieces`)
+    pub fn from_command(from: (Float, Float), cmd: &Command) -> Option<Segment> {
+        Some(match *cmd {
+            Command::MoveTo { .. } | Command::ClosePath | Command::EllipticalArc { .. } => {
+                return None
+            }
+            Command::LineTo { x, y } => Segment::Line(from, (x, y)),
+            Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => Segment::Cubic(from, (x1, y1), (x2, y2), (x, y)),
+            Command::SmoothCurveTo {
+                cx,
+                cy,
+                x2,
+                y2,
+                x,
+                y,
+            } => Segment::Cubic(from, (cx, cy), (x2, y2), (x, y)),
+            Command::QuadraticBezierCurveTo { x1, y1, x, y } => {
+                Segment::Quad(from, (x1, y1), (x, y))
+            }
+            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
+                Segment::Quad(from, (cx, cy), (x, y))
+            }
+        })
+    }
+
+    // every segment drawn by `cmd` when the pen is at `from`: the one from `from_command`, or up
+    // to four cubics for an elliptical arc
+    pub fn pieces(from: (Float, Float), cmd: &Command) -> impl Iterator<Item = Segment> {
+        pieces_of(from, cmd).into_iter().flatten()
+    }
+}
+
+fn pieces_of(from: (Float, Float), cmd: &Command) -> [Option<Segment>; 4] {
+    match cmd {
+        Command::EllipticalArc { .. } => arc_cubics(from, cmd),
+        cmd => [Segment::from_command(from, cmd), None, None, None],
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Subpath<'a> {
+    pub start: (Float, Float),
+    pub commands: &'a [Command],
+    pub closed: bool,
+}
+
+impl<'a> Subpath<'a> {
+    // segments in drawing order, a trailing ClosePath yields the closing line (if it has length)
+    pub fn segments(&self) -> impl Iterator<Item = Segment> + 'a {
+        let start = self.start;
+        let mut current = start;
+
+        self.commands.iter().flat_map(move |cmd| {
+            let pieces = match cmd {
+                Command::MoveTo { x, y } => {
+                    current = (*x, *y);
+                    [None; 4]
+                }
+                Command::ClosePath => {
+                    let from = current;
+                    current = start;
+                    [
+                        (from != start).then_some(Segment::Line(from, start)),
+                        None,
+                        None,
+                        None,
+                    ]
+                }
+                cmd => {
+                    let pieces = pieces_of(current, cmd);
+                    if let Some(last) = pieces.iter().rev().flatten().next() {
+                        current = last.to();
+                    }
+                    pieces
+                }
+            };
+            pieces.into_iter().flatten()
+        })
+    }
+
+    pub fn end(&self) -> (Float, Float) {
+        if self.closed {
+            return self.start;
+        }
+
+        self.segments().last().map_or(self.start, |s| s.to())
+    }
+}
+
+// split a command list into subpaths, a new one starts at every MoveTo and after every ClosePath
+pub fn subpaths(commands: &[Command]) -> Vec<Subpath<'_>> {
+    let mut out = Vec::new();
+
+    let mut begin = 0;
+    let mut start = (0.0, 0.0);
+
+    for (i, cmd) in commands.iter().enumerate() {
+        match *cmd {
+            Command::MoveTo { x, y } => {
+                if i > begin {
+                    out.push(Subpath {
+                        start,
+                        commands: &commands[begin..i],
+                        closed: false,
+                    });
+                }
+
+                begin = i;
+                start = (x, y);
+            }
+            Command::ClosePath => {
+                out.push(Subpath {
+                    start,
+                    commands: &commands[begin..=i],
+                    closed: true,
+                });
+
+                begin = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if begin < commands.len() {
+        out.push(Subpath {
+            start,
+            commands: &commands[begin..],
+            closed: false,
+        });
+    }
+
+    out
+}
+
+// append a subpath drawn by `segments`, starting with a MoveTo to the first segment's start
+pub(crate) fn push_segments(segments: &[Segment], closed: bool, commands: &mut Vec<Command>) {
+    let Some(first) = segments.first() else {
+        return;
+    };
+
+    let (x, y) = first.from();
+    commands.push(Command::MoveTo { x, y });
+    commands.extend(segments.iter().map(|seg| seg.to_command()));
+
+    if closed {
+        commands.push(Command::ClosePath);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_at_length_splits_off_that_length() {
+        let segments = [
+            Segment::Line((0.0, 0.0), (30.0, 40.0)),
+            Segment::Quad((0.0, 0.0), (50.0, 100.0), (100.0, 0.0)),
+            Segment::Cubic((0.0, 0.0), (0.0, 80.0), (100.0, 80.0), (100.0, 0.0)),
+            // a cusp halfway, where the speed drops to zero
+            Segment::Cubic((0.0, 0.0), (10.0, 10.0), (0.0, 10.0), (10.0, 0.0)),
+            // nearly all of the length at one end
+            Segment::Cubic((0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (100.0, 5.0)),
+        ];
+
+        for segment in segments {
+            let total = segment.length();
+            let mut last = 0.0;
+            for i in 1..20 {
+                let length = total * i as Float / 20.0;
+                let t = segment.t_at_length(length);
+                assert!(t > last && t < 1.0, "{segment:?} at {length}: {t}");
+                let error = segment.split(t).0.length() - length;
+                assert!(
+                    error.abs() <= total * 1e-4,
+                    "{segment:?} at {length}: {error}"
+                );
+                last = t;
+            }
+        }
+    }
+
+    #[test]
+    fn t_at_length_clamps_to_the_ends() {
+        let segment = Segment::Quad((0.0, 0.0), (50.0, 100.0), (100.0, 0.0));
+        assert_eq!(segment.t_at_length(-1.0), 0.0);
+        assert_eq!(segment.t_at_length(0.0), 0.0);
+        assert_eq!(segment.t_at_length(Float::NAN), 0.0);
+        assert_eq!(segment.t_at_length(segment.length() + 1.0), 1.0);
+        assert_eq!(segment.t_at_length(Float::INFINITY), 1.0);
+
+        // a point has no length to walk
+        let point = Segment::Cubic((3.0, 3.0), (3.0, 3.0), (3.0, 3.0), (3.0, 3.0));
+        assert_eq!(point.t_at_length(1.0), 0.0);
+        assert_eq!(
+            Segment::Line((0.0, 0.0), (10.0, 0.0)).t_at_length(2.5),
+            0.25
+        );
+    }
+}
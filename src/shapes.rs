@@ -0,0 +1,114 @@
+use crate::{path::Path, Command, Float, Point};
+
+fn arc(rx: Float, ry: Float, x: Float, y: Float) -> Command {
+    Command::EllipticalArc {
+        rx,
+        ry,
+        x_axis_rotation: 0.0,
+        large_arc: false,
+        sweep: true,
+        x,
+        y,
+    }
+}
+
+// the svg basic shapes as paths, drawn the way the svg spec lays them out so they match what the
+// `svg` module reads from <rect>, <circle> and friends. shapes that draw nothing (no size, no
+// radius, no points) give an empty path
+impl Path {
+    // rounded when both radii are above 0, each capped at half its side
+    pub fn rect(x: Float, y: Float, w: Float, h: Float, rx: Float, ry: Float) -> Path {
+        if !(w > 0.0 && h > 0.0) {
+            return Path::new(Vec::new());
+        }
+
+        let (rx, ry) = (rx.clamp(0.0, w / 2.0), ry.clamp(0.0, h / 2.0));
+        let (right, bottom) = (x + w, y + h);
+
+        let commands = if rx == 0.0 || ry == 0.0 {
+            vec![
+                Command::MoveTo { x, y },
+                Command::LineTo { x: right, y },
+                Command::LineTo {
+                    x: right,
+                    y: bottom,
+                },
+                Command::LineTo { x, y: bottom },
+                Command::ClosePath,
+            ]
+        } else {
+            vec![
+                Command::MoveTo { x: x + rx, y },
+                Command::LineTo { x: right - rx, y },
+                arc(rx, ry, right, y + ry),
+                Command::LineTo {
+                    x: right,
+                    y: bottom - ry,
+                },
+                arc(rx, ry, right - rx, bottom),
+                Command::LineTo {
+                    x: x + rx,
+                    y: bottom,
+                },
+                arc(rx, ry, x, bottom - ry),
+                Command::LineTo { x, y: y + ry },
+                arc(rx, ry, x + rx, y),
+                Command::ClosePath,
+            ]
+        };
+
+        Path::new(commands)
+    }
+
+    pub fn circle(cx: Float, cy: Float, r: Float) -> Path {
+        Path::ellipse(cx, cy, r, r)
+    }
+
+    // two half arcs starting from the rightmost point
+    pub fn ellipse(cx: Float, cy: Float, rx: Float, ry: Float) -> Path {
+        if !(rx > 0.0 && ry > 0.0) {
+            return Path::new(Vec::new());
+        }
+
+        Path::new(vec![
+            Command::MoveTo { x: cx + rx, y: cy },
+            arc(rx, ry, cx - rx, cy),
+            arc(rx, ry, cx + rx, cy),
+            Command::ClosePath,
+        ])
+    }
+
+    pub fn line(x1: Float, y1: Float, x2: Float, y2: Float) -> Path {
+        Path::new(vec![
+            Command::MoveTo { x: x1, y: y1 },
+            Command::LineTo { x: x2, y: y2 },
+        ])
+    }
+
+    pub fn polyline(points: &[Point]) -> Path {
+        Path::new(poly(points))
+    }
+
+    // a polyline closed back to its first point
+    pub fn polygon(points: &[Point]) -> Path {
+        let mut commands = poly(points);
+        if !commands.is_empty() {
+            commands.push(Command::ClosePath);
+        }
+        Path::new(commands)
+    }
+}
+
+fn poly(points: &[Point]) -> Vec<Command> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, &(x, y))| {
+            if i == 0 {
+                Command::MoveTo { x, y }
+            } else {
+                Command::LineTo { x, y }
+            }
+        })
+        .collect()
+}
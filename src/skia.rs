@@ -0,0 +1,52 @@
+use tiny_skia::PathBuilder;
+
+use crate::{path::Path, segment::Segment, to_f32, Command};
+
+impl Path {
+    // every command fed to `builder`, arcs as up to four cubics since tiny-skia has none. a
+    // command following a close starts from the last move, as skia does on its own
+    pub fn build_skia(&self, builder: &mut PathBuilder) {
+        let mut start = (0.0, 0.0);
+        let mut pen = start;
+        for cmd in self.commands.iter() {
+            match *cmd {
+                Command::MoveTo { x, y } => {
+                    builder.move_to(to_f32(x), to_f32(y));
+                    start = (x, y);
+                }
+                Command::ClosePath => builder.close(),
+                ref cmd => {
+                    for seg in Segment::pieces(pen, cmd) {
+                        match seg {
+                            Segment::Line(_, (x, y)) => builder.line_to(to_f32(x), to_f32(y)),
+                            Segment::Quad(_, (x1, y1), (x, y)) => {
+                                builder.quad_to(to_f32(x1), to_f32(y1), to_f32(x), to_f32(y))
+                            }
+                            Segment::Cubic(_, (x1, y1), (x2, y2), (x, y)) => builder.cubic_to(
+                                to_f32(x1),
+                                to_f32(y1),
+                                to_f32(x2),
+                                to_f32(y2),
+                                to_f32(x),
+                                to_f32(y),
+                            ),
+                        }
+                    }
+                }
+            }
+
+            pen = match *cmd {
+                Command::ClosePath => start,
+                ref cmd => cmd.points().last().unwrap_or(pen),
+            };
+        }
+    }
+
+    // the path as a tiny-skia path, ready for `Pixmap::fill_path`. `None` when it draws nothing
+    // tiny-skia accepts (no commands, or coordinates that are not finite)
+    pub fn to_skia(&self) -> Option<tiny_skia::Path> {
+        let mut builder = PathBuilder::new();
+        self.build_skia(&mut builder);
+        builder.finish()
+    }
+}
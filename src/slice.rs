@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use crate::{intersect::line_roots, path::Path, segment::Segment, Command, Float, Point};
+
+// a run of a contour that stays on one side of the cut, from crossing `start` to crossing `end`
+struct Chain {
+    left: bool,
+    start: usize,
+    end: usize,
+    segments: Vec<Segment>,
+}
+
+// which side of the line through `p0` and `p1` the point is on: 1 for the left (on screen, looking
+// from p0 towards p1 in svg's y-down space), -1 for the right and 0 on the line
+fn side(p: Point, p0: Point, p1: Point) -> i32 {
+    let cross = (p1.0 - p0.0) * (p.1 - p0.1) - (p1.1 - p0.1) * (p.0 - p0.0);
+    if cross < 0.0 {
+        1
+    } else if cross > 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+// `seg` cut at every point it meets the line
+pub(crate) fn split_at_line(seg: &Segment, p0: Point, p1: Point, out: &mut Vec<Segment>) {
+    let mut ts = line_roots(seg, p0, p1);
+    ts.sort_by(Float::total_cmp);
+
+    let mut rest = *seg;
+    let mut done = 0.0;
+    for t in ts {
+        // roots at the very ends would leave empty pieces
+        if t <= done + 1e-6 || t >= 1.0 - 1e-6 {
+            continue;
+        }
+
+        let (head, tail) = rest.split((t - done) / (1.0 - done));
+        out.push(head);
+        rest = tail;
+        done = t;
+    }
+    out.push(rest);
+}
+
+impl Path {
+    // cut along the infinite line through `p0` and `p1` into the part left of it and the part right
+    // of it (on screen, looking from p0 towards p1), both closed again along the cut. open
+    // subpaths are treated as closed. holes need to wind against their outline, as they do for the
+    // nonzero fill rule, to be put back together correctly
+    pub fn slice(&self, p0: Point, p1: Point) -> (Path, Path) {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+
+        let mut chains = Vec::new();
+        // where each crossing lies along the line
+        let mut crossings: Vec<(usize, Float)> = Vec::new();
+        let d = (p1.0 - p0.0, p1.1 - p0.1);
+        let along = |p: Point| (p.0 - p0.0) * d.0 + (p.1 - p0.1) * d.1;
+
+        for sp in self.subpaths() {
+            let mut segments: Vec<Segment> = sp.segments().collect();
+            let Some(last) = segments.last() else {
+                continue;
+            };
+            if last.to() != sp.start {
+                segments.push(Segment::Line(last.to(), sp.start));
+            }
+
+            let mut pieces = Vec::new();
+            for seg in &segments {
+                split_at_line(seg, p0, p1, &mut pieces);
+            }
+
+            // pieces running along the line belong to whatever came before them
+            let mut sides: Vec<i32> = pieces.iter().map(|p| side(p.eval(0.5), p0, p1)).collect();
+            let Some(mut previous) = sides.iter().rev().copied().find(|&s| s != 0) else {
+                continue;
+            };
+            for s in sides.iter_mut() {
+                if *s == 0 {
+                    *s = previous;
+                }
+                previous = *s;
+            }
+
+            // start at a change of side, a contour without one stays whole
+            let n = pieces.len();
+            let Some(first) = (0..n).find(|&i| sides[i] != sides[(i + n - 1) % n]) else {
+                let target = if sides[0] > 0 { &mut left } else { &mut right };
+                if !matches!(sp.commands.first(), Some(Command::MoveTo { .. })) {
+                    let (x, y) = sp.start;
+                    target.push(Command::MoveTo { x, y });
+                }
+                target.extend_from_slice(sp.commands);
+                if !sp.closed {
+                    target.push(Command::ClosePath);
+                }
+                continue;
+            };
+
+            let base = crossings.len();
+            let mut count = 0;
+            for k in 0..n {
+                let i = (first + k) % n;
+                if k == 0 || sides[i] != sides[(i + n - 1) % n] {
+                    crossings.push((base + count, along(pieces[i].from())));
+                    chains.push(Chain {
+                        left: sides[i] > 0,
+                        start: base + count,
+                        end: 0,
+                        segments: Vec::new(),
+                    });
+                    count += 1;
+                }
+                chains.last_mut().expect("chain").segments.push(pieces[i]);
+            }
+
+            // every chain ends where the next one starts, the last one where the first started
+            let chains_here = chains.len() - count..chains.len();
+            for (k, c) in chains_here.clone().enumerate() {
+                chains[c].end = base + (k + 1) % count;
+            }
+        }
+
+        // inside stretches of the cut run between consecutive crossings, pair them up
+        crossings.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let mut partner = HashMap::new();
+        for pair in crossings.chunks_exact(2) {
+            partner.insert(pair[0].0, pair[1].0);
+            partner.insert(pair[1].0, pair[0].0);
+        }
+
+        for is_left in [true, false] {
+            let target = if is_left { &mut left } else { &mut right };
+            let by_start: HashMap<usize, usize> = chains
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.left == is_left)
+                .map(|(i, c)| (c.start, i))
+                .collect();
+            let mut used = vec![false; chains.len()];
+
+            for (i, chain) in chains.iter().enumerate() {
+                if chain.left != is_left || used[i] {
+                    continue;
+                }
+
+                let (x, y) = chain.segments[0].from();
+                target.push(Command::MoveTo { x, y });
+
+                let mut current = i;
+                loop {
+                    used[current] = true;
+                    target.extend(chains[current].segments.iter().map(|s| s.to_command()));
+
+                    // walk the cut to the chain that continues the outline
+                    let Some(next) = partner
+                        .get(&chains[current].end)
+                        .and_then(|p| by_start.get(p))
+                        .copied()
+                    else {
+                        break;
+                    };
+                    if used[next] {
+                        break;
+                    }
+
+                    let (x, y) = chains[next].segments[0].from();
+                    target.push(Command::LineTo { x, y });
+                    current = next;
+                }
+
+                target.push(Command::ClosePath);
+            }
+        }
+
+        (Path::new(left), Path::new(right))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    flatten::{flatten_subpaths, simplify_polyline, FlattenMethod},
+    path::Path,
+    segment::{push_segments, Segment},
+    Float, Point,
+};
+
+// points where the outline turns more than this (in degrees) are corners and stay put
+pub(crate) const CORNER_ANGLE: Float = 60.0;
+
+// corners are measured between points this many steps away, so jitter does not read as corners
+const CORNER_REACH: usize = 2;
+
+pub(crate) fn turn(a: Point, b: Point, c: Point) -> Float {
+    let (u, v) = ((b.0 - a.0, b.1 - a.1), (c.0 - b.0, c.1 - b.1));
+    let len = (u.0.hypot(u.1) * v.0.hypot(v.1)).max(Float::MIN_POSITIVE);
+    ((u.0 * v.0 + u.1 * v.1) / len)
+        .clamp(-1.0, 1.0)
+        .acos()
+        .to_degrees()
+}
+
+// catmull-rom cubics through `points`, tangents at corners only look at the side they belong to
+fn refit(points: &[Point], corner: &[bool], closed: bool) -> Vec<Segment> {
+    let n = points.len();
+    let at = |i: isize| -> Option<usize> {
+        if closed {
+            Some(i.rem_euclid(n as isize) as usize)
+        } else {
+            (0..n as isize).contains(&i).then_some(i as usize)
+        }
+    };
+
+    let spans = if closed { n } else { n - 1 };
+    (0..spans as isize)
+        .map(|i| {
+            let (b, c) = (at(i).unwrap_or(0), at(i + 1).unwrap_or(0));
+            let a = at(i - 1).filter(|_| !corner[b]).unwrap_or(b);
+            let d = at(i + 2).filter(|_| !corner[c]).unwrap_or(c);
+            let (pa, pb, pc, pd) = (points[a], points[b], points[c], points[d]);
+
+            Segment::Cubic(
+                pb,
+                (pb.0 + (pc.0 - pa.0) / 6.0, pb.1 + (pc.1 - pa.1) / 6.0),
+                (pc.0 - (pd.0 - pb.0) / 6.0, pc.1 - (pd.1 - pb.1) / 6.0),
+                pc,
+            )
+        })
+        .collect()
+}
+
+impl Path {
+    // evens out wobbles in traced or hand drawn outlines: the flattened points are pulled towards
+    // their neighbours `iterations` times by `strength` (0 to 1), then the result is thinned out
+    // and drawn again with smooth curves. corners and the ends of open subpaths stay where they
+    // are
+    pub fn smooth(&self, iterations: usize, strength: Float) -> Path {
+        let size = self.bb.0.max(self.bb.1);
+        let tolerance = (size * 1e-3).max(1e-3);
+        let strength = strength.clamp(0.0, 1.0);
+        let mut commands = Vec::new();
+
+        for (mut points, closed) in
+            flatten_subpaths(&self.commands, tolerance * 0.25, FlattenMethod::Adaptive)
+        {
+            if closed && points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+            let n = points.len();
+            if n < 2 {
+                continue;
+            }
+
+            let neighbours = |i: usize| {
+                if closed {
+                    Some(((i + n - 1) % n, (i + 1) % n))
+                } else {
+                    (i > 0 && i + 1 < n).then(|| (i - 1, i + 1))
+                }
+            };
+            let corner: Vec<bool> = (0..n)
+                .map(|i| {
+                    let (a, c) = if closed {
+                        (
+                            (i + n * CORNER_REACH - CORNER_REACH) % n,
+                            (i + CORNER_REACH) % n,
+                        )
+                    } else if i == 0 || i + 1 == n {
+                        return true;
+                    } else {
+                        (
+                            i.saturating_sub(CORNER_REACH),
+                            (i + CORNER_REACH).min(n - 1),
+                        )
+                    };
+                    turn(points[a], points[i], points[c]) > CORNER_ANGLE
+                })
+                .collect();
+
+            for _ in 0..iterations {
+                let before = points.clone();
+                for (i, p) in points.iter_mut().enumerate() {
+                    if let (false, Some((a, c))) = (corner[i], neighbours(i)) {
+                        let mid = (
+                            (before[a].0 + before[c].0) / 2.0,
+                            (before[a].1 + before[c].1) / 2.0,
+                        );
+                        p.0 += (mid.0 - p.0) * strength;
+                        p.1 += (mid.1 - p.1) * strength;
+                    }
+                }
+            }
+
+            // thin out between corners so the curves have room, corners are always kept
+            let mut kept = Vec::new();
+            let mut kept_corner = Vec::new();
+            let mut from = 0;
+            let mut ends: Vec<usize> = (1..n).filter(|&i| corner[i]).collect();
+            if closed {
+                ends.push(n);
+            }
+            for to in ends {
+                let run: Vec<Point> = (from..=to).map(|i| points[i % n]).collect();
+                let simple = simplify_polyline(&run, tolerance);
+                let count = simple.len() - 1;
+                kept.extend_from_slice(&simple[..count]);
+                kept_corner.push(corner[from]);
+                kept_corner.resize(kept.len(), false);
+                from = to;
+            }
+            if !closed {
+                kept.push(points[n - 1]);
+                kept_corner.push(true);
+            }
+
+            push_segments(&refit(&kept, &kept_corner, closed), closed, &mut commands);
+        }
+
+        Path::new(commands)
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Write;
+
+use crate::{path::Path, writer::write_to};
+
+// `id` with the characters that would end or break an attribute value written as references
+fn escape(out: &mut String, id: &str) {
+    for c in id.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+// an svg sprite with one <symbol> per path, to be drawn with <use href="#name"/>. each symbol's
+// viewBox is the tight box around its path, so icons scavenged from different documents come out
+// the same size whatever they sat in. paths without commands get an empty viewBox and draw nothing
+pub fn build(named_paths: &[(&str, &Path)]) -> String {
+    let mut out =
+        String::from("<svg xmlns=\"http://www.w3.org/2000/svg\" style=\"display:none\">\n");
+
+    for (name, path) in named_paths {
+        let (x, y, w, h) = path.tight_bounds().map_or((0.0, 0.0, 0.0, 0.0), |r| {
+            (r.min_x, r.min_y, r.width(), r.height())
+        });
+
+        out.push_str("  <symbol id=\"");
+        escape(&mut out, name);
+        let _ = write!(out, "\" viewBox=\"{x} {y} {w} {h}\"><path d=\"");
+        write_to(&mut out, path.commands());
+        out.push_str("\"/></symbol>\n");
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
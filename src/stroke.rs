@@ -0,0 +1,405 @@
+use crate::consts::PI;
+
+use crate::{
+    flatten::{flatten_subpaths, FlattenMethod},
+    offset::rings_to_commands,
+    path::Path,
+    segment::{dist, subpaths},
+    Float, Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    // butt pushed out by half the width
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    // bevelled where the miter would reach past `miter_limit`
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+// what `Path::stroke` draws, with svg's defaults
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeStyle {
+    pub width: Float,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    // how far a miter may reach from its corner, in stroke widths
+    pub miter_limit: Float,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+        }
+    }
+}
+
+fn direction(a: Point, b: Point) -> Point {
+    let len = dist(a, b);
+    ((b.0 - a.0) / len, (b.1 - a.1) / len)
+}
+
+// `p` moved `d` along the normal of `dir`, a quarter turn counterclockwise of it with y up
+fn side(p: Point, dir: Point, d: Float) -> Point {
+    (p.0 - dir.1 * d, p.1 + dir.0 * d)
+}
+
+// points on the circle of radius `r` around `center` from angle `from` turning `sweep` radians,
+// both ends left out, close enough to stay within `tolerance`
+fn arc(out: &mut Vec<Point>, center: Point, r: Float, from: Float, sweep: Float, tolerance: Float) {
+    let step = 2.0 * (1.0 - (tolerance / r).min(1.0)).acos();
+    let steps = (sweep.abs() / step.max(1e-3)).ceil().min(256.0) as usize;
+    for i in 1..steps {
+        let a = from + sweep * i as Float / steps as Float;
+        out.push((center.0 + r * a.cos(), center.1 + r * a.sin()));
+    }
+}
+
+struct Stroker {
+    style: StrokeStyle,
+    half: Float,
+    tolerance: Float,
+}
+
+impl Stroker {
+    // the corner at `p` from `d0` to `d1` on the side `h` away
+    fn join(&self, out: &mut Vec<Point>, p: Point, d0: Point, d1: Point, h: Float) {
+        let (a, b) = (side(p, d0, h), side(p, d1, h));
+        let cross = d0.0 * d1.1 - d0.1 * d1.0;
+        let dot = d0.0 * d1.0 + d0.1 * d1.1;
+
+        // straight on
+        if cross.abs() < 1e-6 && dot > 0.0 {
+            out.push(b);
+            return;
+        }
+        out.push(a);
+        // the inside of the turn goes through the corner, the overlap this leaves fills anyway
+        if cross * h > 0.0 {
+            out.push(p);
+            out.push(b);
+            return;
+        }
+
+        match self.style.join {
+            LineJoin::Miter => {
+                // the miter is 1 / cos(half the turn) half widths from the corner
+                let cos = ((1.0 + dot) / 2.0).sqrt();
+                if cos * self.style.miter_limit >= 1.0 {
+                    let m = (d0.0 + d1.0, d0.1 + d1.1);
+                    let len = (m.0 * m.0 + m.1 * m.1).sqrt();
+                    let m = (-m.1 / len, m.0 / len);
+                    let reach = h / cos;
+                    out.push((p.0 + m.0 * reach, p.1 + m.1 * reach));
+                }
+            }
+            LineJoin::Round => {
+                let from = (a.1 - p.1).atan2(a.0 - p.0);
+                let to = (b.1 - p.1).atan2(b.0 - p.0);
+                let mut sweep = to - from;
+                // the short way round, on the outside of the turn
+                if sweep > PI {
+                    sweep -= 2.0 * PI;
+                } else if sweep < -PI {
+                    sweep += 2.0 * PI;
+                }
+                // turning straight back both ways are as short, go round the end it was heading to
+                let mid = from + sweep / 2.0;
+                if mid.cos() * d0.0 + mid.sin() * d0.1 < 0.0 {
+                    sweep -= sweep.signum() * 2.0 * PI;
+                }
+                arc(out, p, self.half, from, sweep, self.tolerance);
+            }
+            LineJoin::Bevel => {}
+        }
+        out.push(b);
+    }
+
+    // from the end of one side at `p`, heading `dir`, round to the other side
+    fn cap(&self, out: &mut Vec<Point>, p: Point, dir: Point) {
+        let h = self.half;
+        match self.style.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let ahead = (p.0 + dir.0 * h, p.1 + dir.1 * h);
+                out.push(side(ahead, dir, h));
+                out.push(side(ahead, dir, -h));
+            }
+            LineCap::Round => {
+                let from = dir.0.atan2(-dir.1);
+                arc(out, p, h, from, -PI, self.tolerance);
+            }
+        }
+        out.push(side(p, dir, -h));
+    }
+
+    // one side of the polyline, `h` along the normals, corners joined
+    fn one_side(&self, out: &mut Vec<Point>, points: &[Point], closed: bool, h: Float) {
+        let n = points.len();
+        let segments = if closed { n } else { n - 1 };
+        let dirs: Vec<Point> = (0..segments)
+            .map(|i| direction(points[i], points[(i + 1) % n]))
+            .collect();
+
+        if closed {
+            self.join(out, points[0], dirs[n - 1], dirs[0], h);
+        } else {
+            out.push(side(points[0], dirs[0], h));
+        }
+        for (d, &p) in dirs.windows(2).zip(&points[1..]) {
+            self.join(out, p, d[0], d[1], h);
+        }
+        if !closed {
+            out.push(side(points[n - 1], dirs[n - 2], h));
+        }
+    }
+
+    // the rings a polyline strokes to
+    fn polyline(&self, points: &[Point], closed: bool, rings: &mut Vec<Vec<Point>>) {
+        let h = self.half;
+        if closed {
+            let mut left = Vec::new();
+            self.one_side(&mut left, points, true, h);
+            let reversed: Vec<Point> = points.iter().rev().copied().collect();
+            let mut right = Vec::new();
+            self.one_side(&mut right, &reversed, true, h);
+            rings.push(left);
+            rings.push(right);
+            return;
+        }
+
+        let n = points.len();
+        let mut ring = Vec::new();
+        self.one_side(&mut ring, points, false, h);
+        self.cap(
+            &mut ring,
+            points[n - 1],
+            direction(points[n - 2], points[n - 1]),
+        );
+        let reversed: Vec<Point> = points.iter().rev().copied().collect();
+        // the way back is the other side, the first point of it was put down by the cap
+        let mut back = Vec::new();
+        self.one_side(&mut back, &reversed, false, h);
+        ring.extend(&back[1..]);
+        self.cap(&mut ring, points[0], direction(points[1], points[0]));
+        ring.pop();
+        rings.push(ring);
+    }
+
+    // a subpath of no length: round and square caps still draw a dot, facing along x
+    fn dot(&self, p: Point, rings: &mut Vec<Vec<Point>>) {
+        let h = self.half;
+        match self.style.cap {
+            LineCap::Butt => {}
+            LineCap::Square => rings.push(vec![
+                (p.0 - h, p.1 - h),
+                (p.0 + h, p.1 - h),
+                (p.0 + h, p.1 + h),
+                (p.0 - h, p.1 + h),
+            ]),
+            LineCap::Round => {
+                let mut ring = vec![(p.0 + h, p.1)];
+                arc(&mut ring, p, h, 0.0, 2.0 * PI, self.tolerance);
+                rings.push(ring);
+            }
+        }
+    }
+}
+
+impl Path {
+    // the outline of the stroke `style` draws along the path, as a path to fill with the non-zero
+    // rule. curves are flattened first, dashes are left to `dash`. the outline is not cleaned up:
+    // the inside of every turn loops back through its corner, and a closed subpath gives an outer
+    // ring and an inner one wound the other way with those loops in it. under non-zero the loops
+    // add up to the stroke, under even-odd they punch holes at the corners
+    pub fn stroke(&self, style: &StrokeStyle) -> Path {
+        let mut commands = Vec::new();
+        if style.width.is_nan() || style.width <= 0.0 {
+            return Path::new(commands);
+        }
+
+        let half = style.width / 2.0;
+        let tolerance = ((self.bb.0.max(self.bb.1) * 1e-3).max(1e-3)).min(half * 0.1);
+        let stroker = Stroker {
+            style: *style,
+            half,
+            tolerance,
+        };
+
+        let mut rings = Vec::new();
+        let polylines = flatten_subpaths(&self.commands, tolerance, FlattenMethod::Adaptive);
+        for ((mut points, closed), sp) in polylines.into_iter().zip(subpaths(&self.commands)) {
+            points.dedup();
+            if closed && points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+
+            match points.len() {
+                // a lone move draws nothing, a move followed by something that goes nowhere
+                // draws its caps
+                1 if sp.commands.len() > 1 => stroker.dot(points[0], &mut rings),
+                0 | 1 => {}
+                _ => stroker.polyline(&points, closed, &mut rings),
+            }
+        }
+
+        rings_to_commands(&rings, &mut commands);
+        Path::new(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{offset::segment_distance, parse_path_str, FillRule};
+
+    fn stroke(d: &str, width: Float, cap: LineCap, join: LineJoin, miter_limit: Float) -> Path {
+        let style = StrokeStyle {
+            width,
+            cap,
+            join,
+            miter_limit,
+        };
+        Path::new(parse_path_str(d).unwrap()).stroke(&style)
+    }
+
+    // (x, y, inside) for each probe
+    fn assert_hits(path: &Path, probes: &[(Float, Float, bool)], what: &str) {
+        for &(x, y, inside) in probes {
+            assert_eq!(
+                path.contains(x, y, FillRule::NonZero),
+                inside,
+                "{what} at ({x}, {y})"
+            );
+        }
+    }
+
+    #[test]
+    fn caps() {
+        // past the end on the axis, and off it toward where a square cap has its corner
+        for (cap, ahead, corner) in [
+            (LineCap::Butt, false, false),
+            (LineCap::Square, true, true),
+            (LineCap::Round, true, false),
+        ] {
+            let path = stroke("M0 0 L10 0", 2.0, cap, LineJoin::Miter, 4.0);
+            let probes = [
+                (5.0, 0.9, true),
+                (5.0, -1.1, false),
+                (10.5, 0.0, ahead),
+                (10.8, 0.8, corner),
+                (-0.5, 0.0, ahead),
+                (-0.8, -0.8, corner),
+                (11.2, 0.0, false),
+            ];
+            assert_hits(&path, &probes, &format!("{cap:?}"));
+        }
+
+        // a line that goes nowhere draws a dot with round and square caps
+        for (cap, corner, center) in [
+            (LineCap::Butt, false, false),
+            (LineCap::Square, true, true),
+            (LineCap::Round, false, true),
+        ] {
+            let path = stroke("M5 5 L5 5", 2.0, cap, LineJoin::Miter, 4.0);
+            assert_hits(
+                &path,
+                &[(5.0, 5.0, center), (5.9, 5.9, corner)],
+                &format!("{cap:?} dot"),
+            );
+        }
+    }
+
+    #[test]
+    fn joins() {
+        // a right angle turning toward +y, the outside of the corner is toward (11, -1)
+        let corner = "M0 0 L10 0 L10 10";
+        for (join, miter_limit, tip, middle) in [
+            (LineJoin::Miter, 4.0, true, true),
+            // a right angle reaches sqrt(2) widths, past a limit of 1 it is bevelled
+            (LineJoin::Miter, 1.0, false, false),
+            (LineJoin::Round, 4.0, false, true),
+            (LineJoin::Bevel, 4.0, false, false),
+        ] {
+            let path = stroke(corner, 2.0, LineCap::Butt, join, miter_limit);
+            let probes = [
+                (10.9, -0.9, tip),
+                (10.55, -0.55, middle),
+                (10.3, -0.3, true),
+                // the inside of the turn
+                (9.5, 0.5, true),
+                (8.5, 1.5, false),
+                (11.1, -1.1, false),
+            ];
+            assert_hits(&path, &probes, &format!("{join:?} {miter_limit}"));
+        }
+    }
+
+    // with round caps and joins the stroke is every point within half the width of the line,
+    // which can be checked anywhere
+    #[test]
+    fn round_strokes_cover_half_the_width() {
+        let sources = [
+            "M0 0 L20 0 L2 3 L15 15",
+            "M0 0 L10 0 L10 10 L0 10 Z",
+            "M0 0 L20 5 L0 10 Z",
+            "M0 0 C 20 0 -10 20 10 20",
+            // turning straight back, open and closed
+            "M0 0 L5 0 L1 0 M0 10 L10 10 Z",
+        ];
+        let half = 1.5;
+
+        for source in sources {
+            let original = Path::new(parse_path_str(source).unwrap());
+            let lines: Vec<_> = original
+                .flatten_with(0.001, FlattenMethod::Adaptive)
+                .into_iter()
+                .flat_map(|line| {
+                    let closing = line
+                        .closed
+                        .then(|| (line.points[line.points.len() - 1], line.points[0]));
+                    let pairs: Vec<_> = line.points.windows(2).map(|w| (w[0], w[1])).collect();
+                    pairs.into_iter().chain(closing)
+                })
+                .collect();
+            let path = original.stroke(&StrokeStyle {
+                width: half * 2.0,
+                cap: LineCap::Round,
+                join: LineJoin::Round,
+                miter_limit: 4.0,
+            });
+
+            for i in -10..=60 {
+                for j in -10..=60 {
+                    let p = (i as Float * 0.45, j as Float * 0.45);
+                    let d = lines
+                        .iter()
+                        .map(|&(a, b)| segment_distance(p, a, b))
+                        .fold(Float::INFINITY, Float::min);
+                    // leave out what sits on the outline, within the flattening
+                    if (d - half).abs() < 0.1 {
+                        continue;
+                    }
+                    assert_eq!(
+                        path.contains(p.0, p.1, FillRule::NonZero),
+                        d < half,
+                        "{source} at {p:?}, {d} from the line"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,631 @@
+mod select;
+mod shapes;
+mod stream;
+mod style;
+mod tree;
+mod viewport;
+mod xml;
+
+use crate::{
+    path::Path, transform::Transform, FillRule, Float, ParseError, ParsePath, Parser, Point, Region,
+};
+pub use select::Selector;
+use shapes::{is_shape, shape_data};
+use std::collections::HashMap;
+pub use stream::extract_streaming;
+pub use style::Style;
+use tree::Element;
+use viewport::viewport;
+pub use xml::XmlError;
+use xml::{Event, Reader};
+
+// containers whose content is only drawn when something refers to it
+const REFERENCED_ONLY: [&str; 6] = ["defs", "symbol", "clipPath", "mask", "marker", "pattern"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgError {
+    Xml(XmlError),
+    // the `d` of a <path> is not valid path data
+    Path(ParseError),
+    // a selector that could not be read, at this byte offset into it
+    Selector { offset: usize },
+    // reading a streamed document failed
+    Io(std::io::ErrorKind),
+    // gzip data (.svgz), which needs the `flate2` feature
+    Compressed,
+}
+
+// element name without a namespace prefix (`svg:path` -> `path`)
+pub(crate) fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+// gzip streams start with these two bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+// the text of an .svg or .svgz file, gzip compressed data is inflated first
+pub fn decode(bytes: Vec<u8>) -> Result<String, SvgError> {
+    #[cfg(feature = "flate2")]
+    let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+        use std::io::Read;
+
+        let mut out = Vec::new();
+        flate2::read::GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut out)
+            .map_err(|e| SvgError::Io(e.kind()))?;
+        out
+    } else {
+        bytes
+    };
+    #[cfg(not(feature = "flate2"))]
+    if bytes.starts_with(&GZIP_MAGIC) {
+        return Err(SvgError::Compressed);
+    }
+
+    String::from_utf8(bytes).map_err(|e| {
+        SvgError::Xml(XmlError {
+            offset: e.utf8_error().valid_up_to(),
+        })
+    })
+}
+
+// the `d` attribute of every <path> element, in document order
+pub fn path_data(source: &str) -> Result<Vec<String>, XmlError> {
+    let mut out = Vec::new();
+
+    for event in Reader::new(source) {
+        if let Event::Start {
+            name, attributes, ..
+        } = event?
+        {
+            if local_name(name) != "path" {
+                continue;
+            }
+
+            if let Some((_, d)) = attributes.into_iter().find(|(key, _)| *key == "d") {
+                out.push(d.into_owned());
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// an svg `transform` list such as `translate(10 20) rotate(45)`, `None` when it is malformed (svg
+// then ignores the attribute)
+pub fn parse_transform(source: &str) -> Option<Transform> {
+    let mut out = Transform::identity();
+    let mut rest = source.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+
+    while !rest.is_empty() {
+        let open = rest.find('(')?;
+        let close = rest.find(')')?;
+        let name = rest[..open].trim();
+        let args: Vec<Float> = rest[open + 1..close]
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| arg.parse().ok())
+            .collect::<Option<_>>()?;
+
+        let next = match (name, args.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::matrix(a, b, c, d, e, f),
+            ("translate", &[x]) => Transform::translate(x, 0.0),
+            ("translate", &[x, y]) => Transform::translate(x, y),
+            ("scale", &[s]) => Transform::scale(s, s),
+            ("scale", &[x, y]) => Transform::scale(x, y),
+            ("rotate", &[angle]) => Transform::rotate(angle),
+            ("rotate", &[angle, cx, cy]) => Transform::translate(-cx, -cy)
+                .then(&Transform::rotate(angle))
+                .then(&Transform::translate(cx, cy)),
+            ("skewX", &[angle]) => Transform::skew_x(angle),
+            ("skewY", &[angle]) => Transform::skew_y(angle),
+            _ => return None,
+        };
+        // the list reads outermost first, so each entry applies before the ones left of it
+        out = next.then(&out);
+
+        rest = rest[close + 1..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+
+    Some(out)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractOptions {
+    // only paths matching this or inside an element that does, so picking a group picks
+    // everything drawn in it
+    pub selector: Option<Selector>,
+    // apply the transforms of each path and its groups to the path. when off the path keeps its
+    // own coordinates and the transform comes alongside it
+    pub bake_transforms: bool,
+    // cut each path to its clip regions instead of handing them over
+    pub apply_clips: bool,
+    // threads parsing and transforming the shapes once the document is read, 0 for one per
+    // core. the paths come out in document order either way
+    pub threads: usize,
+    // map the viewBox of the document (and of nested <svg> elements) onto its width and height,
+    // so paths come out in the units the document is displayed at rather than its own
+    pub apply_view_box: bool,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        ExtractOptions {
+            selector: None,
+            bake_transforms: true,
+            apply_clips: false,
+            threads: 1,
+            apply_view_box: false,
+        }
+    }
+}
+
+// a drawn path and the transform from its coordinates to the document's, the identity once baked
+pub struct Extracted {
+    pub path: Path,
+    pub transform: Transform,
+    pub style: Style,
+    // in the path's coordinates, the path only shows where it is inside all of them. these come
+    // from `clip-path` and `mask` on the path and its groups; a mask counts as the area its
+    // content covers, whatever its colors or opacity
+    pub clips: Vec<Region>,
+}
+
+// every drawn <path> and basic shape (<rect>, <circle>, <polygon>, ...) as a path, in document
+// order. an <image> comes as the rectangle it fills, tagged with its `href`, and <text> as a rough
+// box around it, tagged with the `text`. <use> elements draw what they point to; content of
+// <defs>, <symbol> and the like only shows up through a <use>, and `display: none` hides an
+// element with everything in it. each path is tagged with `element` and, when the element has
+// them, its `id` and `class`
+pub fn extract(source: &str, options: &ExtractOptions) -> Result<Vec<Extracted>, SvgError> {
+    let root = tree::parse(source).map_err(SvgError::Xml)?;
+    let mut ids = HashMap::new();
+    root.ids(&mut ids);
+
+    let mut walk = Walk {
+        ids,
+        options,
+        using: Vec::new(),
+        shapes: Vec::new(),
+    };
+    let scope = Scope::root();
+    for child in &root.children {
+        walk.element(child, &scope)?;
+    }
+
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    walk.take(threads)
+}
+
+// something to draw: a path in document coordinates, already cut to its clips, and its paint
+pub struct DrawItem {
+    pub path: Path,
+    pub style: Style,
+}
+
+// everything the document draws, in painting order. shapes with neither fill nor stroke are left
+// out, images and text stay as unpainted boxes tagged with their `href` or `text`
+pub fn flatten_document(source: &str) -> Result<Vec<DrawItem>, SvgError> {
+    let options = ExtractOptions {
+        apply_clips: true,
+        ..ExtractOptions::default()
+    };
+
+    Ok(extract(source, &options)?
+        .into_iter()
+        .filter(|e| {
+            e.style.fill.is_some()
+                || e.style.stroke.is_some()
+                || matches!(e.path.tag("element"), Some("image" | "text"))
+        })
+        .map(|e| DrawItem {
+            path: e.path,
+            style: e.style,
+        })
+        .collect())
+}
+
+// `extract` with the transforms baked in and the viewBoxes mapped, so the paths come out in the
+// units the document is displayed at
+pub fn paths(source: &str) -> Result<Vec<Path>, SvgError> {
+    let options = ExtractOptions {
+        apply_view_box: true,
+        ..ExtractOptions::default()
+    };
+    Ok(extract(source, &options)?
+        .into_iter()
+        .map(|e| e.path)
+        .collect())
+}
+
+// the paths of `paths` that match `selectors` (`#icon-home, .glyph`) or sit inside an element that
+// does
+pub fn paths_matching(source: &str, selectors: &str) -> Result<Vec<Path>, SvgError> {
+    let options = ExtractOptions {
+        selector: Some(Selector::parse(selectors)?),
+        apply_view_box: true,
+        ..ExtractOptions::default()
+    };
+    Ok(extract(source, &options)?
+        .into_iter()
+        .map(|e| e.path)
+        .collect())
+}
+
+// the id in a `url(#id)` reference
+fn url_reference(value: &str) -> Option<&str> {
+    let inner = value.trim().strip_prefix("url(")?.strip_suffix(')')?;
+    inner
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .strip_prefix('#')
+}
+
+// what an element takes from the elements around it
+#[derive(Clone)]
+struct Scope {
+    transform: Transform,
+    matched: bool,
+    // in document coordinates
+    clips: Vec<Region>,
+    style: Style,
+    // inside an <svg> element, so another one is placed at its `x` and `y`
+    nested: bool,
+}
+
+impl Scope {
+    fn root() -> Scope {
+        Scope {
+            transform: Transform::identity(),
+            matched: false,
+            clips: Vec::new(),
+            style: Style::default(),
+            nested: false,
+        }
+    }
+
+    // the scope of `element` inside this one, without the clips it adds
+    fn child(&self, element: &Element, options: &ExtractOptions) -> Scope {
+        let mut scope = self.clone();
+        scope.style = self.style.child(element);
+        scope.matched = self.matched
+            || match &options.selector {
+                Some(selector) => selector.matches(element.name, &element.attributes),
+                None => true,
+            };
+        if let Some(own) = element.attribute("transform").and_then(parse_transform) {
+            scope.transform = own.then(&self.transform);
+        }
+        if element.name == "svg" {
+            if options.apply_view_box {
+                if let Some(viewport) = viewport(element, self.nested) {
+                    scope.transform = viewport.then(&scope.transform);
+                }
+            }
+            scope.nested = true;
+        }
+        scope
+    }
+}
+
+// the path a shape element draws in `scope`, `None` when it draws nothing or is not selected
+fn shape(
+    element: &Element,
+    scope: &Scope,
+    options: &ExtractOptions,
+) -> Result<Option<Extracted>, SvgError> {
+    if !scope.matched {
+        return Ok(None);
+    }
+    let Some(d) = shape_data(element, &scope.style) else {
+        return Ok(None);
+    };
+
+    let mut path = Parser::new(&d).parse_path().map_err(SvgError::Path)?;
+    let mut transform = scope.transform;
+    let mut clips = scope.clips.clone();
+    if options.bake_transforms {
+        if transform != Transform::identity() {
+            path.transform(&transform);
+        }
+        transform = Transform::identity();
+    } else if let Some(inverse) = transform.invert() {
+        for region in &mut clips {
+            region.path.transform(&inverse);
+        }
+    }
+    if options.apply_clips {
+        for region in clips.drain(..) {
+            path = path.clip_to(&region);
+        }
+    }
+
+    path.set_tag("element", element.name);
+    for key in ["id", "class"] {
+        if let Some(value) = element.attribute(key) {
+            path.set_tag(key, value);
+        }
+    }
+
+    let mut style = scope.style.clone();
+    // an image is a placeholder for where the picture goes, nothing paints it
+    if element.name == "image" {
+        let href = element
+            .attribute("href")
+            .or_else(|| element.attribute("xlink:href"));
+        if let Some(href) = href {
+            path.set_tag("href", href.trim());
+        }
+        style.fill = None;
+        style.stroke = None;
+    }
+    // text is only the box it roughly takes up, with the words in a tag
+    if element.name == "text" {
+        path.set_tag("text", element.text_content());
+        style.fill = None;
+        style.stroke = None;
+    }
+
+    Ok(Some(Extracted {
+        path,
+        transform,
+        style,
+        clips,
+    }))
+}
+
+struct Walk<'a, 'src> {
+    ids: HashMap<&'a str, &'a Element<'src>>,
+    options: &'a ExtractOptions,
+    // ids of the <use> targets and clips being drawn, a reference back into one of them would
+    // never end
+    using: Vec<&'a str>,
+    // shapes to draw in document order, with what they take from around them
+    shapes: Vec<(&'a Element<'src>, Scope)>,
+}
+
+impl<'a, 'src> Walk<'a, 'src> {
+    // the paths of the shapes found so far, made on `threads` threads
+    fn take(&mut self, threads: usize) -> Result<Vec<Extracted>, SvgError> {
+        let shapes = std::mem::take(&mut self.shapes);
+        let threads = threads.clamp(1, shapes.len().max(1));
+        if threads == 1 {
+            return Ok(shapes
+                .iter()
+                .map(|(element, scope)| shape(element, scope, self.options))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect());
+        }
+
+        // each thread takes a run of shapes, the runs are put back together in order
+        let options = self.options;
+        std::thread::scope(|s| {
+            let runs: Vec<_> = shapes
+                .chunks(shapes.len().div_ceil(threads))
+                .map(|run| {
+                    s.spawn(move || {
+                        run.iter()
+                            .map(|(element, scope)| shape(element, scope, options))
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                })
+                .collect();
+
+            let mut out = Vec::with_capacity(shapes.len());
+            for run in runs {
+                out.extend(run.join().expect("shape thread")?.into_iter().flatten());
+            }
+            Ok(out)
+        })
+    }
+
+    fn element(&mut self, element: &'a Element<'src>, parent: &Scope) -> Result<(), SvgError> {
+        if REFERENCED_ONLY.contains(&element.name) {
+            return Ok(());
+        }
+        self.drawn(element, parent)
+    }
+
+    // `element` drawn, whether or not it is normally hidden away
+    fn drawn(&mut self, element: &'a Element<'src>, parent: &Scope) -> Result<(), SvgError> {
+        if element.property("display") == Some("none") {
+            return Ok(());
+        }
+
+        let mut scope = parent.child(element, self.options);
+        for key in ["clip-path", "mask"] {
+            let Some(id) = element.attribute(key).and_then(url_reference) else {
+                continue;
+            };
+            if let Some(region) = self.region(id, element, &scope.transform)? {
+                scope.clips.push(region);
+            }
+        }
+
+        self.content(element, &scope)
+    }
+
+    // what `element` draws in `scope`, which already holds its own transform and clips
+    fn content(&mut self, element: &'a Element<'src>, scope: &Scope) -> Result<(), SvgError> {
+        match element.name {
+            "use" => {
+                let Some((id, target)) =
+                    element.href().and_then(|id| Some((id, *self.ids.get(id)?)))
+                else {
+                    return Ok(());
+                };
+                if self.using.contains(&id) {
+                    return Ok(());
+                }
+
+                let number = |key| {
+                    element
+                        .attribute(key)
+                        .and_then(|v| v.trim().parse().ok())
+                        .unwrap_or(0.0)
+                };
+                let mut scope = scope.clone();
+                scope.transform =
+                    Transform::translate(number("x"), number("y")).then(&scope.transform);
+
+                self.using.push(id);
+                if target.name == "symbol" {
+                    scope.matched = scope.matched
+                        || matches!(&self.options.selector, Some(s) if s.matches(target.name, &target.attributes));
+                    for child in &target.children {
+                        self.element(child, &scope)?;
+                    }
+                } else {
+                    self.drawn(target, &scope)?;
+                }
+                self.using.pop();
+            }
+            _ if is_shape(element.name) => self.shapes.push((element, scope.clone())),
+            _ => {
+                for child in &element.children {
+                    self.element(child, scope)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // the area of the <clipPath> or <mask> `id` as applied to `element`, in document coordinates.
+    // `transform` takes `element`'s coordinates there
+    fn region(
+        &mut self,
+        id: &'a str,
+        element: &'a Element<'src>,
+        transform: &Transform,
+    ) -> Result<Option<Region>, SvgError> {
+        let Some(&target) = self.ids.get(id) else {
+            return Ok(None);
+        };
+        let units = match target.name {
+            "clipPath" => "clipPathUnits",
+            "mask" => "maskContentUnits",
+            _ => return Ok(None),
+        };
+        if self.using.contains(&id) {
+            return Ok(None);
+        }
+
+        let options = ExtractOptions::default();
+        let mut walk = Walk {
+            ids: self.ids.clone(),
+            options: &options,
+            using: self.using.clone(),
+            shapes: Vec::new(),
+        };
+        walk.using.push(id);
+
+        let mut inner = target
+            .attribute("transform")
+            .and_then(parse_transform)
+            .unwrap_or_default();
+        if target.attribute(units) == Some("objectBoundingBox") {
+            // fractions of the box around what `element` itself draws
+            let scope = Scope {
+                transform: Transform::identity(),
+                matched: true,
+                clips: Vec::new(),
+                style: Style::default(),
+                nested: false,
+            };
+            walk.content(element, &scope)?;
+            let Some((min, max)) = walk
+                .take(1)?
+                .into_iter()
+                .flat_map(|e| e.path.take_commands())
+                .flat_map(|cmd| cmd.points())
+                .fold(None, |acc: Option<(Point, Point)>, (x, y)| match acc {
+                    None => Some(((x, y), (x, y))),
+                    Some((lo, hi)) => {
+                        Some(((lo.0.min(x), lo.1.min(y)), (hi.0.max(x), hi.1.max(y))))
+                    }
+                })
+            else {
+                return Ok(None);
+            };
+            inner = inner.then(&Transform::matrix(
+                max.0 - min.0,
+                0.0,
+                0.0,
+                max.1 - min.1,
+                min.0,
+                min.1,
+            ));
+        }
+
+        let scope = Scope {
+            transform: inner.then(transform),
+            matched: true,
+            clips: Vec::new(),
+            style: Style::default(),
+            nested: false,
+        };
+        for child in &target.children {
+            walk.element(child, &scope)?;
+        }
+
+        let fill_rule = match target
+            .children
+            .first()
+            .and_then(|c| c.attribute("clip-rule"))
+        {
+            Some("evenodd") => FillRule::EvenOdd,
+            _ => FillRule::NonZero,
+        };
+        let commands = walk
+            .take(1)?
+            .into_iter()
+            .flat_map(|e| e.path.take_commands())
+            .collect();
+        Ok(Some(Region {
+            path: Path::new(commands),
+            fill_rule,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a line across a 10 by 10 viewBox shown in a 40 by 20 nested <svg> at (10, 20), inside a
+    // document drawn at twice its viewBox
+    fn nested(aspect: &str) -> String {
+        let source = format!(
+            r#"<svg viewBox="0 0 100 100" width="200" height="200">
+                <svg x="10" y="20" width="40" height="20" viewBox="0 0 10 10"
+                    preserveAspectRatio="{aspect}">
+                    <path d="M0 0 L10 10"/>
+                </svg>
+            </svg>"#
+        );
+        let paths = paths(&source).unwrap();
+        assert_eq!(paths.len(), 1);
+        paths[0].to_d()
+    }
+
+    #[test]
+    fn paths_map_nested_view_boxes() {
+        assert_eq!(nested("xMidYMid meet"), "M40 40 L80 80");
+        assert_eq!(nested("xMinYMin meet"), "M20 40 L60 80");
+        assert_eq!(nested("xMidYMid slice"), "M20 20 L100 100");
+        assert_eq!(nested("none"), "M20 40 L100 80");
+
+        // `extract` leaves the viewBoxes to the options
+        let source =
+            r#"<svg viewBox="0 0 10 10" width="20" height="20"><path d="M1 1 L2 2"/></svg>"#;
+        let extracted = extract(source, &ExtractOptions::default()).unwrap();
+        assert_eq!(extracted[0].path.to_d(), "M1 1 L2 2");
+        assert_eq!(paths(source).unwrap()[0].to_d(), "M2 2 L4 4");
+    }
+}
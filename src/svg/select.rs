@@ -0,0 +1,100 @@
+use super::SvgError;
+use std::borrow::Cow;
+
+// one compound selector such as `path`, `#icon-home`, `.glyph` or `g.icon#home`
+#[derive(Debug, Clone, PartialEq)]
+struct Compound {
+    // `None` for `*` or a selector without an element name
+    element: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+// a comma separated list of compound selectors. combinators, attribute selectors and pseudo
+// classes are not supported
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    compounds: Vec<Compound>,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_') || !c.is_ascii()
+}
+
+impl Selector {
+    pub fn parse(source: &str) -> Result<Selector, SvgError> {
+        let error = |offset| SvgError::Selector { offset };
+
+        let mut compounds = Vec::new();
+        let mut offset = 0;
+
+        for part in source.split(',') {
+            let start = offset + part.len() - part.trim_start().len();
+            offset += part.len() + 1;
+
+            let part = part.trim();
+            if part.is_empty() {
+                return Err(error(start));
+            }
+
+            let mut compound = Compound {
+                element: None,
+                id: None,
+                classes: Vec::new(),
+            };
+
+            let mut rest = part;
+            if let Some(after) = rest.strip_prefix('*') {
+                rest = after;
+            }
+
+            while !rest.is_empty() {
+                let at = start + part.len() - rest.len();
+                let (sigil, body) = match rest.chars().next() {
+                    Some(c @ ('#' | '.')) => (Some(c), &rest[1..]),
+                    _ if at == start => (None, rest),
+                    _ => return Err(error(at)),
+                };
+
+                let len = body.find(|c| !is_name_char(c)).unwrap_or(body.len());
+                if len == 0 {
+                    return Err(error(at));
+                }
+                let name = body[..len].to_string();
+                rest = &body[len..];
+
+                match sigil {
+                    Some('#') => compound.id = Some(name),
+                    Some(_) => compound.classes.push(name),
+                    None => compound.element = Some(name),
+                }
+            }
+
+            compounds.push(compound);
+        }
+
+        Ok(Selector { compounds })
+    }
+
+    // whether an element with this (local) name and these attributes matches any of the compounds
+    pub(crate) fn matches(&self, name: &str, attributes: &[(&str, Cow<'_, str>)]) -> bool {
+        let attribute = |key: &str| {
+            attributes
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.as_ref())
+        };
+
+        let class = attribute("class").unwrap_or("");
+
+        self.compounds.iter().any(|compound| {
+            let element = !matches!(&compound.element, Some(element) if element != name);
+            let id = !matches!(&compound.id, Some(id) if attribute("id") != Some(id.as_str()));
+            let classes = compound
+                .classes
+                .iter()
+                .all(|c| class.split_whitespace().any(|have| have == c));
+            element && id && classes
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use super::{tree::Element, Style};
+use crate::Float;
+use std::borrow::Cow;
+
+// rough proportions of a typical font, in ems: how far a character advances on average and how
+// far the glyphs reach above and below the baseline
+const ADVANCE: Float = 0.55;
+const ASCENT: Float = 0.8;
+const DESCENT: Float = 0.2;
+
+pub(crate) fn is_shape(name: &str) -> bool {
+    matches!(
+        name,
+        "path" | "rect" | "circle" | "ellipse" | "line" | "polyline" | "polygon" | "image" | "text"
+    )
+}
+
+// path data drawing `element`, for <path> and the basic shapes, and the box an <image> or <text>
+// takes up. text is measured with average glyph sizes, so its box is only roughly right. `None`
+// for anything else and for shapes that draw nothing (a rect without a size, a circle without a
+// radius, empty text)
+pub(crate) fn shape_data<'e>(element: &'e Element, style: &Style) -> Option<Cow<'e, str>> {
+    let len = |key| element.length(key).unwrap_or(0.0);
+
+    let d = match element.name {
+        "path" => return element.attribute("d").map(Cow::Borrowed),
+        "rect" | "image" => {
+            let (x, y, w, h) = (len("x"), len("y"), len("width"), len("height"));
+            if w <= 0.0 || h <= 0.0 {
+                return None;
+            }
+
+            // a missing radius takes the other one, both are capped at half the side
+            let (rx, ry) = match (element.length("rx"), element.length("ry")) {
+                _ if element.name == "image" => (0.0, 0.0),
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0.0, 0.0),
+            };
+            let (rx, ry) = (rx.clamp(0.0, w / 2.0), ry.clamp(0.0, h / 2.0));
+
+            if rx == 0.0 || ry == 0.0 {
+                format!("M{x} {y}H{}V{}H{x}Z", x + w, y + h)
+            } else {
+                format!(
+                    "M{} {y}H{}A{rx} {ry} 0 0 1 {} {}V{}A{rx} {ry} 0 0 1 {} {}H{}A{rx} {ry} 0 0 1 {x} {}V{}A{rx} {ry} 0 0 1 {} {y}Z",
+                    x + rx,
+                    x + w - rx,
+                    x + w,
+                    y + ry,
+                    y + h - ry,
+                    x + w - rx,
+                    y + h,
+                    x + rx,
+                    y + h - ry,
+                    y + ry,
+                    x + rx,
+                )
+            }
+        }
+        "circle" | "ellipse" => {
+            let (cx, cy) = (len("cx"), len("cy"));
+            let (rx, ry) = if element.name == "circle" {
+                (len("r"), len("r"))
+            } else {
+                (len("rx"), len("ry"))
+            };
+            if rx <= 0.0 || ry <= 0.0 {
+                return None;
+            }
+
+            format!(
+                "M{} {cy}A{rx} {ry} 0 0 1 {} {cy}A{rx} {ry} 0 0 1 {} {cy}Z",
+                cx + rx,
+                cx - rx,
+                cx + rx
+            )
+        }
+        "line" => format!("M{} {}L{} {}", len("x1"), len("y1"), len("x2"), len("y2")),
+        // coordinates after a moveto are linetos
+        "polyline" | "polygon" => {
+            let points = element.attribute("points")?.trim();
+            if points.is_empty() {
+                return None;
+            }
+            let close = if element.name == "polygon" { "Z" } else { "" };
+            format!("M{points}{close}")
+        }
+        "text" => {
+            let chars = element.text_content().chars().count();
+            if chars == 0 {
+                return None;
+            }
+
+            let size = style.font_size;
+            let width = chars as Float * size * ADVANCE;
+            // x and y place the start of the baseline, text-anchor moves the box along it
+            let x = len("x")
+                - match element.property("text-anchor") {
+                    Some("middle") => width / 2.0,
+                    Some("end") => width,
+                    _ => 0.0,
+                };
+            let y = len("y");
+
+            format!(
+                "M{x} {}H{}V{}H{x}Z",
+                y - size * ASCENT,
+                x + width,
+                y + size * DESCENT
+            )
+        }
+        _ => return None,
+    };
+
+    Some(Cow::Owned(d))
+}
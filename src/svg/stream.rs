@@ -0,0 +1,234 @@
+use super::{
+    local_name, shape,
+    shapes::is_shape,
+    tree::{self, Element},
+    xml::{Event, Reader, XmlError},
+    ExtractOptions, Extracted, Scope, SvgError, GZIP_MAGIC, REFERENCED_ONLY,
+};
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+
+// bytes asked of the reader at a time
+const CHUNK: usize = 64 * 1024;
+
+// a <text> element being read, it is only drawn once its content is complete
+struct OpenText {
+    // where its start tag begins in the document
+    start: usize,
+    // elements open inside it, itself included
+    depth: usize,
+    scope: Scope,
+}
+
+struct Stream<'a, F> {
+    options: &'a ExtractOptions,
+    each: F,
+    // the scope of every open element that is drawn
+    open: Vec<Scope>,
+    // elements open inside one that is not drawn, itself included
+    hidden: usize,
+    text: Option<OpenText>,
+}
+
+impl<'a, F: FnMut(Extracted)> Stream<'a, F> {
+    // `source` holds the document from byte `base` on, `at` is where the event just read ends
+    fn event(
+        &mut self,
+        event: Event,
+        source: &str,
+        base: usize,
+        at: usize,
+    ) -> Result<(), SvgError> {
+        let (name, attributes, self_closing) = match event {
+            Event::Start {
+                name,
+                attributes,
+                self_closing,
+            } => (name, attributes, self_closing),
+            Event::End { .. } => {
+                if self.hidden > 0 {
+                    self.hidden -= 1;
+                } else if let Some(text) = &mut self.text {
+                    text.depth -= 1;
+                    if text.depth == 0 {
+                        let text = self.text.take().expect("open text");
+                        self.text_element(&source[text.start - base..at], &text.scope)?;
+                    }
+                } else {
+                    self.open.pop();
+                }
+                return Ok(());
+            }
+            Event::Text(_) => return Ok(()),
+        };
+
+        if self.hidden > 0 || self.text.is_some() {
+            if !self_closing {
+                match &mut self.text {
+                    Some(text) if self.hidden == 0 => text.depth += 1,
+                    _ => self.hidden += 1,
+                }
+            }
+            return Ok(());
+        }
+
+        let element = Element {
+            name: local_name(name),
+            attributes,
+            children: Vec::new(),
+            text: Vec::new(),
+        };
+        // shapes draw nothing of what is inside them
+        if REFERENCED_ONLY.contains(&element.name) || element.property("display") == Some("none") {
+            if !self_closing {
+                self.hidden = 1;
+            }
+            return Ok(());
+        }
+
+        let parent = self.open.last().cloned().unwrap_or_else(Scope::root);
+        let scope = parent.child(&element, self.options);
+
+        if element.name == "text" && !self_closing {
+            // attribute values cannot hold a `<`, so the last one is where the tag starts
+            let start = source[..at].rfind('<').expect("start tag");
+            self.text = Some(OpenText {
+                start: base + start,
+                depth: 1,
+                scope,
+            });
+        } else if is_shape(element.name) {
+            if let Some(extracted) = shape(&element, &scope, self.options)? {
+                (self.each)(extracted);
+            }
+            if !self_closing {
+                self.hidden = 1;
+            }
+        } else if !self_closing {
+            self.open.push(scope);
+        }
+
+        Ok(())
+    }
+
+    // a whole <text> element, read again with its content
+    fn text_element(&mut self, source: &str, scope: &Scope) -> Result<(), SvgError> {
+        let root = tree::parse(source).map_err(SvgError::Xml)?;
+        let Some(element) = root.children.first() else {
+            return Ok(());
+        };
+        if let Some(extracted) = shape(element, scope, self.options)? {
+            (self.each)(extracted);
+        }
+        Ok(())
+    }
+}
+
+// `extract` for documents too large to hold in memory: `each` gets every path as soon as its
+// element has been read, and only the element being read is kept around (a whole <text>, since
+// its box depends on its content). references need the rest of the document, so <use>,
+// `clip-path` and `mask` are ignored, and everything in <defs>, <symbol> and the like is skipped.
+// paths come one at a time as they are read, so `threads` goes unused. gzip compressed documents
+// (.svgz) are inflated on the way with the `flate2` feature
+pub fn extract_streaming(
+    reader: impl Read,
+    options: &ExtractOptions,
+    each: impl FnMut(Extracted),
+) -> Result<(), SvgError> {
+    let mut reader = BufReader::new(reader);
+    let head = loop {
+        match reader.fill_buf() {
+            Ok(head) => break head,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(SvgError::Io(e.kind())),
+        }
+    };
+
+    if head.starts_with(&GZIP_MAGIC) {
+        #[cfg(feature = "flate2")]
+        return stream(flate2::read::GzDecoder::new(reader), options, each);
+        #[cfg(not(feature = "flate2"))]
+        return Err(SvgError::Compressed);
+    }
+    stream(reader, options, each)
+}
+
+fn stream(
+    mut reader: impl Read,
+    options: &ExtractOptions,
+    each: impl FnMut(Extracted),
+) -> Result<(), SvgError> {
+    let mut stream = Stream {
+        options,
+        each,
+        open: Vec::new(),
+        hidden: 0,
+        text: None,
+    };
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut chunk = vec![0; CHUNK];
+    // where `buffer` starts in the document
+    let mut base = 0;
+    // how much of `buffer` was read already, an open <text> keeps it around
+    let mut read = 0;
+    let mut done = false;
+
+    while !done {
+        let n = match reader.read(&mut chunk) {
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(SvgError::Io(e.kind())),
+        };
+        done = n == 0;
+        buffer.extend_from_slice(&chunk[..n]);
+
+        // a chunk may end inside a character
+        let valid = match std::str::from_utf8(&buffer) {
+            Ok(s) => s,
+            Err(e) if e.error_len().is_none() && !done => {
+                std::str::from_utf8(&buffer[..e.valid_up_to()]).expect("valid prefix")
+            }
+            Err(e) => {
+                return Err(SvgError::Xml(XmlError {
+                    offset: base + e.valid_up_to(),
+                }))
+            }
+        };
+        // until the end only read up to the last tag that is complete. a `>` can also sit in an
+        // attribute value or a comment, the reader then runs out and we wait for more
+        let source = match valid.rfind('>') {
+            _ if done => valid,
+            Some(i) => &valid[..=i],
+            None => continue,
+        };
+
+        let from = read;
+        let mut events = Reader::new(&source[from..]);
+        loop {
+            match events.next() {
+                None => {
+                    read = source.len();
+                    break;
+                }
+                Some(Ok(event)) => {
+                    stream.event(event, source, base, from + events.offset())?;
+                    read = from + events.offset();
+                }
+                Some(Err(_)) if !done => break,
+                Some(Err(e)) => {
+                    return Err(SvgError::Xml(XmlError {
+                        offset: base + from + e.offset,
+                    }))
+                }
+            }
+        }
+
+        // an open <text> is kept until it closes
+        let keep = stream.text.as_ref().map_or(read, |text| text.start - base);
+        buffer.drain(..keep);
+        base += keep;
+        read -= keep;
+    }
+
+    Ok(())
+}
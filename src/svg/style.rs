@@ -0,0 +1,88 @@
+use super::tree::Element;
+use crate::{
+    units::{parse_length, Unit},
+    FillRule, Float,
+};
+
+// how a shape is painted, after inheritance. paints are kept as written (`#f00`, `red`,
+// `url(#gradient)`), `None` is `none`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub fill: Option<String>,
+    pub fill_opacity: Float,
+    pub fill_rule: FillRule,
+    pub stroke: Option<String>,
+    pub stroke_opacity: Float,
+    pub stroke_width: Float,
+    // the element's opacity times that of every group around it
+    pub opacity: Float,
+    pub font_size: Float,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some("black".to_string()),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            opacity: 1.0,
+            // the usual browser default
+            font_size: 16.0,
+        }
+    }
+}
+
+fn paint(value: &str) -> Option<String> {
+    (value != "none").then(|| value.to_string())
+}
+
+// a number or a percentage, clamped to 0..1
+fn fraction(value: &str) -> Option<Float> {
+    let v: Float = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<Float>().ok()? / 100.0,
+        None => value.parse().ok()?,
+    };
+    Some(v.clamp(0.0, 1.0))
+}
+
+impl Style {
+    // the style of `element` inside an element styled like this
+    pub(crate) fn child(&self, element: &Element) -> Style {
+        let mut style = self.clone();
+        // `inherit` and values that do not parse leave the inherited one
+        let property = |key| element.property(key).filter(|v| *v != "inherit");
+
+        if let Some(v) = property("fill") {
+            style.fill = paint(v);
+        }
+        if let Some(v) = property("stroke") {
+            style.stroke = paint(v);
+        }
+        if let Some(v) = property("fill-rule") {
+            style.fill_rule = match v {
+                "evenodd" => FillRule::EvenOdd,
+                _ => FillRule::NonZero,
+            };
+        }
+        if let Some(f) = property("fill-opacity").and_then(fraction) {
+            style.fill_opacity = f;
+        }
+        if let Some(f) = property("stroke-opacity").and_then(fraction) {
+            style.stroke_opacity = f;
+        }
+        if let Some((w, unit)) = property("stroke-width").and_then(parse_length) {
+            style.stroke_width = unit.convert(w, Unit::Px);
+        }
+        if let Some(f) = property("opacity").and_then(fraction) {
+            style.opacity *= f;
+        }
+        if let Some((size, unit)) = property("font-size").and_then(parse_length) {
+            style.font_size = unit.convert(size, Unit::Px);
+        }
+
+        style
+    }
+}
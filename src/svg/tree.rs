@@ -0,0 +1,130 @@
+use super::{
+    local_name,
+    xml::{Event, Reader, XmlError},
+};
+use crate::units::{parse_length, Unit};
+use crate::Float;
+use std::{borrow::Cow, collections::HashMap};
+
+// an element with everything inside it
+pub(crate) struct Element<'src> {
+    // without the namespace prefix
+    pub(crate) name: &'src str,
+    pub(crate) attributes: Vec<(&'src str, Cow<'src, str>)>,
+    pub(crate) children: Vec<Element<'src>>,
+    // runs of text directly inside, each after this many children
+    pub(crate) text: Vec<(usize, String)>,
+}
+
+impl<'src> Element<'src> {
+    pub(crate) fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    // a presentation property, from the `style` attribute when it is set there
+    pub(crate) fn property(&self, key: &str) -> Option<&str> {
+        let declared = self.attribute("style").and_then(|style| {
+            style
+                .split(';')
+                .filter_map(|declaration| declaration.split_once(':'))
+                .rfind(|(k, _)| k.trim() == key)
+                .map(|(_, v)| v.trim())
+        });
+        declared.or_else(|| self.attribute(key).map(str::trim))
+    }
+
+    // a length in user units (px), lengths in physical units are converted
+    pub(crate) fn length(&self, key: &str) -> Option<Float> {
+        let (value, unit) = parse_length(self.attribute(key)?)?;
+        Some(unit.convert(value, Unit::Px))
+    }
+
+    // the `#id` a <use> points at, from `href` or the older `xlink:href`
+    pub(crate) fn href(&self) -> Option<&str> {
+        self.attribute("href")
+            .or_else(|| self.attribute("xlink:href"))
+            .and_then(|href| href.trim().strip_prefix('#'))
+    }
+
+    // the text of the element and everything in it, whitespace runs collapsed to one space
+    pub(crate) fn text_content(&self) -> String {
+        fn collect(element: &Element, out: &mut String) {
+            let mut runs = element.text.iter().peekable();
+            for i in 0..=element.children.len() {
+                while let Some((_, run)) = runs.next_if(|(at, _)| *at == i) {
+                    out.push_str(run);
+                }
+                if let Some(child) = element.children.get(i) {
+                    collect(child, out);
+                }
+            }
+        }
+
+        let mut raw = String::new();
+        collect(self, &mut raw);
+        raw.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    // every element with an id, the first one wins when ids repeat
+    pub(crate) fn ids<'a>(&'a self, out: &mut HashMap<&'a str, &'a Element<'src>>) {
+        if let Some(id) = self.attribute("id") {
+            out.entry(id).or_insert(self);
+        }
+        for child in &self.children {
+            child.ids(out);
+        }
+    }
+}
+
+// move the innermost open element into its parent
+fn close(stack: &mut Vec<Element<'_>>) {
+    if stack.len() > 1 {
+        let element = stack.pop().expect("open element");
+        stack.last_mut().expect("root").children.push(element);
+    }
+}
+
+// the whole document under a nameless root. stray closing tags are ignored and elements left
+// open at the end are closed
+pub(crate) fn parse(source: &str) -> Result<Element<'_>, XmlError> {
+    let mut stack = vec![Element {
+        name: "",
+        attributes: Vec::new(),
+        children: Vec::new(),
+        text: Vec::new(),
+    }];
+
+    for event in Reader::new(source) {
+        match event? {
+            Event::Start {
+                name,
+                attributes,
+                self_closing,
+            } => {
+                stack.push(Element {
+                    name: local_name(name),
+                    attributes,
+                    children: Vec::new(),
+                    text: Vec::new(),
+                });
+                if self_closing {
+                    close(&mut stack);
+                }
+            }
+            Event::End { .. } => close(&mut stack),
+            Event::Text(text) => {
+                let parent = stack.last_mut().expect("root");
+                parent.text.push((parent.children.len(), text.into_owned()));
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+
+    Ok(stack.pop().expect("root"))
+}
@@ -0,0 +1,70 @@
+use super::tree::Element;
+use crate::{transform::Transform, Float};
+
+// where the space left over by a uniformly scaled viewBox goes: 0 all after, 1 all before
+fn align(s: &str) -> Option<Float> {
+    match s {
+        "Min" => Some(0.0),
+        "Mid" => Some(0.5),
+        "Max" => Some(1.0),
+        _ => None,
+    }
+}
+
+// the transform from the coordinates inside an <svg> element to the ones around it: its viewBox
+// fitted to its width and height as `preserveAspectRatio` says. nested ones are also moved to
+// their `x` and `y`, the outermost one is not. sizes that are missing or relative (`100%`) are
+// taken to be the viewBox's own. `None` when nothing moves
+pub(crate) fn viewport(element: &Element, nested: bool) -> Option<Transform> {
+    let offset = |key| {
+        if nested {
+            element.length(key).unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    };
+    let (x, y) = (offset("x"), offset("y"));
+
+    let view_box: Option<Vec<Float>> = element.attribute("viewBox").and_then(|v| {
+        v.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse().ok())
+            .collect()
+    });
+    let Some(&[min_x, min_y, width, height]) = view_box.as_deref() else {
+        return (x != 0.0 || y != 0.0).then(|| Transform::translate(x, y));
+    };
+    // a viewBox without area turns rendering off, which is not ours to decide
+    if !(width > 0.0 && height > 0.0) {
+        return None;
+    }
+
+    let w = element.length("width").unwrap_or(width);
+    let h = element.length("height").unwrap_or(height);
+    let (mut sx, mut sy) = (w / width, h / height);
+
+    let mut words = element
+        .attribute("preserveAspectRatio")
+        .unwrap_or_default()
+        .split_whitespace();
+    let (ax, ay) = match words.next().unwrap_or("xMidYMid") {
+        "none" => (0.0, 0.0),
+        alignment => {
+            let scale = match words.next() {
+                Some("slice") => sx.max(sy),
+                _ => sx.min(sy),
+            };
+            (sx, sy) = (scale, scale);
+
+            alignment
+                .strip_prefix('x')
+                .and_then(|rest| rest.split_once('Y'))
+                .and_then(|(ax, ay)| Some((align(ax)?, align(ay)?)))
+                .unwrap_or((0.5, 0.5))
+        }
+    };
+
+    let tx = x - min_x * sx + (w - width * sx) * ax;
+    let ty = y - min_y * sy + (h - height * sy) * ay;
+    Some(Transform::matrix(sx, 0.0, 0.0, sy, tx, ty))
+}
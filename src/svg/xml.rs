@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+
+// just enough xml for svg documents: elements, attributes and text. comments, processing
+// instructions and doctypes are skipped, cdata is returned as text
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'src> {
+    Start {
+        name: &'src str,
+        attributes: Vec<(&'src str, Cow<'src, str>)>,
+        self_closing: bool,
+    },
+    End {
+        name: &'src str,
+    },
+    Text(Cow<'src, str>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XmlError {
+    pub offset: usize,
+}
+
+pub struct Reader<'src> {
+    src: &'src str,
+    pos: usize,
+}
+
+impl<'src> Reader<'src> {
+    pub fn new(src: &'src str) -> Self {
+        // skip a byte order mark
+        let pos = if src.starts_with('\u{feff}') { 3 } else { 0 };
+        Reader { src, pos }
+    }
+
+    // bytes read so far
+    pub(crate) fn offset(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    fn rest(&self) -> &'src str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), XmlError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => Err(XmlError { offset: self.pos }),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn name(&mut self) -> Result<&'src str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+
+        if len == 0 {
+            return Err(XmlError { offset: self.pos });
+        }
+
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn doctype(&mut self) -> Result<(), XmlError> {
+        // doctypes may contain an internal subset in brackets
+        let mut depth = 0;
+        for (i, c) in self.rest().char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                '>' if depth == 0 => {
+                    self.pos += i + 1;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        Err(XmlError { offset: self.pos })
+    }
+
+    fn element(&mut self) -> Result<Event<'src>, XmlError> {
+        // past the '<'
+        self.pos += 1;
+
+        if self.rest().starts_with('/') {
+            self.pos += 1;
+            let name = self.name()?;
+            self.skip_past(">")?;
+            return Ok(Event::End { name });
+        }
+
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(Event::Start {
+                    name,
+                    attributes,
+                    self_closing: true,
+                });
+            }
+
+            if rest.starts_with('>') {
+                self.pos += 1;
+                return Ok(Event::Start {
+                    name,
+                    attributes,
+                    self_closing: false,
+                });
+            }
+
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(XmlError { offset: self.pos });
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(XmlError { offset: self.pos }),
+            };
+            self.pos += 1;
+
+            let rest = self.rest();
+            let Some(len) = rest.find(quote) else {
+                return Err(XmlError { offset: self.pos });
+            };
+            self.pos += len + 1;
+
+            attributes.push((key, unescape(&rest[..len])));
+        }
+    }
+}
+
+impl<'src> Iterator for Reader<'src> {
+    type Item = Result<Event<'src>, XmlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return None;
+            }
+
+            if !rest.starts_with('<') {
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.pos += len;
+
+                let text = &rest[..len];
+                if text.trim().is_empty() {
+                    continue;
+                }
+                return Some(Ok(Event::Text(unescape(text))));
+            }
+
+            let skipped = if rest.starts_with("<!--") {
+                self.skip_past("-->")
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let start = self.pos;
+                if let Err(e) = self.skip_past("]]>") {
+                    return Some(Err(e));
+                }
+                let text = &self.src[start..self.pos - 3];
+                return Some(Ok(Event::Text(Cow::Borrowed(text))));
+            } else if rest.starts_with("<!") {
+                self.doctype()
+            } else {
+                return Some(self.element());
+            };
+
+            if let Err(e) = skipped {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+// replace the predefined entities and character references
+pub fn unescape(s: &str) -> Cow<'_, str> {
+    if !s.contains('&') {
+        return Cow::Borrowed(s);
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                .and_then(|n| n.ok())
+                .and_then(char::from_u32),
+        };
+
+        match c {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    Cow::Owned(out)
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::{
+    clip::clip_convex,
+    path::Path,
+    segment::{push_segments, Segment},
+    trace::audit,
+    Float, SimplifyOptions,
+};
+
+impl Path {
+    // the path cut into `tile_size` squares keyed by (column, row), tile (i, j) covering x from
+    // i * tile_size to (i + 1) * tile_size and likewise for y. coordinates stay as they are. closed
+    // contours are closed again along the tile edges so each tile fills on its own, open ones are
+    // cut into the runs that fall in each tile
+    pub fn chunk_by_grid(&self, tile_size: Float) -> HashMap<(i32, i32), Path> {
+        let mut tiles: HashMap<(i32, i32), Vec<_>> = HashMap::new();
+
+        if tile_size.is_nan() || tile_size <= 0.0 {
+            return HashMap::new();
+        }
+
+        for sp in self.subpaths() {
+            let segments: Vec<Segment> = sp.segments().collect();
+            if segments.is_empty() {
+                continue;
+            }
+
+            let (min, max) = sp
+                .commands
+                .iter()
+                .flat_map(|cmd| cmd.points())
+                .fold((sp.start, sp.start), |(lo, hi), (x, y)| {
+                    ((lo.0.min(x), lo.1.min(y)), (hi.0.max(x), hi.1.max(y)))
+                });
+            let tile = |v: Float| (v / tile_size).floor() as i32;
+
+            for j in tile(min.1)..=tile(max.1) {
+                for i in tile(min.0)..=tile(max.0) {
+                    let (x0, y0) = (i as Float * tile_size, j as Float * tile_size);
+                    let (x1, y1) = (x0 + tile_size, y0 + tile_size);
+                    let square = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+
+                    for run in clip_convex(&segments, sp.closed, &square) {
+                        push_segments(&run, sp.closed, tiles.entry((i, j)).or_default());
+                    }
+                }
+            }
+        }
+
+        tiles
+            .into_iter()
+            .map(|(key, commands)| (key, Path::new(commands)))
+            .collect()
+    }
+}
+
+// tiles are this many units across, as in mapbox vector tiles
+pub const EXTENT: Float = 4096.0;
+
+pub struct Tile {
+    pub zoom: u32,
+    pub x: i32,
+    pub y: i32,
+    // in the tile's own coordinates, 0 to EXTENT across
+    pub path: Path,
+}
+
+// every tile of the zooms below `zoom_levels`. zoom 0 is a single tile over the path's bounding
+// square and each zoom after splits every tile in four. a level is simplified to one tile unit
+// before it is cut, so far out zooms stay light, and only one level is worked on at a time.
+// tiles with nothing in them are left out
+pub fn generate(path: &Path, zoom_levels: u32) -> impl Iterator<Item = Tile> + '_ {
+    let (min, max) = path.commands.iter().flat_map(|cmd| cmd.points()).fold(
+        ((Float::MAX, Float::MAX), (Float::MIN, Float::MIN)),
+        |(lo, hi), (x, y)| ((lo.0.min(x), lo.1.min(y)), (hi.0.max(x), hi.1.max(y))),
+    );
+    let side = (max.0 - min.0).max(max.1 - min.1);
+
+    // an empty or degenerate path has no tiles at any zoom
+    let levels = if side > 0.0 && side.is_finite() {
+        zoom_levels
+    } else {
+        0
+    };
+
+    (0..levels).flat_map(move |zoom| {
+        let scale = EXTENT * Float::powi(2.0, zoom as i32) / side;
+        let world = Path::new(
+            path.commands
+                .iter()
+                .map(|cmd| {
+                    let placed =
+                        cmd.map_points(|(x, y)| ((x - min.0) * scale, (y - min.1) * scale));
+                    audit!("tiles", *cmd, placed);
+                    placed
+                })
+                .collect(),
+        );
+
+        let mut tiles: Vec<_> = world
+            .simplify_with(
+                1.0,
+                &SimplifyOptions {
+                    flatten_curves: true,
+                },
+            )
+            .chunk_by_grid(EXTENT)
+            .into_iter()
+            .collect();
+        tiles.sort_by_key(|&(key, _)| key);
+
+        tiles.into_iter().map(move |((x, y), mut path)| {
+            path.translate(-(x as Float) * EXTENT, -(y as Float) * EXTENT);
+            Tile { zoom, x, y, path }
+        })
+    })
+}
@@ -0,0 +1,2 @@
+// the tracing and audit macros of scavenger-core, checking this crate's features where they expand
+pub(crate) use scavenger_core::{audit, trace_count, trace_span};
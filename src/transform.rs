@@ -0,0 +1,14 @@
+pub use scavenger_core::transform::Transform;
+
+use crate::{path::Path, viewbox::calculate_bb};
+
+impl Path {
+    // every command mapped through `transform`, see `Command::transform`
+    pub fn transform(&mut self, transform: &Transform) {
+        for cmd in self.commands_mut().iter_mut() {
+            *cmd = cmd.transform(transform);
+        }
+
+        self.bb = calculate_bb(self.commands.iter());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{path::Path, viewbox::calculate_bb, Float};
+
+// css absolute length units, a px is 1/96 of an inch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Px,
+    Pt,
+    Pc,
+    In,
+    Cm,
+    Mm,
+    Q,
+}
+
+impl Unit {
+    // the unit from its css suffix, `px`, `pt`, `mm`, ...
+    pub fn parse(suffix: &str) -> Option<Unit> {
+        Some(match suffix.to_ascii_lowercase().as_str() {
+            "px" => Unit::Px,
+            "pt" => Unit::Pt,
+            "pc" => Unit::Pc,
+            "in" => Unit::In,
+            "cm" => Unit::Cm,
+            "mm" => Unit::Mm,
+            "q" => Unit::Q,
+            _ => return None,
+        })
+    }
+
+    pub fn per_inch(self) -> Float {
+        match self {
+            Unit::Px => 96.0,
+            Unit::Pt => 72.0,
+            Unit::Pc => 6.0,
+            Unit::In => 1.0,
+            Unit::Cm => 2.54,
+            Unit::Mm => 25.4,
+            Unit::Q => 101.6,
+        }
+    }
+
+    // `value` in this unit expressed in `to`
+    pub fn convert(self, value: Float, to: Unit) -> Float {
+        value * to.per_inch() / self.per_inch()
+    }
+}
+
+// a number with an optional unit suffix such as `210mm` or `12.5pt`, bare numbers are px
+pub fn parse_length(s: &str) -> Option<(Float, Unit)> {
+    let s = s.trim();
+    let split = s.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len();
+
+    let value = s[..split].trim_end().parse().ok()?;
+    let unit = match &s[split..] {
+        "" => Unit::Px,
+        suffix => Unit::parse(suffix)?,
+    };
+
+    Some((value, unit))
+}
+
+impl Path {
+    // scale about the origin from one resolution to another, e.g. 96dpi svg user units to 72dpi
+    // pdf points or to device pixels at 300dpi
+    pub fn scale_units(&mut self, from_dpi: Float, to_dpi: Float) {
+        let factor = to_dpi / from_dpi;
+
+        for cmd in self.commands_mut().iter_mut() {
+            *cmd = cmd.scale(factor, factor);
+        }
+
+        self.bb = calculate_bb(self.commands.iter());
+    }
+
+    // `scale_units` between two physical units
+    pub fn convert_units(&mut self, from: Unit, to: Unit) {
+        self.scale_units(from.per_inch(), to.per_inch());
+    }
+}
@@ -1,16 +1,16 @@
-use crate::{path::Path, Command};
+use crate::{path::Path, segment::Segment, Command, Float};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ViewBox {
-    pub min_x: f32,
-    pub min_y: f32,
+    pub min_x: Float,
+    pub min_y: Float,
 
-    pub width: f32,
-    pub height: f32,
+    pub width: Float,
+    pub height: Float,
 }
 
 impl ViewBox {
-    pub fn new(min_x: f32, min_y: f32, width: f32, height: f32) -> Self {
+    pub fn new(min_x: Float, min_y: Float, width: Float, height: Float) -> Self {
         Self {
             min_x,
             min_y,
@@ -20,17 +20,17 @@ impl ViewBox {
     }
 
     #[inline(always)]
-    fn scale_x(&self, x: f32, w: f32) -> f32 {
+    fn scale_x(&self, x: Float, w: Float) -> Float {
         (x - self.min_x) * w / self.width
     }
 
     #[inline(always)]
-    fn scale_y(&self, y: f32, h: f32) -> f32 {
+    fn scale_y(&self, y: Float, h: Float) -> Float {
         (y - self.min_y) * h / self.height
     }
 
     #[inline(always)]
-    pub(crate) fn scale_cmd(&self, cmd: &Command, w: f32, h: f32) -> Command {
+    pub(crate) fn scale_cmd(&self, cmd: &Command, w: Float, h: Float) -> Command {
         match cmd {
             Command::MoveTo { x, y } => Command::MoveTo {
                 x: self.scale_x(*x, w),
@@ -85,6 +85,9 @@ impl ViewBox {
                     y: self.scale_y(*y, h),
                 }
             }
+            arc @ Command::EllipticalArc { .. } => arc
+                .translate(-self.min_x, -self.min_y)
+                .scale(w / self.width, h / self.height),
         }
     }
 
@@ -102,7 +105,7 @@ impl ViewBox {
 
 pub struct ScaledIterator<'a> {
     view_box: &'a ViewBox,
-    dims: (f32, f32),
+    dims: (Float, Float),
     iter: std::slice::Iter<'a, Command>,
 }
 
@@ -110,7 +113,7 @@ impl<'a> ScaledIterator<'a> {
     pub(crate) fn new(
         view_box: &'a ViewBox,
         iter: std::slice::Iter<'a, Command>,
-        dims: (f32, f32),
+        dims: (Float, Float),
     ) -> Self {
         Self {
             view_box,
@@ -131,11 +134,14 @@ impl<'a> Iterator for ScaledIterator<'a> {
 }
 
 // calcualte the bounding box of a path, returns (width, height) (x and y are always 0)
-pub fn calculate_bb<'a, I: IntoIterator<Item = &'a Command>>(path: I) -> (f32, f32) {
-    let mut min_x = 0.0f32;
-    let mut min_y = 0.0f32;
-    let mut max_x = 0.0f32;
-    let mut max_y = 0.0f32;
+pub fn calculate_bb<'a, I: IntoIterator<Item = &'a Command>>(path: I) -> (Float, Float) {
+    let mut min_x: Float = 0.0;
+    let mut min_y: Float = 0.0;
+    let mut max_x: Float = 0.0;
+    let mut max_y: Float = 0.0;
+    // arcs bulge past their end points, so they are measured from where the pen is
+    let mut start = (0.0, 0.0);
+    let mut pen = start;
 
     for cmd in path {
         match cmd {
@@ -221,7 +227,27 @@ pub fn calculate_bb<'a, I: IntoIterator<Item = &'a Command>>(path: I) -> (f32, f
                 max_x = max_x.max(*x);
                 max_y = max_y.max(*y);
             }
+            Command::EllipticalArc { .. } => {
+                // each cubic lies inside its control points
+                for seg in Segment::pieces(pen, cmd) {
+                    for (x, y) in seg.to_command().points() {
+                        min_x = min_x.min(x);
+                        min_y = min_y.min(y);
+                        max_x = max_x.max(x);
+                        max_y = max_y.max(y);
+                    }
+                }
+            }
         }
+
+        pen = match cmd {
+            Command::MoveTo { x, y } => {
+                start = (*x, *y);
+                start
+            }
+            Command::ClosePath => start,
+            cmd => cmd.points().last().unwrap_or(pen),
+        };
     }
 
     (max_x - min_x, max_y - min_y)
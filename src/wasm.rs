@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{path::Path, writer::write_svg, FlattenMethod, Float, ParsePath, Parser};
+
+// a parsed path kept on the rust side, js calls its methods
+#[wasm_bindgen(js_name = Path)]
+pub struct WasmPath {
+    path: Path,
+}
+
+// `d` parsed, throws with the parse error when it does not parse
+#[wasm_bindgen(js_name = parsePath)]
+pub fn parse_path(d: &str) -> Result<WasmPath, JsError> {
+    let path = Parser::new(d)
+        .parse_path()
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(WasmPath { path })
+}
+
+#[wasm_bindgen(js_class = Path)]
+impl WasmPath {
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> Float {
+        self.path.bb().0
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> Float {
+        self.path.bb().1
+    }
+
+    pub fn scale(&mut self, scale: Float) {
+        self.path.scale(scale);
+    }
+
+    pub fn translate(&mut self, x: Float, y: Float) {
+        self.path.translate(x, y);
+    }
+
+    pub fn fit(&mut self, width: Float, height: Float) {
+        self.path.fit(width, height);
+    }
+
+    #[wasm_bindgen(js_name = toD)]
+    pub fn to_d(&self) -> String {
+        self.path.to_d()
+    }
+
+    #[wasm_bindgen(js_name = toSvg)]
+    pub fn to_svg(&self, width: Float, height: Float) -> String {
+        write_svg(&[self.path.fork()], width, height)
+    }
+
+    // the path as polylines within `tolerance`, ready for a canvas `moveTo`/`lineTo` loop
+    pub fn flatten(&self, tolerance: Float) -> Polylines {
+        let mut out = Polylines {
+            points: Vec::new(),
+            lengths: Vec::new(),
+            closed: Vec::new(),
+        };
+
+        for polyline in self.path.flatten_with(tolerance, FlattenMethod::Adaptive) {
+            out.points
+                .extend(polyline.points.iter().flat_map(|&(x, y)| [x, y]));
+            out.lengths.push(polyline.points.len() as u32);
+            out.closed.push(u8::from(polyline.closed));
+        }
+        out
+    }
+}
+
+// flattened polylines as flat arrays, which js receives as typed arrays
+#[wasm_bindgen]
+pub struct Polylines {
+    points: Vec<Float>,
+    lengths: Vec<u32>,
+    closed: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Polylines {
+    // x, y of every point, polyline after polyline. a Float32Array, Float64Array with `f64`
+    #[wasm_bindgen(getter)]
+    pub fn points(&self) -> Vec<Float> {
+        self.points.clone()
+    }
+
+    // how many points of `points` each polyline takes, a Uint32Array
+    #[wasm_bindgen(getter)]
+    pub fn lengths(&self) -> Vec<u32> {
+        self.lengths.clone()
+    }
+
+    // 1 for each polyline that closes, 0 otherwise, a Uint8Array
+    #[wasm_bindgen(getter)]
+    pub fn closed(&self) -> Vec<u8> {
+        self.closed.clone()
+    }
+}
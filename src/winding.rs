@@ -0,0 +1,179 @@
+use crate::{
+    path::Path,
+    roots::{coefficients, unit_roots},
+    scanline::FillRule,
+    segment::Segment,
+    to_f64, Float,
+};
+
+// where a segment turns around vertically, splitting it into pieces that only go up or down
+fn y_monotone(seg: &Segment) -> Vec<Float> {
+    let [a, b, c, _] = coefficients(seg, |p| p.1);
+    let mut ts = vec![0.0];
+    let mut turns = unit_roots([0.0, 3.0 * a, 2.0 * b, c]);
+    turns.sort_by(Float::total_cmp);
+    ts.extend(turns.into_iter().filter(|&t| t > 0.0 && t < 1.0));
+    ts.push(1.0);
+    ts
+}
+
+fn bisect(seg: &Segment, y: Float, mut t0: Float, mut t1: Float) -> Float {
+    let rising = seg.eval(t1).1 > seg.eval(t0).1;
+    for _ in 0..32 {
+        let mid = (t0 + t1) / 2.0;
+        if (seg.eval(mid).1 <= y) == rising {
+            t0 = mid;
+        } else {
+            t1 = mid;
+        }
+    }
+    (t0 + t1) / 2.0
+}
+
+// winding contribution of `seg` for a ray from (x, y) towards +x
+fn crossings(seg: &Segment, (x, y): (Float, Float)) -> i32 {
+    let mut winding = 0;
+    let [a, b, c, d] = coefficients(seg, |p| p.1);
+    let roots = unit_roots([a, b, c, d - to_f64(y)]);
+
+    for piece in y_monotone(seg).windows(2) {
+        let (t0, t1) = (piece[0], piece[1]);
+        let (y0, y1) = (seg.eval(t0).1, seg.eval(t1).1);
+
+        // half open in y so a ray through a shared endpoint counts once
+        if (y0 <= y) == (y1 <= y) {
+            continue;
+        }
+
+        // the root inside this piece. near a vertical tangent the solver can lose it to rounding,
+        // the piece is monotone so bisection finds it then
+        const SLACK: Float = 1e-4;
+        let t = roots
+            .iter()
+            .copied()
+            .find(|&t| t >= t0 - SLACK && t <= t1 + SLACK)
+            .map_or_else(|| bisect(seg, y, t0, t1), |t| t.clamp(t0, t1));
+
+        if seg.eval(t).0 > x {
+            winding += if y1 > y0 { 1 } else { -1 };
+        }
+    }
+
+    winding
+}
+
+impl Path {
+    // how many times the outline winds around (x, y), solved against the curves themselves rather
+    // than a flattening. open subpaths count as closed by a straight line, as when filling.
+    // contours with a positive signed area (clockwise on screen in svg's y-down space) add one
+    pub fn winding_at(&self, x: Float, y: Float) -> i32 {
+        let mut winding = 0;
+
+        for sp in self.subpaths() {
+            let mut end = sp.start;
+            for seg in sp.segments() {
+                winding += crossings(&seg, (x, y));
+                end = seg.to();
+            }
+
+            if end != sp.start {
+                winding += crossings(&Segment::Line(end, sp.start), (x, y));
+            }
+        }
+
+        winding
+    }
+
+    // whether (x, y) is inside the filled shape under `fill_rule`, for hit testing
+    pub fn contains(&self, x: Float, y: Float, fill_rule: FillRule) -> bool {
+        fill_rule.is_inside(self.winding_at(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{consts::TAU, parse_path_str, Command, Parser};
+
+    fn path(d: &str) -> Path {
+        Path::new(Parser::new(d).preserve_arcs(true).parse().unwrap())
+    }
+
+    #[test]
+    fn fill_rules_differ_inside_a_donut() {
+        let outer = "M0 0 L10 0 L10 10 L0 10 Z";
+        // the hole wound the same way as the outline, then the other way
+        let same = path(&format!("{outer} M3 3 L7 3 L7 7 L3 7 Z"));
+        let reversed = path(&format!("{outer} M3 3 L3 7 L7 7 L7 3 Z"));
+
+        assert_eq!(same.winding_at(5.0, 5.0), 2);
+        assert!(same.contains(5.0, 5.0, FillRule::NonZero));
+        assert!(!same.contains(5.0, 5.0, FillRule::EvenOdd));
+
+        assert_eq!(reversed.winding_at(5.0, 5.0), 0);
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            assert!(!reversed.contains(5.0, 5.0, rule));
+            // the ring itself, and outside
+            assert!(same.contains(1.0, 5.0, rule) && reversed.contains(1.0, 5.0, rule));
+            assert!(!same.contains(11.0, 5.0, rule) && !reversed.contains(11.0, 5.0, rule));
+        }
+        // clockwise on screen adds one, counterclockwise takes one away
+        assert_eq!(path("M0 0 L0 10 L10 10 L10 0 Z").winding_at(5.0, 5.0), -1);
+    }
+
+    #[test]
+    fn rays_through_vertices_count_once() {
+        // through the tip of a triangle, where two edges meet going on the same way
+        let tip = path("M0 0 L10 5 L0 10 Z");
+        assert_eq!(tip.winding_at(2.0, 5.0), 1);
+        assert_eq!(tip.winding_at(-2.0, 5.0), 0);
+
+        // through the bottom of a notch, where both edges turn back up
+        let notch = path("M0 0 L5 5 L10 0 L10 10 L0 10 Z");
+        for x in [-1.0, 1.0, 6.0] {
+            assert_eq!(notch.winding_at(x, 5.0), i32::from(x > 0.0), "{x}");
+        }
+        // the notch is not filled above its bottom
+        assert_eq!(notch.winding_at(5.0, 4.0), 0);
+
+        // along a horizontal edge, and through a row of shared vertices
+        let step = path("M0 0 L10 0 L10 5 L5 5 L5 10 L0 10 Z");
+        assert_eq!(step.winding_at(2.0, 5.0), 1);
+        assert_eq!(step.winding_at(-2.0, 5.0), 0);
+        let stacked = path("M0 0 L4 0 L4 5 L0 5 Z M0 5 L4 5 L4 10 L0 10 Z");
+        assert_eq!(stacked.winding_at(2.0, 5.0), 1);
+        assert_eq!(stacked.winding_at(-1.0, 5.0), 0);
+    }
+
+    #[test]
+    fn arcs_are_solved_as_arcs() {
+        // a circle of radius 5 around (5, 0) from two preserved arcs
+        let circle = path("M0 0 A 5 5 0 1 0 10 0 A 5 5 0 1 0 0 0 Z");
+        assert!(matches!(
+            circle.commands()[1],
+            Command::EllipticalArc { .. }
+        ));
+
+        let winding = circle.winding_at(5.0, 0.0);
+        assert_eq!(winding.abs(), 1);
+        for angle in 0..24 {
+            let (sin, cos) = (angle as Float * TAU / 24.0).sin_cos();
+            for (r, inside) in [(4.95, true), (5.05, false)] {
+                let (x, y) = (5.0 + cos * r, sin * r);
+                let expected = if inside { winding } else { 0 };
+                assert_eq!(circle.winding_at(x, y), expected, "{angle} at {r}");
+            }
+        }
+    }
+
+    #[test]
+    fn open_subpaths_count_as_closed() {
+        let open = Path::new(parse_path_str("M0 0 L10 0 L10 10").unwrap());
+        let closed = Path::new(parse_path_str("M0 0 L10 0 L10 10 Z").unwrap());
+        for (x, y) in [(8.0, 2.0), (2.0, 8.0), (5.0, 4.0), (5.0, 6.0), (-1.0, 0.5)] {
+            assert_eq!(open.winding_at(x, y), closed.winding_at(x, y), "({x}, {y})");
+        }
+        assert_eq!(open.winding_at(8.0, 2.0), 1);
+        assert_eq!(open.winding_at(2.0, 8.0), 0);
+    }
+}
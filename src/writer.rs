@@ -0,0 +1,763 @@
+use std::{fmt, io, ops::Range};
+
+use crate::{path::Path, to_f64, transform::Transform, Command, Float};
+
+// how far a stored smooth control point may be from the implied one and still be written as S/T
+const SMOOTH_EPSILON: Float = 1e-4;
+
+// more decimals than this only write out f32 noise
+const MAX_DECIMALS: u8 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberFormat {
+    // the shortest text that reads back as the same f32
+    #[default]
+    Shortest,
+    // the shortest text that reads back as the same f32 or, when that needs more, the value
+    // rounded to this many decimals. leading zeros are dropped (`.5`, `-.25`) for smaller output
+    Decimals(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WriteOptions {
+    pub numbers: NumberFormat,
+}
+
+fn close(a: (Float, Float), b: (Float, Float)) -> bool {
+    (a.0 - b.0).abs() <= SMOOTH_EPSILON && (a.1 - b.1).abs() <= SMOOTH_EPSILON
+}
+
+// absolute path data for `commands`. smooth curves are only written as S/T when a reader will infer
+// the same control point, otherwise they fall back to C/Q
+pub fn write_d(commands: &[Command]) -> String {
+    write_d_with(commands, &WriteOptions::default())
+}
+
+pub fn write_d_with(commands: &[Command], options: &WriteOptions) -> String {
+    let mut out = String::with_capacity(commands.len() * 16);
+    write_to_with(&mut out, commands, options);
+    out
+}
+
+// `write_d` appended to `out`, so one buffer can be cleared and reused for many paths
+pub fn write_to(out: &mut String, commands: &[Command]) {
+    write_to_with(out, commands, &WriteOptions::default());
+}
+
+pub fn write_to_with(out: &mut String, commands: &[Command], options: &WriteOptions) {
+    let _ = write_commands(out, commands, options);
+}
+
+// `write_d` straight into `out` without building the string first. every number is its own small
+// write, so hand in something buffered (a `BufWriter`, a `Vec<u8>`)
+pub fn write_io(out: &mut impl io::Write, commands: &[Command]) -> io::Result<()> {
+    write_io_with(out, commands, &WriteOptions::default())
+}
+
+pub fn write_io_with(
+    out: &mut impl io::Write,
+    commands: &[Command],
+    options: &WriteOptions,
+) -> io::Result<()> {
+    let mut adapter = Adapter {
+        inner: out,
+        error: None,
+    };
+    match write_commands(&mut adapter, commands, options) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::other("formatting failed"))),
+    }
+}
+
+// `fmt::Write` over an `io::Write`, keeping the io error that `fmt::Error` cannot carry
+struct Adapter<'a, W> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for Adapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+// `v` rounded to `decimals` and written with integer formatting, which is quicker than formatting
+// the float and lets trailing and leading zeros be dropped on the way
+fn write_decimal(out: &mut impl fmt::Write, v: Float, decimals: u8) -> fmt::Result {
+    let exact = to_f64(v);
+    let mut decimals = u32::from(decimals.min(MAX_DECIMALS));
+
+    // fewest decimals that still read back as `v`
+    let mut places = 0;
+    let mut scale = 1.0;
+    while places < decimals && ((exact * scale).round() / scale) as Float != v {
+        places += 1;
+        scale *= 10.0;
+    }
+    decimals = places;
+
+    let scaled = (exact * scale).round();
+    // past this u64 digits no longer hold every value, and f32 has nothing after the point
+    if !scaled.is_finite() || scaled.abs() >= 1e18 {
+        return write!(out, "{v}");
+    }
+
+    let mut digits = scaled.abs() as u64;
+    while decimals > 0 && digits.is_multiple_of(10) {
+        digits /= 10;
+        decimals -= 1;
+    }
+
+    // no `-0`
+    if digits == 0 {
+        return out.write_char('0');
+    }
+    if scaled < 0.0 {
+        out.write_char('-')?;
+    }
+
+    let pow = 10u64.pow(decimals);
+    let (int, frac) = (digits / pow, digits % pow);
+    if int > 0 || decimals == 0 {
+        write!(out, "{int}")?;
+    }
+    if decimals > 0 {
+        write!(out, ".{frac:0width$}", width = decimals as usize)?;
+    }
+    Ok(())
+}
+
+// `values` separated by spaces
+fn write_numbers(
+    out: &mut impl fmt::Write,
+    values: &[Float],
+    numbers: NumberFormat,
+) -> fmt::Result {
+    for (i, &v) in values.iter().enumerate() {
+        if i > 0 {
+            out.write_char(' ')?;
+        }
+        match numbers {
+            NumberFormat::Shortest => write!(out, "{v}")?,
+            NumberFormat::Decimals(decimals) => write_decimal(out, v, decimals)?,
+        }
+    }
+    Ok(())
+}
+
+// a command letter followed by its numbers
+fn write_command(
+    out: &mut impl fmt::Write,
+    letter: char,
+    values: &[Float],
+    numbers: NumberFormat,
+) -> fmt::Result {
+    out.write_char(letter)?;
+    write_numbers(out, values, numbers)
+}
+
+// what the commands written so far leave behind for the next one
+#[derive(Default)]
+struct Pen {
+    start: (Float, Float),
+    current: (Float, Float),
+    // reflection point for a following S (cubic) or T (quadratic)
+    cubic_ctrl: Option<(Float, Float)>,
+    quad_ctrl: Option<(Float, Float)>,
+}
+
+// a `fmt::Write` that drops everything, to move a `Pen` past commands without writing them
+struct Discard;
+
+impl fmt::Write for Discard {
+    fn write_str(&mut self, _: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+fn write_commands(
+    out: &mut impl fmt::Write,
+    commands: &[Command],
+    options: &WriteOptions,
+) -> fmt::Result {
+    write_from(out, commands, &mut Pen::default(), options)
+}
+
+fn write_from(
+    out: &mut impl fmt::Write,
+    commands: &[Command],
+    pen: &mut Pen,
+    options: &WriteOptions,
+) -> fmt::Result {
+    let numbers = options.numbers;
+    let Pen {
+        start,
+        current,
+        cubic_ctrl,
+        quad_ctrl,
+    } = pen;
+
+    for (i, cmd) in commands.iter().enumerate() {
+        if i > 0 {
+            out.write_char(' ')?;
+        }
+
+        let (next_cubic, next_quad) = match *cmd {
+            Command::MoveTo { x, y } => {
+                write_command(out, 'M', &[x, y], numbers)?;
+                *start = (x, y);
+                *current = (x, y);
+                (None, None)
+            }
+            Command::LineTo { x, y } => {
+                write_command(out, 'L', &[x, y], numbers)?;
+                *current = (x, y);
+                (None, None)
+            }
+            Command::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                write_command(out, 'C', &[x1, y1, x2, y2, x, y], numbers)?;
+                *current = (x, y);
+                (Some((x2, y2)), None)
+            }
+            Command::SmoothCurveTo {
+                cx,
+                cy,
+                x2,
+                y2,
+                x,
+                y,
+            } => {
+                let implied = cubic_ctrl.map_or(*current, |(px, py): (Float, Float)| {
+                    (2.0 * current.0 - px, 2.0 * current.1 - py)
+                });
+
+                if close(implied, (cx, cy)) {
+                    write_command(out, 'S', &[x2, y2, x, y], numbers)?;
+                } else {
+                    write_command(out, 'C', &[cx, cy, x2, y2, x, y], numbers)?;
+                }
+                *current = (x, y);
+                (Some((x2, y2)), None)
+            }
+            Command::QuadraticBezierCurveTo { x1, y1, x, y } => {
+                write_command(out, 'Q', &[x1, y1, x, y], numbers)?;
+                *current = (x, y);
+                (None, Some((x1, y1)))
+            }
+            Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
+                let implied = quad_ctrl.map_or(*current, |(px, py): (Float, Float)| {
+                    (2.0 * current.0 - px, 2.0 * current.1 - py)
+                });
+
+                if close(implied, (cx, cy)) {
+                    write_command(out, 'T', &[x, y], numbers)?;
+                } else {
+                    write_command(out, 'Q', &[cx, cy, x, y], numbers)?;
+                }
+                *current = (x, y);
+                (None, Some((cx, cy)))
+            }
+            Command::EllipticalArc {
+                rx,
+                ry,
+                x_axis_rotation,
+                large_arc,
+                sweep,
+                x,
+                y,
+            } => {
+                let (large_arc, sweep) = (large_arc as u8, sweep as u8);
+                write_command(out, 'A', &[rx, ry, x_axis_rotation], numbers)?;
+                write!(out, " {large_arc} {sweep} ")?;
+                write_numbers(out, &[x, y], numbers)?;
+                *current = (x, y);
+                (None, None)
+            }
+            Command::ClosePath => {
+                out.write_char('Z')?;
+                *current = *start;
+                (None, None)
+            }
+        };
+
+        *cubic_ctrl = next_cubic;
+        *quad_ctrl = next_quad;
+    }
+
+    Ok(())
+}
+
+// the commands of one command letter or one repeat of its arguments, and where they were written
+struct Group {
+    commands: Range<usize>,
+    text: Range<usize>,
+    // starts with its command letter rather than leaning on the one before
+    letter: bool,
+    // written as `S`, `s`, `T` or `t`, so it reflects a control point of the text before it
+    smooth: bool,
+}
+
+impl Group {
+    // reads the same whatever letter the text before it was written with
+    fn stands_alone(&self) -> bool {
+        self.letter && !self.smooth
+    }
+}
+
+fn groups(source: &str, spans: &[Range<usize>]) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+
+    for (i, span) in spans.iter().enumerate() {
+        match groups.last_mut() {
+            Some(group) if group.text == *span => group.commands.end = i + 1,
+            _ => {
+                let first = source[span.start..].chars().next();
+                groups.push(Group {
+                    commands: i..i + 1,
+                    text: span.clone(),
+                    letter: first.is_some_and(|c| c.is_ascii_alphabetic()),
+                    smooth: matches!(first, Some('S' | 's' | 'T' | 't')),
+                })
+            }
+        }
+    }
+
+    groups
+}
+
+// the subpath start before each command, and after the last
+fn subpath_starts(commands: &[Command]) -> Vec<(Float, Float)> {
+    let mut start = (0.0, 0.0);
+    let mut starts = Vec::with_capacity(commands.len() + 1);
+    starts.push(start);
+
+    for cmd in commands {
+        if let Command::MoveTo { x, y } = *cmd {
+            start = (x, y);
+        }
+        starts.push(start);
+    }
+
+    starts
+}
+
+// `source` with only what changed between `original` and `edited` written again, so untouched
+// commands keep their formatting and a diff of the file shows just the edit. `original` and
+// `spans` come from `Parser::parse_with_spans` on `source`. commands are matched from both ends,
+// and one by one in between when the count did not change. text that would read differently after
+// an edit (relative or smooth commands, bare repeated arguments) is written again too
+pub fn patch(
+    source: &str,
+    original: &[Command],
+    spans: &[Range<usize>],
+    edited: &[Command],
+) -> String {
+    let groups = groups(source, spans);
+    if groups.is_empty() || spans.len() != original.len() {
+        return write_d(edited);
+    }
+
+    let (original_starts, edited_starts) = (subpath_starts(original), subpath_starts(edited));
+    // command `i` of `original` is written to mean command `j` of `edited`
+    let same = |i: usize, j: usize| {
+        let after_same = match (i, j) {
+            (0, 0) => true,
+            (0, _) | (_, 0) => false,
+            _ => original[i - 1] == edited[j - 1] && original_starts[i] == edited_starts[j],
+        };
+        after_same && original.get(i) == edited.get(j)
+    };
+    let keep = |group: &Group, j: usize| group.commands.clone().zip(j..).all(|(i, j)| same(i, j));
+    // where command `i` of `original` would be in `edited` if only the middle changed
+    let shift = |i: usize| (i + edited.len()).checked_sub(original.len());
+
+    // groups kept at the front, then at the back
+    let mut front = 0;
+    while front < groups.len() && keep(&groups[front], groups[front].commands.start) {
+        front += 1;
+    }
+    let fixed = groups[..front].last().map_or(0, |g| g.commands.end);
+
+    let mut back = groups.len();
+    while back > front {
+        match shift(groups[back - 1].commands.start) {
+            Some(j) if j >= fixed && keep(&groups[back - 1], j) => back -= 1,
+            _ => break,
+        }
+    }
+
+    let original_end = groups
+        .get(back)
+        .map_or(original.len(), |g| g.commands.start);
+    if front == back && original_end + edited.len() == original.len() + fixed {
+        return source.to_string();
+    }
+    // bare arguments after new text would repeat the wrong letter, and smooth commands reflect
+    // whatever the new text ends with
+    while back < groups.len() && !groups[back].stands_alone() {
+        back += 1;
+    }
+    let original_end = groups
+        .get(back)
+        .map_or(original.len(), |g| g.commands.start);
+    let edited_end = original_end + edited.len() - original.len();
+
+    // (text replaced, commands of `edited` written in its place)
+    let mut runs: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    if edited.len() == original.len() {
+        let mut last_kept = true;
+        for group in &groups[front..back] {
+            last_kept = (last_kept || group.stands_alone()) && keep(group, group.commands.start);
+            if last_kept {
+                continue;
+            }
+            match runs.last_mut() {
+                Some((text, commands)) if commands.end == group.commands.start => {
+                    text.end = group.text.end;
+                    commands.end = group.commands.end;
+                }
+                _ => runs.push((group.text.clone(), group.commands.clone())),
+            }
+        }
+    } else if front < back {
+        let text = groups[front].text.start..groups[back - 1].text.end;
+        runs.push((text, fixed..edited_end));
+    } else {
+        // nothing to replace, the new commands go between two groups
+        let at = groups[..front].last().map_or(0, |g| g.text.end);
+        runs.push((at..at, fixed..edited_end));
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut cursor = 0;
+    // the pen is moved past the commands between runs without writing them
+    let mut pen = Pen::default();
+    let mut written = 0;
+    let options = WriteOptions::default();
+
+    for (text, commands) in runs {
+        let before = &source[cursor..text.start];
+        // a deleted run takes the space before it along
+        out.push_str(if commands.is_empty() {
+            before.trim_end()
+        } else {
+            before
+        });
+
+        let _ = write_from(
+            &mut Discard,
+            &edited[written..commands.start],
+            &mut pen,
+            &options,
+        );
+        let inserted = text.is_empty() && !commands.is_empty();
+        if inserted && text.start > 0 {
+            out.push(' ');
+        }
+        let _ = write_from(&mut out, &edited[commands.clone()], &mut pen, &options);
+        if inserted && text.start == 0 {
+            out.push(' ');
+        }
+
+        written = commands.end;
+        cursor = text.end;
+    }
+
+    out.push_str(&source[cursor..]);
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SvgOptions {
+    pub numbers: NumberFormat,
+    // scale the drawing so the viewBox is 0 0 100 100 and every coordinate reads as a percentage
+    // of its width or height, for inline snippets sized by css. path data has no % unit, so this
+    // is done with numbers and preserveAspectRatio="none" keeps the document drawing the same.
+    // coordinates come out exact when 100 / width and 100 / height are powers of two (a width of
+    // 25, 50, 100, 200, ...), otherwise each is rounded once to the nearest f32, a relative error
+    // of at most 2^-24, before `numbers` rounds it for writing
+    pub percent: bool,
+}
+
+// a standalone svg document with one <path> element per path
+pub fn write_svg(paths: &[Path], width: Float, height: Float) -> String {
+    write_svg_with(paths, width, height, &SvgOptions::default())
+}
+
+pub fn write_svg_with(paths: &[Path], width: Float, height: Float, options: &SvgOptions) -> String {
+    let write = WriteOptions {
+        numbers: options.numbers,
+    };
+    // a viewBox without area has nothing to take percentages of
+    let scale = (options.percent && width > 0.0 && height > 0.0)
+        .then(|| Transform::scale(100.0 / width, 100.0 / height))
+        .filter(|t| t.a.is_finite() && t.d.is_finite());
+
+    let mut out = match scale {
+        Some(_) => format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 100 100\" preserveAspectRatio=\"none\">\n"
+        ),
+        None => format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        ),
+    };
+
+    for path in paths {
+        out.push_str("  <path d=\"");
+        match &scale {
+            Some(scale) => {
+                let mut scaled = path.fork();
+                scaled.transform(scale);
+                write_to_with(&mut out, &scaled.commands, &write);
+            }
+            None => write_to_with(&mut out, &path.commands, &write),
+        }
+        out.push_str("\"/>\n");
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+impl Path {
+    pub fn to_d(&self) -> String {
+        write_d(&self.commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, Rng, SplitMix64};
+
+    // what a command draws, smooth commands with their control point written out. the writer
+    // spells out a smooth command that no longer lines up with the one before it
+    fn drawn(commands: &[Command]) -> Vec<Command> {
+        commands
+            .iter()
+            .map(|cmd| match *cmd {
+                Command::SmoothCurveTo {
+                    cx,
+                    cy,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => Command::CurveTo {
+                    x1: cx,
+                    y1: cy,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                },
+                Command::SmoothQuadraticBezierCurveTo { cx, cy, x, y } => {
+                    Command::QuadraticBezierCurveTo {
+                        x1: cx,
+                        y1: cy,
+                        x,
+                        y,
+                    }
+                }
+                cmd => cmd,
+            })
+            .collect()
+    }
+
+    fn assert_patches(source: &str, edit: impl FnOnce(&mut Vec<Command>)) {
+        let (original, spans) = Parser::new(source).parse_with_spans().unwrap();
+        let mut edited = original.clone();
+        edit(&mut edited);
+
+        let patched = patch(source, &original, &spans, &edited);
+        let read = Parser::new(&patched)
+            .parse()
+            .unwrap_or_else(|e| panic!("{source:?} patched to {patched:?}: {e}"));
+        assert_eq!(
+            drawn(&read),
+            drawn(&edited),
+            "{source:?} patched to {patched:?}"
+        );
+    }
+
+    fn number(rng: &mut SplitMix64) -> i32 {
+        rng.below(19) as i32 - 9
+    }
+
+    // every command letter but arcs (read as curves only close to their ends), relative and
+    // absolute, some with their arguments repeated
+    fn random_d(rng: &mut SplitMix64) -> String {
+        let mut d = format!("M {} {}", number(rng), number(rng));
+        for _ in 0..1 + rng.below(8) {
+            let letter = b"MmLlHhVvCcSsQqTtZz"[rng.below(18)] as char;
+            let count = match letter.to_ascii_uppercase() {
+                'Z' => 0,
+                'H' | 'V' => 1,
+                'C' => 6,
+                'S' | 'Q' => 4,
+                _ => 2,
+            };
+            d.push(' ');
+            d.push(letter);
+            for _ in 0..count * (1 + rng.below(2)) {
+                d += &format!(" {}", number(rng));
+            }
+        }
+        d
+    }
+
+    #[test]
+    fn patch_keeps_untouched_text() {
+        let source = "M10 10 l 5 5 h2 v2 C 1 2 3 4 5 6";
+        let (original, spans) = Parser::new(source).parse_with_spans().unwrap();
+        assert_eq!(patch(source, &original, &spans, &original), source);
+
+        let mut edited = original.clone();
+        edited[4] = Command::LineTo { x: 0.0, y: 0.0 };
+        assert_eq!(
+            patch(source, &original, &spans, &edited),
+            "M10 10 l 5 5 h2 v2 L0 0"
+        );
+    }
+
+    #[test]
+    fn patch_rewrites_smooth_commands_after_an_edit() {
+        let source = "M 1 1 S -4 5 3 2 T -8 3 T -8 -9";
+        let (original, spans) = Parser::new(source).parse_with_spans().unwrap();
+        let mut edited = original.clone();
+        edited[1] = edited[1].translate(2.0, -3.0);
+        // the last T is written again, it reflects whatever the new text before it ends with
+        assert_eq!(
+            patch(source, &original, &spans, &edited),
+            "M 1 1 C3 -2 -2 2 5 -1 Q3 2 -8 3 T-8 -9"
+        );
+
+        assert_patches(source, |edited| {
+            edited[1] = edited[1].translate(2.0, -3.0);
+        });
+        assert_patches("M 0 0 Q 1 1 2 0 T 4 0 T 6 0", |edited| {
+            edited[1] = edited[1].translate(0.0, 1.0);
+        });
+        assert_patches("M 0 0 C 1 1 2 1 3 0 s 2 -1 3 0 4 0 5 0", |edited| {
+            edited[1] = edited[1].translate(1.0, 0.0);
+        });
+    }
+
+    #[test]
+    fn patch_round_trips_single_edits() {
+        let mut rng = SplitMix64::new(0x5ca7);
+        for _ in 0..5000 {
+            let source = random_d(&mut rng);
+            let len = Parser::new(&source).parse().unwrap().len();
+            let at = 1 + rng.below(len);
+            let (dx, dy) = (number(&mut rng) as Float, number(&mut rng) as Float);
+
+            match rng.below(3) {
+                0 if at < len => assert_patches(&source, |edited| {
+                    edited[at] = edited[at].map_points(|(x, y)| (x + dx, y + dy));
+                }),
+                1 if at < len => assert_patches(&source, |edited| {
+                    edited.remove(at);
+                }),
+                _ => assert_patches(&source, |edited| {
+                    edited.insert(at, Command::LineTo { x: dx, y: dy });
+                }),
+            }
+        }
+    }
+
+    fn assert_reads_back(commands: &[Command], written: &str, tolerance: Float) {
+        let read = Parser::new(written)
+            .parse()
+            .unwrap_or_else(|e| panic!("{written:?}: {e}"));
+        let (read, commands) = (drawn(&read), drawn(commands));
+        assert_eq!(read.len(), commands.len(), "{written:?}");
+        for (a, b) in read.iter().zip(&commands) {
+            let bare = |cmd: &Command| cmd.map_points(|_| (0.0, 0.0));
+            assert_eq!(bare(a), bare(b), "{written:?}");
+            assert!(
+                a.points()
+                    .zip(b.points())
+                    .all(|(p, q)| (p.0 - q.0).abs() <= tolerance && (p.1 - q.1).abs() <= tolerance),
+                "{written:?}: {a:?} read back, {b:?} written"
+            );
+        }
+    }
+
+    // some random paths as parsed, and turned so their numbers need every digit
+    fn random_paths(seed: u64) -> impl Iterator<Item = (Vec<Command>, Vec<Command>)> {
+        let mut rng = SplitMix64::new(seed);
+        let turn = Transform::rotate(17.0).then(&Transform::scale(0.37, 1.9));
+        (0..2000).map(move |_| {
+            let commands = Parser::new(&random_d(&mut rng)).parse().unwrap();
+            let turned = commands.iter().map(|cmd| cmd.transform(&turn)).collect();
+            (commands, turned)
+        })
+    }
+
+    #[test]
+    fn write_d_round_trips() {
+        for (commands, turned) in random_paths(0xd0d0) {
+            assert_reads_back(&commands, &write_d(&commands), 0.0);
+            // shortest numbers read back as the same floats, only smooth control points, which
+            // the reader reflects again, may move by rounding
+            assert_reads_back(&turned, &write_d(&turned), SMOOTH_EPSILON);
+        }
+    }
+
+    #[test]
+    fn write_io_matches_write_d() {
+        for (_, turned) in random_paths(0x10) {
+            let mut out = Vec::new();
+            write_io(&mut out, &turned).unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), write_d(&turned));
+
+            let mut out = "kept ".to_string();
+            write_to(&mut out, &turned);
+            assert_eq!(out, format!("kept {}", write_d(&turned)));
+        }
+    }
+
+    // rounded numbers keep the end points within the rounding. the control points of chained
+    // smooth commands drift further, each reflects the rounding before it
+    #[test]
+    fn decimals_round_the_end_points() {
+        let options = WriteOptions {
+            numbers: NumberFormat::Decimals(2),
+        };
+        for (_, turned) in random_paths(0xdec) {
+            let written = write_d_with(&turned, &options);
+            let read = Parser::new(&written).parse().unwrap();
+            assert_eq!(read.len(), turned.len(), "{written:?}");
+            for (a, b) in read.iter().zip(&turned) {
+                if let (Some(p), Some(q)) = (a.points().last(), b.points().last()) {
+                    assert!(
+                        (p.0 - q.0).abs() <= 0.005 + 1e-5 && (p.1 - q.1).abs() <= 0.005 + 1e-5,
+                        "{written:?}: {a:?} read back, {b:?} written"
+                    );
+                }
+            }
+        }
+
+        let commands = [
+            Command::MoveTo { x: 0.5, y: -0.25 },
+            Command::LineTo {
+                x: 1.0 / 3.0,
+                y: 2.0,
+            },
+        ];
+        assert_eq!(write_d_with(&commands, &options), "M.5 -.25 L.33 2");
+    }
+}